@@ -1,17 +1,24 @@
 use crate::{
     context::Selection,
-    field::allocate_padding,
-    gui::{ClassListPanel, InspectorPanel, ToolBarPanel, ToolBarResponse},
-    process::Process,
+    field::{allocate_padding, Overlay},
+    gui::{ClassListPanel, InspectorPanel, RecoveryWindow, ToolBarPanel, ToolBarResponse},
+    history::Edit,
+    process::{Process, ProcessBackend},
+    recovery::{self, AUTOSAVE_INTERVAL},
     state::StateRef,
 };
-use eframe::{egui::Context, epaint::Color32, App, Frame};
-use std::{sync::Once, time::Duration};
+use eframe::{egui::Context, epaint::Color32, glow, App, Frame};
+use std::{
+    sync::Once,
+    time::{Duration, Instant},
+};
 
 pub struct YClassApp {
     class_list: ClassListPanel,
     inspector: InspectorPanel,
     tool_bar: ToolBarPanel,
+    recovery_window: RecoveryWindow,
+    last_autosave: Instant,
     state: StateRef,
 }
 
@@ -21,9 +28,30 @@ impl YClassApp {
             class_list: ClassListPanel::new(state),
             inspector: InspectorPanel::new(state),
             tool_bar: ToolBarPanel::new(state),
+            recovery_window: RecoveryWindow::new(state, recovery::start()),
+            last_autosave: Instant::now(),
             state,
         }
     }
+
+    fn autosave(&mut self) {
+        if self.recovery_window.pending() || self.last_autosave.elapsed() < AUTOSAVE_INTERVAL {
+            return;
+        }
+        self.last_autosave = Instant::now();
+
+        let state = &mut *self.state.borrow_mut();
+        if state.dummy {
+            return;
+        }
+
+        let data = state.project_data();
+        if let Err(e) = recovery::autosave(&data, state.last_opened_project.as_deref()) {
+            state
+                .toasts
+                .error(format!("Failed to autosave the project. {e}"));
+        }
+    }
 }
 
 impl App for YClassApp {
@@ -36,7 +64,34 @@ impl App for YClassApp {
             ctx.set_pixels_per_point(dpi);
         });
 
-        match self.tool_bar.show(ctx) {
+        let mut response = self.tool_bar.show(ctx);
+        if let Some(
+            ToolBarResponse::Add(_)
+            | ToolBarResponse::Remove(_)
+            | ToolBarResponse::Insert(_)
+            | ToolBarResponse::ChangeKind(_)
+            | ToolBarResponse::SetComment(_),
+        ) = response
+        {
+            let state = &mut *self.state.borrow_mut();
+            if let Some(class) = state
+                .selection
+                .map(|s| s.container_id)
+                .or_else(|| state.class_list.selected())
+                .and_then(|cid| state.class_list.by_id(cid))
+            {
+                if let Some(library) = class.library.as_ref() {
+                    let msg = format!(
+                        "Class `{}` is imported from {library} and can't be changed",
+                        class.name
+                    );
+                    state.toasts.error(msg);
+                    response = None;
+                }
+            }
+        }
+
+        match response {
             Some(ToolBarResponse::Add(n)) => {
                 let state = &mut *self.state.borrow_mut();
 
@@ -45,8 +100,14 @@ impl App for YClassApp {
                     .map(|s| s.container_id)
                     .or_else(|| state.class_list.selected())
                 {
-                    let class = state.class_list.by_id_mut(cid).unwrap();
-                    class.fields.extend(allocate_padding(n));
+                    let class = state.class_list.by_id(cid).unwrap();
+                    let edit = Edit::Splice {
+                        class: cid,
+                        pos: class.fields.len(),
+                        len: 0,
+                        fields: allocate_padding(n),
+                    };
+                    state.history.apply(&mut state.class_list, edit);
 
                     state.dummy = false;
                 }
@@ -60,28 +121,26 @@ impl App for YClassApp {
                     ..
                 }) = state.selection
                 {
-                    let class = state.class_list.by_id_mut(container_id).unwrap();
-                    let mut discrd_sel = false;
+                    let class = state.class_list.by_id(container_id).unwrap();
                     let pos = class
                         .fields
                         .iter()
-                        .position(|f| {
-                            discrd_sel |= state
-                                .selection
-                                .map(|s| s.field_id == f.id())
-                                .unwrap_or(false);
-
-                            f.id() == field_id
-                        })
+                        .position(|f| f.id() == field_id)
                         .unwrap();
-                    if discrd_sel {
-                        state.selection = None;
-                    }
+
+                    // Selected field is always the first one removed.
+                    state.selection = None;
 
                     let from = pos.min(class.fields.len());
                     let to = (pos + n).min(class.fields.len());
 
-                    class.fields.drain(from..to);
+                    let edit = Edit::Splice {
+                        class: container_id,
+                        pos: from,
+                        len: to - from,
+                        fields: vec![],
+                    };
+                    state.history.apply(&mut state.class_list, edit);
                     state.dummy = false;
                 }
             }
@@ -94,17 +153,20 @@ impl App for YClassApp {
                     ..
                 }) = state.selection
                 {
-                    let class = state.class_list.by_id_mut(container_id).unwrap();
+                    let class = state.class_list.by_id(container_id).unwrap();
                     let pos = class
                         .fields
                         .iter()
                         .position(|f| f.id() == field_id)
                         .unwrap();
-                    let mut padding = allocate_padding(n);
 
-                    while let Some(field) = padding.pop() {
-                        class.fields.insert(pos, field);
-                    }
+                    let edit = Edit::Splice {
+                        class: container_id,
+                        pos,
+                        len: 0,
+                        fields: allocate_padding(n),
+                    };
+                    state.history.apply(&mut state.class_list, edit);
 
                     state.dummy = false;
                 }
@@ -118,54 +180,149 @@ impl App for YClassApp {
                     ..
                 }) = state.selection
                 {
-                    let class = state.class_list.by_id_mut(container_id).unwrap();
+                    let class = state.class_list.by_id(container_id).unwrap();
                     let pos = class
                         .fields
                         .iter()
                         .position(|f| f.id() == field_id)
                         .unwrap();
 
-                    let (old_size, old_name) = (class.fields[pos].size(), class.fields[pos].name());
-                    if old_size > new.size() {
-                        let mut padding = allocate_padding(old_size - new.size());
-                        class.fields[pos] = new.into_field(old_name);
-                        while let Some(pad) = padding.pop() {
-                            class.fields.insert(pos + 1, pad);
-                        }
+                    let old_name = class.fields[pos].name();
+                    let (mut steal_size, mut steal_len) = (0, 0);
+                    while steal_size < new.size() && pos + steal_len < class.fields.len() {
+                        steal_size += class.fields[pos + steal_len].size();
+                        steal_len += 1;
+                    }
 
-                        state.selection.as_mut().unwrap().field_id = class.fields[pos].id();
+                    if steal_size < new.size() {
+                        state.toasts.error("Not enough space for a new field");
                     } else {
-                        let (mut steal_size, mut steal_len) = (0, 0);
-                        while steal_size < new.size() {
-                            if pos >= class.fields.len() {
-                                break;
-                            }
+                        let field = new.into_field(old_name);
+                        let new_id = field.id();
 
-                            let index = pos + steal_len;
-                            if index >= class.fields.len() {
-                                break;
-                            }
+                        let mut fields = vec![field];
+                        fields.extend(allocate_padding(steal_size - new.size()));
+                        let edit = Edit::Splice {
+                            class: container_id,
+                            pos,
+                            len: steal_len,
+                            fields,
+                        };
+                        state.history.apply(&mut state.class_list, edit);
+                        state.selection.as_mut().unwrap().field_id = new_id;
 
-                            steal_size += class.fields[index].size();
-                            steal_len += 1;
+                        // Comment stays with the field unless it lost its name, the old
+                        // one is kept in case the change is undone.
+                        let class = state.class_list.by_id_mut(container_id).unwrap();
+                        if let Some(comment) = class.field_comments.get(&field_id).cloned() {
+                            if class.fields[pos].name().is_some() {
+                                class.field_comments.insert(new_id, comment);
+                            }
                         }
 
-                        if steal_size < new.size() {
-                            state.toasts.error("Not enough space for a new field");
-                        } else {
-                            class.fields.drain(pos..pos + steal_len);
-                            let mut padding = allocate_padding(steal_size - new.size());
-                            class.fields.insert(pos, new.into_field(old_name));
+                        state.dummy = false;
+                    }
+                }
+            }
+            Some(ToolBarResponse::Undo) => {
+                let state = &mut *self.state.borrow_mut();
 
-                            while let Some(pad) = padding.pop() {
-                                class.fields.insert(pos + 1, pad);
-                            }
+                // Selected field might not exist anymore.
+                if state.history.undo(&mut state.class_list) {
+                    state.selection = None;
+                    state.dummy = false;
+                }
+            }
+            Some(ToolBarResponse::Redo) => {
+                let state = &mut *self.state.borrow_mut();
+
+                if state.history.redo(&mut state.class_list) {
+                    state.selection = None;
+                    state.dummy = false;
+                }
+            }
+            Some(ToolBarResponse::ToggleFreeze) => {
+                let state = &mut *self.state.borrow_mut();
 
-                            state.selection.as_mut().unwrap().field_id = class.fields[pos].id();
+                if let Some(Selection {
+                    container_id,
+                    field_id,
+                    address,
+                }) = state.selection
+                {
+                    let class = state.class_list.by_id(container_id).unwrap();
+                    let process = state.process.read();
+
+                    match (
+                        class.fields.iter().find(|f| f.id() == field_id),
+                        process.as_ref(),
+                    ) {
+                        (Some(field), Some(process)) => {
+                            let name = format!(
+                                "{}.{}",
+                                class.name,
+                                field.name().unwrap_or_else(|| format!("{address:X}"))
+                            );
+                            process.toggle_freeze(address, field.size(), name);
                         }
+                        (None, _) => _ = state.toasts.error("Only fields of a class can be frozen"),
+                        (_, None) => _ = state.toasts.error("Attach to a process first"),
                     }
+                } else {
+                    state.toasts.error("Select a field to freeze first");
+                }
+            }
+            Some(ToolBarResponse::AddOverlay(kind)) => {
+                let state = &mut *self.state.borrow_mut();
 
-                    state.dummy = false;
+                if let Some(Selection {
+                    container_id,
+                    field_id,
+                    ..
+                }) = state.selection
+                {
+                    let class = state.class_list.by_id_mut(container_id).unwrap();
+                    class.overlays.push(Overlay {
+                        field_id,
+                        field: kind.into_field(None),
+                    });
+                } else {
+                    state.toasts.error("Select a field to overlay first");
+                }
+            }
+            Some(ToolBarResponse::ClearOverlays) => {
+                let state = &mut *self.state.borrow_mut();
+                state
+                    .class_list
+                    .classes_mut()
+                    .iter_mut()
+                    .for_each(|c| c.overlays.clear());
+            }
+            Some(ToolBarResponse::SetComment(comment)) => {
+                let state = &mut *self.state.borrow_mut();
+
+                if let Some(Selection {
+                    container_id,
+                    field_id,
+                    ..
+                }) = state.selection
+                {
+                    let class = state.class_list.by_id_mut(container_id).unwrap();
+                    if !class
+                        .fields
+                        .iter()
+                        .any(|f| f.id() == field_id && f.name().is_some())
+                    {
+                        state.toasts.error("Only named fields can have a comment");
+                    } else if comment.is_empty() {
+                        class.field_comments.remove(&field_id);
+                        state.dummy = false;
+                    } else {
+                        class.field_comments.insert(field_id, comment);
+                        state.dummy = false;
+                    }
+                } else {
+                    state.toasts.error("Select a field to comment first");
                 }
             }
             Some(ToolBarResponse::ProcessDetach) => {
@@ -193,11 +350,11 @@ impl App for YClassApp {
                     match Process::attach(pid, &state.config) {
                         Ok(proc) => {
                             frame.set_window_title(&format!("YClass - Attached to {pid}"));
-                            if let Process::Internal((op, _)) = &proc {
+                            if let ProcessBackend::Internal((op, ..)) = &proc.backend {
                                 match op.name() {
                                     Ok(name) => {
                                         state.config.last_attached_process_name = Some(name);
-                                        state.config.save();
+                                        state.save_config();
                                     }
                                     Err(e) => {
                                         _ = state
@@ -207,6 +364,7 @@ impl App for YClassApp {
                                 }
                             }
 
+                            proc.set_read_only(state.read_only());
                             *process = Some(proc);
                         }
                         Err(e) => {
@@ -233,6 +391,16 @@ impl App for YClassApp {
 
         self.state.borrow_mut().toasts.show(ctx);
         ctx.set_style(saved);
+
+        self.recovery_window.show(ctx);
+        self.autosave();
+    }
+
+    fn on_exit(&mut self, _gl: Option<&glow::Context>) {
+        // Recovered project is kept until the user decides what to do with it.
+        if !self.recovery_window.pending() {
+            recovery::finish();
+        }
     }
 }
 
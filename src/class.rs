@@ -1,5 +1,8 @@
-use crate::field::{Field, FieldKind, HexField};
-use std::{cell::Cell, iter::repeat_with};
+use crate::{
+    field::{allocate_padding, Field, FieldId, FieldKind, HexField, Overlay},
+    history::Edit,
+};
+use std::{cell::Cell, collections::HashMap, iter::repeat_with};
 
 pub type ClassId = usize;
 
@@ -8,17 +11,31 @@ pub struct Class {
     pub name: String,
     pub address: Cell<usize>,
     pub fields: Vec<Box<dyn Field>>,
+    pub overlays: Vec<Overlay>,
+    /// Free-form note about the class, empty if there is none.
+    pub comment: String,
+    /// Notes about named fields of the class.
+    pub field_comments: HashMap<FieldId, String>,
+    base: Option<ClassId>,
+    /// File name of the included project the class is imported from, imported classes can't be changed.
+    pub library: Option<String>,
 }
 
 impl Class {
-    fn new(id: usize, name: String) -> Self {
+    /// Creates a class with unknown fields that isn't in any list yet.
+    pub fn new(id: usize, name: String) -> Self {
         Self {
             id,
             name,
             fields: repeat_with(|| Box::new(HexField::<8>::new()) as Box<dyn Field>)
                 .take(10)
                 .collect(),
+            overlays: vec![],
             address: 0.into(),
+            comment: String::new(),
+            field_comments: HashMap::new(),
+            base: None,
+            library: None,
         }
     }
 
@@ -27,13 +44,60 @@ impl Class {
             id,
             name,
             fields: vec![],
+            overlays: vec![],
             address: 0.into(),
+            comment: String::new(),
+            field_comments: HashMap::new(),
+            base: None,
+            library: None,
         }
     }
 
     pub fn id(&self) -> usize {
         self.id
     }
+
+    /// Class whose fields come before the fields of this class.
+    pub fn base(&self) -> Option<ClassId> {
+        self.base
+    }
+
+    /// Returns size in bytes, without the base class.
+    pub fn size(&self) -> usize {
+        self.fields.iter().map(|f| f.size()).sum()
+    }
+
+    /// Returns the edit that places `field` at `offset` replacing the fields it overlaps,
+    /// the rest of their space is filled with padding. `None` if the class is too small.
+    pub fn put_field(&self, offset: usize, field: Box<dyn Field>) -> Option<Edit> {
+        let end = offset + field.size();
+        if end > self.size() {
+            return None;
+        }
+
+        let (mut from, mut start) = (0, 0);
+        while start + self.fields[from].size() <= offset {
+            start += self.fields[from].size();
+            from += 1;
+        }
+
+        let (mut to, mut stop) = (from, start);
+        while stop < end {
+            stop += self.fields[to].size();
+            to += 1;
+        }
+
+        let mut replacement = allocate_padding(offset - start);
+        replacement.push(field);
+        replacement.extend(allocate_padding(stop - end));
+
+        Some(Edit::Splice {
+            class: self.id,
+            pos: from,
+            len: to - from,
+            fields: replacement,
+        })
+    }
 }
 
 pub struct ClassList {
@@ -64,19 +128,28 @@ impl ClassList {
         &mut self.classes[..]
     }
 
-    pub fn remove_empty(&mut self) {
-        self.classes.retain(|c| {
-            !c.fields.iter().all(|f| {
-                f.kind() == FieldKind::Unk8
-                    || f.kind() == FieldKind::Unk16
-                    || f.kind() == FieldKind::Unk32
-                    || f.kind() == FieldKind::Unk64
+    /// Returns ids of classes that only have unknown fields.
+    pub fn empty_classes(&self) -> Vec<ClassId> {
+        self.classes
+            .iter()
+            .filter(|c| {
+                c.library.is_none()
+                    && c.fields.iter().all(|f| {
+                        f.kind() == FieldKind::Unk8
+                            || f.kind() == FieldKind::Unk16
+                            || f.kind() == FieldKind::Unk32
+                            || f.kind() == FieldKind::Unk64
+                    })
             })
-        });
+            .map(|c| c.id)
+            .collect()
     }
 
-    pub fn add_empty_class(&mut self, name: String) -> usize {
-        let id = fastrand::usize(..);
+    /// Adds a class without fields, `id` is kept unless another class already has it.
+    pub fn add_empty_class(&mut self, name: String, id: Option<ClassId>) -> usize {
+        let id = id
+            .filter(|&id| self.by_id(id).is_none())
+            .unwrap_or_else(|| fastrand::usize(..));
         self.classes.push(Class::empty(id, name));
         id
     }
@@ -88,8 +161,20 @@ impl ClassList {
         id
     }
 
-    pub fn add_class_with_id(&mut self, name: String, id: usize) {
-        self.classes.push(Class::new(id, name));
+    pub fn insert_class(&mut self, index: usize, class: Class) {
+        self.classes.insert(index.min(self.classes.len()), class);
+    }
+
+    /// Removes the class, classes that inherited it no longer have a base class.
+    /// Returns the index the class was at.
+    pub fn remove_class(&mut self, id: ClassId) -> Option<(usize, Class)> {
+        let index = self.classes.iter().position(|c| c.id == id)?;
+        self.classes
+            .iter_mut()
+            .filter(|c| c.base == Some(id))
+            .for_each(|c| c.base = None);
+
+        Some((index, self.classes.remove(index)))
     }
 
     pub fn selected(&self) -> Option<usize> {
@@ -112,8 +197,68 @@ impl ClassList {
         self.classes.iter().find(|c| c.name == name)
     }
 
-    pub fn delete_by_id(&mut self, id: usize) {
-        self.classes.retain(|c| c.id != id);
+    /// Appends a number to `name` if there is a class with the same name already.
+    pub fn unique_name(&self, name: String) -> String {
+        if self.by_name(&name).is_none() {
+            return name;
+        }
+
+        (2..)
+            .map(|i| format!("{name}{i}"))
+            .find(|n| self.by_name(n).is_none())
+            .unwrap()
+    }
+
+    /// Returns `false` if inheriting `base` would make the class inherit itself.
+    pub fn can_inherit(&self, id: ClassId, base: Option<ClassId>) -> bool {
+        let mut next = base;
+        while let Some(cid) = next {
+            if cid == id {
+                return false;
+            }
+            next = self.by_id(cid).and_then(|c| c.base);
+        }
+
+        true
+    }
+
+    /// Sets base class of the class, returns `false` if that would make it inherit itself.
+    pub fn set_base(&mut self, id: ClassId, base: Option<ClassId>) -> bool {
+        if !self.can_inherit(id, base) {
+            return false;
+        }
+
+        if let Some(class) = self.by_id_mut(id) {
+            class.base = base;
+        }
+        true
+    }
+
+    /// Returns size in bytes including base classes.
+    pub fn class_size(&self, id: ClassId) -> usize {
+        self.by_id(id).map_or(0, |c| {
+            c.size() + c.base.map_or(0, |base| self.class_size(base))
+        })
+    }
+
+    /// Returns classes ordered so that base classes come before classes inheriting them.
+    pub fn ordered_by_base(&self) -> Vec<&Class> {
+        fn visit<'a>(list: &'a ClassList, class: &'a Class, out: &mut Vec<&'a Class>) {
+            if out.iter().any(|c| c.id == class.id) {
+                return;
+            }
+
+            if let Some(base) = class.base.and_then(|id| list.by_id(id)) {
+                visit(list, base, out);
+            }
+            out.push(class);
+        }
+
+        let mut out = vec![];
+        for class in self.classes.iter() {
+            visit(self, class, &mut out);
+        }
+        out
     }
 
     pub fn selected_class(&self) -> Option<&Class> {
@@ -8,6 +8,10 @@ pub struct YClassConfig {
     pub plugin_path: Option<PathBuf>,
     pub recent_projects: Option<HashSet<PathBuf>>,
     pub dpi: Option<f32>,
+    /// Milliseconds between rewrites of frozen values.
+    pub freeze_interval: Option<u64>,
+    /// Forbids writing to memory of any process.
+    pub read_only: Option<bool>,
 }
 
 impl YClassConfig {
@@ -17,28 +21,30 @@ impl YClassConfig {
             .unwrap_or_else(|| "./config.toml".into())
     }
 
+    /// Loads the config, falls back to the default one if it can't be read.
     pub fn load_or_default() -> Self {
         let path = Self::config_path();
 
         if fs::metadata(&path).is_ok() {
-            toml::from_str(&fs::read_to_string(&path).unwrap()).unwrap_or_default()
+            fs::read_to_string(&path)
+                .ok()
+                .and_then(|text| toml::from_str(&text).ok())
+                .unwrap_or_default()
         } else {
             let value = Self::default();
-            if let Some(p) = path.parent() {
-                fs::create_dir_all(p).unwrap();
-            }
-
-            fs::write(&path, toml::to_string(&value).unwrap().as_bytes()).unwrap();
+            // Not being able to write the default config isn't worth failing over.
+            _ = value.save();
             value
         }
     }
 
-    pub fn save(&self) {
+    pub fn save(&self) -> eyre::Result<()> {
         let path = Self::config_path();
         if let Some(p) = path.parent() {
-            fs::create_dir_all(p).unwrap();
+            fs::create_dir_all(p)?;
         }
 
-        fs::write(&path, toml::to_string(self).unwrap().as_bytes()).unwrap();
+        fs::write(&path, toml::to_string(self)?.as_bytes())?;
+        Ok(())
     }
 }
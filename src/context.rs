@@ -1,7 +1,9 @@
 use crate::{
     class::{ClassId, ClassList},
     field::FieldId,
+    history::Edit,
     process::Process,
+    session::NodeStates,
 };
 use eframe::egui::Id;
 use egui_notify::Toasts;
@@ -21,6 +23,9 @@ pub struct InspectionContext<'a> {
     pub process: &'a Process,
     pub class_list: &'a ClassList,
     pub toasts: &'a mut Toasts,
+    pub nodes: &'a NodeStates,
+    /// Edits made while drawing, applied through the history afterwards.
+    pub edits: &'a mut Vec<Edit>,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -43,6 +48,13 @@ impl InspectionContext<'_> {
         }
     }
 
+    /// Whether the inspected class is imported from another project and can't be changed.
+    pub fn imported(&self) -> bool {
+        self.class_list
+            .by_id(self.current_container)
+            .is_some_and(|c| c.library.is_some())
+    }
+
     pub fn is_selected(&self, field_id: FieldId) -> bool {
         self.selection
             .as_ref()
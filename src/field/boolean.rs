@@ -1,6 +1,6 @@
 use super::{
-    display_field_name, display_field_prelude, display_field_value, next_id, CodegenData, Field,
-    FieldId, FieldKind, FieldResponse, NamedState,
+    display_field_comment, display_field_name, display_field_prelude, display_field_value, next_id,
+    CodegenData, Field, FieldId, FieldKind, FieldResponse, NamedState,
 };
 use crate::{context::InspectionContext, generator::Generator};
 use eframe::{
@@ -32,6 +32,10 @@ impl Field for BoolField {
         Some(self.state.name.borrow().clone())
     }
 
+    fn rename(&self, id: FieldId, name: &str) -> Option<String> {
+        self.state.rename(self.id, id, name)
+    }
+
     fn size(&self) -> usize {
         1
     }
@@ -80,6 +84,8 @@ impl Field for BoolField {
                     _ => false,
                 },
             );
+
+            display_field_comment(self, ui, ctx);
         });
 
         ctx.offset += 1;
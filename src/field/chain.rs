@@ -0,0 +1,240 @@
+use super::{
+    container::read_usize, create_text_format, display_field_comment, display_field_name,
+    display_field_prelude, display_field_value, next_id, CodegenData, Field, FieldId, FieldKind,
+    FieldResponse, NamedState,
+};
+use crate::{
+    address::parse_address,
+    context::InspectionContext,
+    generator::Generator,
+    gui::{bytes_to_value, parse_kind_to_value},
+    process::Process,
+    FID_M,
+};
+use eframe::{
+    egui::{popup_below_widget, Label, RichText, Sense, TextEdit, Ui},
+    epaint::{text::LayoutJob, Color32},
+};
+use std::cell::{Cell, RefCell};
+
+/// Pointer that is followed through a chain of offsets to a value,
+/// i.e. `[[this+0x10]+0x28]+0x4` where `0x10` is the offset of the field.
+pub struct ChainField {
+    id: FieldId,
+    state: NamedState,
+    offsets: RefCell<Vec<usize>>,
+    value: Cell<FieldKind>,
+    // Text of the offsets edit box in the popup.
+    offsets_buf: RefCell<String>,
+}
+
+impl ChainField {
+    pub fn new(name: String) -> Self {
+        Self::with_chain(name, vec![0], FieldKind::I32)
+    }
+
+    pub fn with_chain(name: String, offsets: Vec<usize>, value: FieldKind) -> Self {
+        Self {
+            id: next_id(),
+            state: NamedState::new(name),
+            offsets_buf: format_offsets(&offsets).into(),
+            offsets: offsets.into(),
+            value: value.into(),
+        }
+    }
+
+    /// Returns address of every hop(i.e. the address that is dereferenced)
+    /// and the address of the final value, `None` if some hop can't be read.
+    fn resolve(&self, process: &Process, address: usize) -> (Vec<usize>, Option<usize>) {
+        let offsets = self.offsets.borrow();
+        let mut hops = vec![address];
+        let mut current = read_usize(process, address);
+
+        for (i, offset) in offsets.iter().enumerate() {
+            if !process.can_read(current) {
+                return (hops, None);
+            }
+
+            let next = current.wrapping_add(*offset);
+            if i + 1 == offsets.len() {
+                return (hops, Some(next));
+            }
+
+            hops.push(next);
+            current = read_usize(process, next);
+        }
+
+        (hops, Some(current))
+    }
+
+    fn show_chain(&self, ui: &mut Ui, ctx: &mut InspectionContext, hops: &[usize]) {
+        let mut job = LayoutJob::default();
+        job.append(
+            &chain_expression(&format!("this+{:#X}", ctx.offset), &self.offsets.borrow()),
+            4.,
+            create_text_format(ctx.is_selected(self.id), Color32::LIGHT_GRAY),
+        );
+        job.append(
+            self.value.get().label().unwrap_or_default(),
+            4.,
+            create_text_format(ctx.is_selected(self.id), Color32::WHITE),
+        );
+
+        let popup_id = ctx.current_id.with("_chain");
+        let r = ui
+            .add(Label::new(job).sense(Sense::click()))
+            .on_hover_ui(|ui| {
+                for hop in hops {
+                    ui.label(
+                        RichText::new(format!("[{hop:X}] -> {:X}", read_usize(ctx.process, *hop)))
+                            .font(FID_M),
+                    );
+                }
+            });
+        if r.secondary_clicked() && !ctx.imported() {
+            ui.memory_mut(|m| m.toggle_popup(popup_id));
+        } else if r.clicked() {
+            ctx.select(self.id);
+        }
+
+        popup_below_widget(ui, popup_id, &r, |ui| {
+            ui.set_width(160.);
+
+            let buf = &mut *self.offsets_buf.borrow_mut();
+            ui.horizontal(|ui| {
+                ui.add(TextEdit::singleline(buf).desired_width(100.));
+                ui.label("Offsets");
+            });
+
+            if let Some(offsets) = parse_offsets(buf) {
+                *self.offsets.borrow_mut() = offsets;
+            }
+
+            ui.separator();
+            ui.vertical_centered_justified(|ui| {
+                for (kind, label) in FieldKind::NAMED_VARIANTS {
+                    if ui
+                        .selectable_label(*kind == self.value.get(), *label)
+                        .clicked()
+                    {
+                        self.value.set(*kind);
+                    }
+                }
+            });
+        });
+    }
+}
+
+impl Field for ChainField {
+    fn id(&self) -> FieldId {
+        self.id
+    }
+
+    fn name(&self) -> Option<String> {
+        Some(self.state.name.borrow().clone())
+    }
+
+    fn rename(&self, id: FieldId, name: &str) -> Option<String> {
+        self.state.rename(self.id, id, name)
+    }
+
+    fn size(&self) -> usize {
+        8
+    }
+
+    fn kind(&self) -> FieldKind {
+        FieldKind::Chain
+    }
+
+    fn draw(&self, ui: &mut Ui, ctx: &mut InspectionContext) -> Option<FieldResponse> {
+        let (hops, value_address) = self.resolve(ctx.process, ctx.address + ctx.offset);
+
+        ui.horizontal(|ui| {
+            let mut job = LayoutJob::default();
+            display_field_prelude(ui.ctx(), self, ctx, &mut job);
+            if ui.add(Label::new(job).sense(Sense::click())).clicked() {
+                ctx.select(self.id);
+            }
+
+            display_field_name(self, ui, ctx, &self.state, Color32::BROWN);
+            self.show_chain(ui, ctx, &hops);
+
+            match value_address.filter(|a| ctx.process.can_read(*a)) {
+                Some(address) => {
+                    let mut buf = [0; 16];
+                    ctx.process
+                        .read(address, &mut buf[..self.value.get().size()]);
+                    let value = bytes_to_value(&buf, self.value.get());
+
+                    display_field_value(
+                        self,
+                        ui,
+                        ctx,
+                        &self.state,
+                        Color32::WHITE,
+                        |_| value,
+                        |new| match parse_kind_to_value(self.value.get(), new) {
+                            Ok(new) => {
+                                ctx.process.write(address, &new.to_ne_bytes());
+                                true
+                            }
+                            Err(_) => false,
+                        },
+                    );
+                }
+                None => {
+                    ui.label(
+                        RichText::new("Broken chain")
+                            .color(Color32::RED)
+                            .font(FID_M),
+                    );
+                }
+            }
+
+            display_field_comment(self, ui, ctx);
+        });
+
+        ctx.offset += self.size();
+        None
+    }
+
+    fn codegen(&self, generator: &mut dyn Generator, _: &CodegenData) {
+        generator.add_chain(
+            self.state.name.borrow().as_str(),
+            &self.offsets.borrow(),
+            self.value.get(),
+        );
+    }
+}
+
+/// Formats offsets the same way they are typed, i.e. `28, 4`.
+fn format_offsets(offsets: &[usize]) -> String {
+    offsets
+        .iter()
+        .map(|o| format!("{o:X}"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn parse_offsets(text: &str) -> Option<Vec<usize>> {
+    let offsets = text
+        .split(',')
+        .map(|o| parse_address(o.trim()))
+        .collect::<Option<Vec<_>>>()?;
+
+    (!offsets.is_empty()).then_some(offsets)
+}
+
+/// Human readable chain, `this` is the expression of the first pointer.
+pub fn chain_expression(this: &str, offsets: &[usize]) -> String {
+    let mut expr = format!("{}{this}]", "[".repeat(offsets.len()));
+
+    for (i, offset) in offsets.iter().enumerate() {
+        expr += &format!("+{offset:#X}");
+        if i + 1 != offsets.len() {
+            expr += "]";
+        }
+    }
+
+    expr
+}
@@ -0,0 +1,451 @@
+use super::{
+    create_text_format, display_field_comment, display_field_name, display_field_prelude,
+    draw_base_fields, draw_overlays, next_id, CodegenData, Field, FieldId, FieldKind,
+    FieldResponse, NamedState,
+};
+use crate::{
+    class::ClassId,
+    context::InspectionContext,
+    generator::{ElementType, Generator},
+    history::Edit,
+    process::Process,
+    FID_M,
+};
+use eframe::{
+    egui::{popup_below_widget, Label, RichText, Sense, Ui},
+    epaint::{text::LayoutJob, Color32},
+};
+use std::cell::{Cell, RefCell};
+
+/// Maximum amount of elements displayed for a single container.
+const MAX_ELEMENTS: usize = 256;
+
+/// Kinds that can be selected as an element of a container.
+pub const ELEMENT_KINDS: &[FieldKind] = &[
+    FieldKind::Unk64,
+    FieldKind::Bool,
+    FieldKind::I8,
+    FieldKind::I16,
+    FieldKind::I32,
+    FieldKind::I64,
+    FieldKind::U8,
+    FieldKind::U16,
+    FieldKind::U32,
+    FieldKind::U64,
+    FieldKind::F32,
+    FieldKind::F64,
+    FieldKind::I128,
+    FieldKind::U128,
+    FieldKind::F16,
+    FieldKind::BF16,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ContainerElement {
+    Kind(FieldKind),
+    Class(ClassId),
+}
+
+/// Field that displays contents of vectors and linked lists.
+pub struct ContainerField {
+    id: FieldId,
+    kind: FieldKind,
+    state: NamedState,
+    element: Cell<ContainerElement>,
+    // Fields used to display elements of `ContainerElement::Kind`,
+    // cleared every time the element changes.
+    element_fields: RefCell<Vec<Box<dyn Field>>>,
+}
+
+impl ContainerField {
+    pub fn new(name: String, kind: FieldKind, element: ContainerElement) -> Self {
+        Self {
+            id: next_id(),
+            kind,
+            state: NamedState::new(name),
+            element: element.into(),
+            element_fields: vec![].into(),
+        }
+    }
+
+    fn element_size(&self, ctx: &InspectionContext) -> usize {
+        match self.element.get() {
+            ContainerElement::Kind(kind) => kind.size(),
+            ContainerElement::Class(cid) => ctx.class_list.class_size(cid),
+        }
+    }
+
+    fn element_label(&self, ctx: &InspectionContext) -> String {
+        match self.element.get() {
+            ContainerElement::Kind(kind) => format!("{kind:?}"),
+            ContainerElement::Class(cid) => ctx
+                .class_list
+                .by_id(cid)
+                .map(|c| c.name.clone())
+                .unwrap_or_else(|| "?".into()),
+        }
+    }
+
+    /// Returns the total amount of elements if it is known, and the addresses of the elements to display.
+    fn collect_elements(&self, ctx: &InspectionContext) -> (Option<usize>, Vec<usize>) {
+        let address = ctx.address + ctx.offset;
+        let process = ctx.process;
+        let size = self.element_size(ctx);
+
+        let contiguous = |ptr: usize, len: usize| {
+            if size == 0 || (len != 0 && !process.can_read(ptr)) {
+                return (Some(len), vec![]);
+            }
+
+            (
+                Some(len),
+                (0..len.min(MAX_ELEMENTS)).map(|i| ptr + i * size).collect(),
+            )
+        };
+
+        match self.kind {
+            FieldKind::StdVector => {
+                let (begin, end) = (
+                    read_usize(process, address),
+                    read_usize(process, address + 8),
+                );
+                let len = end.saturating_sub(begin).checked_div(size).unwrap_or(0);
+
+                contiguous(begin, len)
+            }
+            FieldKind::RustVec => {
+                let parts = BufferParts::rust_vec(process, address);
+                contiguous(parts.ptr, parts.len)
+            }
+            // Nodes are `{ next, prev, value }`, the list itself is a sentinel node followed by the size.
+            FieldKind::StdList => walk_list(
+                process,
+                read_usize(process, address),
+                Some(address),
+                |node| node + 16,
+                |node| node,
+                Some(read_usize(process, address + 16)),
+            ),
+            // Nodes are `{ next, prev, value }`, the list is `{ head, tail, len }`.
+            FieldKind::RustList => walk_list(
+                process,
+                read_usize(process, address),
+                None,
+                |node| node + 16,
+                |node| node,
+                Some(read_usize(process, address + 16)),
+            ),
+            // Nodes are the elements themselves, next pointer is the first pointer field of the element class.
+            FieldKind::IntrusiveList => {
+                let ContainerElement::Class(cid) = self.element.get() else {
+                    return (None, vec![]);
+                };
+                let Some(next_offset) = ctx.class_list.by_id(cid).and_then(|c| {
+                    let pos = c.fields.iter().position(|f| f.kind() == FieldKind::Ptr)?;
+                    Some(c.fields[..pos].iter().map(|f| f.size()).sum::<usize>())
+                }) else {
+                    return (None, vec![]);
+                };
+
+                walk_list(
+                    process,
+                    read_usize(process, address),
+                    None,
+                    |node| node,
+                    |node| node + next_offset,
+                    None,
+                )
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    fn show_header(&self, ui: &mut Ui, ctx: &mut InspectionContext, len: Option<usize>) {
+        let mut job = LayoutJob::default();
+        display_field_prelude(ui.ctx(), self, ctx, &mut job);
+
+        if ui.add(Label::new(job).sense(Sense::click())).clicked() {
+            ctx.select(self.id);
+        }
+
+        display_field_name(self, ui, ctx, &self.state, Color32::LIGHT_BLUE);
+
+        let mut job = LayoutJob::default();
+        job.append(
+            &format!(
+                "{}<{}>",
+                container_label(self.kind),
+                self.element_label(ctx)
+            ),
+            4.,
+            create_text_format(ctx.is_selected(self.id), Color32::LIGHT_GRAY),
+        );
+        job.append(
+            &len.map(|l| format!("[{l}]")).unwrap_or_default(),
+            4.,
+            create_text_format(ctx.is_selected(self.id), Color32::WHITE),
+        );
+
+        let popup_id = ctx.current_id.with("_element");
+        let r = ui.add(Label::new(job).sense(Sense::click()));
+        if r.secondary_clicked() && !ctx.imported() {
+            ui.memory_mut(|m| m.toggle_popup(popup_id));
+        } else if r.clicked() {
+            ctx.select(self.id);
+        }
+
+        popup_below_widget(ui, popup_id, &r, |ui| {
+            ui.set_width(80.);
+            ui.vertical_centered_justified(|ui| {
+                let mut new = None;
+                for kind in ELEMENT_KINDS {
+                    if ui.button(format!("{kind:?}")).clicked() {
+                        new = Some(ContainerElement::Kind(*kind));
+                    }
+                }
+
+                ui.separator();
+                for cl in ctx.class_list.classes() {
+                    if ui.button(&cl.name).clicked() {
+                        new = Some(ContainerElement::Class(cl.id()));
+                    }
+                }
+
+                if let Some(new) = new {
+                    self.element.set(new);
+                    self.element_fields.borrow_mut().clear();
+                }
+            });
+        });
+
+        display_field_comment(self, ui, ctx);
+    }
+
+    fn show_body(
+        &self,
+        ui: &mut Ui,
+        ctx: &mut InspectionContext,
+        elements: &[usize],
+    ) -> Option<FieldResponse> {
+        let mut response = None;
+
+        match self.element.get() {
+            ContainerElement::Kind(kind) => {
+                let fields = &mut *self.element_fields.borrow_mut();
+                while fields.len() < elements.len() {
+                    let name = format!("[{}]", fields.len());
+                    fields.push(kind.into_field(Some(name)));
+                }
+
+                // Elements aren't part of any class, so they can't be selected
+                // and their renames don't go through the history.
+                let mut renames = vec![];
+                let mut inner_ctx = InspectionContext {
+                    class_list: ctx.class_list,
+                    parent_id: ctx.current_id,
+                    selection: ctx.selection,
+                    current_container: ctx.current_container,
+                    current_id: ctx.current_id,
+                    process: ctx.process,
+                    toasts: ctx.toasts,
+                    edits: &mut renames,
+                    nodes: ctx.nodes,
+                    level_rng: ctx.level_rng,
+                    offset: 0,
+                    address: 0,
+                };
+
+                for (i, (address, field)) in elements.iter().zip(fields.iter()).enumerate() {
+                    inner_ctx.current_id = ctx.current_id.with(i);
+                    inner_ctx.address = *address;
+                    inner_ctx.offset = 0;
+                    response = response.or(field.draw(ui, &mut inner_ctx));
+                    if let Some(Edit::RenameField { name, .. }) = inner_ctx.edits.pop() {
+                        field.rename(field.id(), &name);
+                    }
+                }
+            }
+            ContainerElement::Class(cid) => {
+                let class = ctx.class_list.by_id(cid)?;
+
+                let mut inner_ctx = InspectionContext {
+                    class_list: ctx.class_list,
+                    parent_id: ctx.current_id,
+                    selection: ctx.selection,
+                    current_container: cid,
+                    current_id: ctx.current_id,
+                    process: ctx.process,
+                    toasts: ctx.toasts,
+                    edits: ctx.edits,
+                    nodes: ctx.nodes,
+                    level_rng: ctx.level_rng,
+                    offset: 0,
+                    address: 0,
+                };
+
+                for (i, address) in elements.iter().enumerate() {
+                    let id = ctx.current_id.with(i);
+                    ctx.nodes
+                        .load(ui.ctx(), id, false)
+                        .show_header(ui, |ui| {
+                            ui.label(
+                                RichText::new(format!("[{i}] {address:012X}"))
+                                    .color(Color32::LIGHT_GRAY)
+                                    .font(FID_M),
+                            );
+                        })
+                        .body(|ui| {
+                            inner_ctx.address = *address;
+                            inner_ctx.offset = 0;
+                            inner_ctx.current_id = id;
+                            response =
+                                response
+                                    .take()
+                                    .or(draw_base_fields(class, ui, &mut inner_ctx));
+
+                            for (j, field) in class.fields.iter().enumerate() {
+                                inner_ctx.current_id = id.with(j);
+                                response = response.take().or(field.draw(ui, &mut inner_ctx)).or(
+                                    draw_overlays(
+                                        &class.overlays,
+                                        field.as_ref(),
+                                        ui,
+                                        &mut inner_ctx,
+                                    ),
+                                );
+                            }
+                        });
+                }
+
+                ctx.selection = inner_ctx.selection;
+            }
+        }
+
+        response
+    }
+}
+
+impl Field for ContainerField {
+    fn id(&self) -> FieldId {
+        self.id
+    }
+
+    fn name(&self) -> Option<String> {
+        Some(self.state.name.borrow().clone())
+    }
+
+    fn rename(&self, id: FieldId, name: &str) -> Option<String> {
+        self.state.rename(self.id, id, name)
+    }
+
+    fn size(&self) -> usize {
+        self.kind.size()
+    }
+
+    fn kind(&self) -> FieldKind {
+        self.kind
+    }
+
+    fn draw(&self, ui: &mut Ui, ctx: &mut InspectionContext) -> Option<FieldResponse> {
+        let (len, elements) = self.collect_elements(ctx);
+
+        let state = ctx.nodes.load(ui.ctx(), ctx.current_id, false);
+        let body = state
+            .show_header(ui, |ui| self.show_header(ui, ctx, len))
+            .body(|ui| self.show_body(ui, ctx, &elements))
+            .2;
+
+        ctx.offset += self.size();
+        body.and_then(|inner| inner.inner)
+    }
+
+    fn codegen(&self, generator: &mut dyn Generator, data: &CodegenData) {
+        let element = match self.element.get() {
+            ContainerElement::Class(cid) => data
+                .classes
+                .iter()
+                .find(|c| c.id() == cid)
+                .map(|c| ElementType::Class(&c.name)),
+            ContainerElement::Kind(kind) => Some(ElementType::Kind(kind)),
+        };
+
+        generator.add_container(
+            self.state.name.borrow().as_str(),
+            self.kind,
+            element.unwrap_or(ElementType::Kind(FieldKind::Unk64)),
+        );
+    }
+}
+
+fn container_label(kind: FieldKind) -> &'static str {
+    match kind {
+        FieldKind::StdVector => "std::vector",
+        FieldKind::RustVec => "Vec",
+        FieldKind::StdList => "std::list",
+        FieldKind::RustList => "LinkedList",
+        FieldKind::IntrusiveList => "Intrusive",
+        _ => unreachable!(),
+    }
+}
+
+/// Follows `next` pointers from `first` until a null pointer, the `end` node or
+/// `first` node again, `value` maps node address to the element address.
+fn walk_list(
+    process: &Process,
+    first: usize,
+    end: Option<usize>,
+    value: impl Fn(usize) -> usize,
+    next: impl Fn(usize) -> usize,
+    len: Option<usize>,
+) -> (Option<usize>, Vec<usize>) {
+    let mut elements = vec![];
+    let mut node = first;
+
+    while node != 0 && Some(node) != end && elements.len() < MAX_ELEMENTS && process.can_read(node)
+    {
+        elements.push(value(node));
+        node = read_usize(process, next(node));
+
+        if node == first {
+            break;
+        }
+    }
+
+    (len, elements)
+}
+
+pub(super) fn read_usize(process: &Process, address: usize) -> usize {
+    let mut buf = [0; 8];
+    process.read(address, &mut buf);
+    usize::from_ne_bytes(buf)
+}
+
+/// Heap buffer of a vector or a string.
+pub(super) struct BufferParts {
+    pub ptr: usize,
+    pub len: usize,
+    pub cap: usize,
+    /// Offset of the length from the start of the vector.
+    pub len_offset: usize,
+}
+
+impl BufferParts {
+    /// Layout of Rust `Vec` is unspecified, so fields are guessed:
+    /// pointer is the readable address and capacity is never less than length.
+    pub fn rust_vec(process: &Process, address: usize) -> Self {
+        let words = [0, 8, 16].map(|o| read_usize(process, address + o));
+
+        // Current compilers lay it out as `{ cap, ptr, len }`, used as a fallback.
+        let ptr_index = words.iter().position(|w| process.can_read(*w)).unwrap_or(1);
+        let mut rest = [0, 1, 2].into_iter().filter(|i| *i != ptr_index);
+        let (a, b) = (rest.next().unwrap(), rest.next().unwrap());
+        let (len_index, cap_index) = if words[a] < words[b] { (a, b) } else { (b, a) };
+
+        Self {
+            ptr: words[ptr_index],
+            len: words[len_index],
+            cap: words[cap_index],
+            len_offset: len_index * 8,
+        }
+    }
+}
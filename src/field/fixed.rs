@@ -0,0 +1,146 @@
+use super::{
+    display_field_comment, display_field_name, display_field_prelude, display_field_value, next_id,
+    CodegenData, Field, FieldId, FieldKind, FieldResponse, NamedState, NumberFormat,
+};
+use crate::{
+    context::InspectionContext,
+    generator::Generator,
+    value::{f64_to_fixed, fixed_to_f64},
+};
+use eframe::{
+    egui::{DragValue, Label, Sense, Ui},
+    epaint::{text::LayoutJob, Color32},
+};
+use std::cell::Cell;
+
+/// Signed fixed-point number, i.e. Q16.16.
+pub struct FixedField<const N: usize> {
+    id: FieldId,
+    state: NamedState,
+    format: Cell<NumberFormat>,
+    /// Number of fractional bits.
+    frac: Cell<u8>,
+}
+
+impl<const N: usize> FixedField<N> {
+    pub fn new(name: String, frac: u8) -> Self {
+        Self {
+            id: next_id(),
+            state: NamedState::new(name),
+            format: Cell::default(),
+            frac: Cell::new(frac.min(N as u8 * 8 - 1)),
+        }
+    }
+
+    pub fn with_format(self, format: NumberFormat) -> Self {
+        self.format.set(format);
+        self
+    }
+
+    fn menu(&self, ui: &mut Ui) {
+        NumberFormat::menu(&self.format, ui, false);
+
+        let mut frac = self.frac.get();
+        ui.horizontal(|ui| {
+            ui.add(DragValue::new(&mut frac).clamp_range(0..=N * 8 - 1));
+            ui.label(format!(
+                "Fractional bits(Q{}.{frac})",
+                N * 8 - frac as usize
+            ));
+        });
+        self.frac.set(frac);
+    }
+}
+
+impl<const N: usize> Field for FixedField<N> {
+    fn id(&self) -> FieldId {
+        self.id
+    }
+
+    fn size(&self) -> usize {
+        N
+    }
+
+    fn name(&self) -> Option<String> {
+        Some(self.state.name.borrow().clone())
+    }
+
+    fn rename(&self, id: FieldId, name: &str) -> Option<String> {
+        self.state.rename(self.id, id, name)
+    }
+
+    fn kind(&self) -> FieldKind {
+        match N {
+            4 => FieldKind::Fixed32(self.frac.get()),
+            8 => FieldKind::Fixed64(self.frac.get()),
+            _ => unreachable!(),
+        }
+    }
+
+    fn draw(&self, ui: &mut Ui, ctx: &mut InspectionContext) -> Option<FieldResponse> {
+        let mut buf = [0; N];
+        let address = ctx.address + ctx.offset;
+        ctx.process.read(address, &mut buf);
+        let format = self.format.get();
+        let raw = match N {
+            4 => format.read_raw(&buf) as u32 as i32 as i64,
+            8 => format.read_raw(&buf) as u64 as i64,
+            _ => unreachable!(),
+        };
+
+        ui.horizontal(|ui| {
+            let mut job = LayoutJob::default();
+            display_field_prelude(ui.ctx(), self, ctx, &mut job);
+
+            let r = ui
+                .add(Label::new(job).sense(Sense::click()))
+                .context_menu(|ui| self.menu(ui));
+            if r.clicked() {
+                ctx.select(self.id);
+            }
+
+            display_field_name(self, ui, ctx, &self.state, Color32::LIGHT_RED);
+            display_field_value(
+                self,
+                ui,
+                ctx,
+                &self.state,
+                Color32::WHITE,
+                |_| fixed_to_f64(raw, self.frac.get()),
+                |new| {
+                    let Some(raw) = new
+                        .parse::<f64>()
+                        .ok()
+                        .and_then(|v| f64_to_fixed(v, self.frac.get()))
+                    else {
+                        return false;
+                    };
+
+                    let raw = match N {
+                        4 => match i32::try_from(raw) {
+                            Ok(raw) => raw as u32 as u128,
+                            Err(_) => return false,
+                        },
+                        _ => raw as u64 as u128,
+                    };
+
+                    ctx.process.write(address, &format.write_raw(raw, N));
+                    true
+                },
+            );
+
+            display_field_comment(self, ui, ctx);
+        });
+
+        ctx.offset += N;
+        None
+    }
+
+    fn codegen(&self, generator: &mut dyn Generator, _: &CodegenData) {
+        generator.add_field(
+            self.state.name.borrow().as_str(),
+            self.kind(),
+            self.format.get().to_metadata().as_deref(),
+        );
+    }
+}
@@ -1,16 +1,24 @@
 use super::{
-    display_field_name, display_field_prelude, display_field_value, next_id, CodegenData, Field,
-    FieldId, FieldKind, FieldResponse, NamedState,
+    display_field_comment, display_field_name, display_field_prelude, display_field_value, next_id,
+    CodegenData, Field, FieldId, FieldKind, FieldResponse, NamedState, NumberFormat,
+};
+use crate::{
+    context::InspectionContext,
+    generator::Generator,
+    value::{bf16_to_f32, f16_to_f32, f32_to_bf16, f32_to_f16},
 };
-use crate::{context::InspectionContext, generator::Generator};
 use eframe::{
     egui::{Label, Sense, Ui},
     epaint::{text::LayoutJob, Color32},
 };
+use std::cell::Cell;
 
 pub struct FloatField<const N: usize> {
     id: FieldId,
     state: NamedState,
+    format: Cell<NumberFormat>,
+    /// Only for 2-byte floats, bfloat16 instead of half precision.
+    bfloat: bool,
 }
 
 impl<const N: usize> FloatField<N> {
@@ -18,8 +26,22 @@ impl<const N: usize> FloatField<N> {
         Self {
             id: next_id(),
             state: NamedState::new(name),
+            format: Cell::default(),
+            bfloat: false,
+        }
+    }
+
+    pub fn bfloat(name: String) -> Self {
+        Self {
+            bfloat: true,
+            ..Self::new(name)
         }
     }
+
+    pub fn with_format(self, format: NumberFormat) -> Self {
+        self.format.set(format);
+        self
+    }
 }
 
 impl<const N: usize> Field for FloatField<N> {
@@ -35,8 +57,14 @@ impl<const N: usize> Field for FloatField<N> {
         Some(self.state.name.borrow().clone())
     }
 
+    fn rename(&self, id: FieldId, name: &str) -> Option<String> {
+        self.state.rename(self.id, id, name)
+    }
+
     fn kind(&self) -> FieldKind {
         match N {
+            2 if self.bfloat => FieldKind::BF16,
+            2 => FieldKind::F16,
             4 => FieldKind::F32,
             8 => FieldKind::F64,
             _ => unreachable!(),
@@ -47,12 +75,17 @@ impl<const N: usize> Field for FloatField<N> {
         let mut buf = [0; N];
         let address = ctx.address + ctx.offset;
         ctx.process.read(address, &mut buf);
+        let format = self.format.get();
+        let raw = format.read_raw(&buf);
 
         ui.horizontal(|ui| {
             let mut job = LayoutJob::default();
             display_field_prelude(ui.ctx(), self, ctx, &mut job);
 
-            if ui.add(Label::new(job).sense(Sense::click())).clicked() {
+            let r = ui
+                .add(Label::new(job).sense(Sense::click()))
+                .context_menu(|ui| NumberFormat::menu(&self.format, ui, false));
+            if r.clicked() {
                 ctx.select(self.id);
             }
 
@@ -64,30 +97,31 @@ impl<const N: usize> Field for FloatField<N> {
                 &self.state,
                 Color32::WHITE,
                 |_| match N {
-                    4 => f32::from_ne_bytes(buf[..].try_into().unwrap()) as f64,
-                    8 => f64::from_ne_bytes(buf[..].try_into().unwrap()),
+                    2 if self.bfloat => bf16_to_f32(raw as u16).to_string(),
+                    2 => f16_to_f32(raw as u16).to_string(),
+                    4 => (f32::from_bits(raw as u32) as f64).to_string(),
+                    8 => f64::from_bits(raw as u64).to_string(),
                     _ => unreachable!(),
                 },
-                |new| match N {
-                    4 => {
-                        if let Ok(val) = new.parse::<f32>() {
-                            ctx.process.write(address, &val.to_ne_bytes());
-                            true
-                        } else {
-                            false
-                        }
-                    }
-                    8 => {
-                        if let Ok(val) = new.parse::<f64>() {
-                            ctx.process.write(address, &val.to_ne_bytes());
-                            true
-                        } else {
-                            false
-                        }
+                |new| {
+                    let raw = match N {
+                        2 if self.bfloat => new.parse::<f32>().map(|v| f32_to_bf16(v) as u128),
+                        2 => new.parse::<f32>().map(|v| f32_to_f16(v) as u128),
+                        4 => new.parse::<f32>().map(|v| v.to_bits() as u128),
+                        8 => new.parse::<f64>().map(|v| v.to_bits() as u128),
+                        _ => return false,
+                    };
+
+                    if let Ok(raw) = raw {
+                        ctx.process.write(address, &format.write_raw(raw, N));
+                        true
+                    } else {
+                        false
                     }
-                    _ => false,
                 },
             );
+
+            display_field_comment(self, ui, ctx);
         });
 
         ctx.offset += N;
@@ -97,12 +131,8 @@ impl<const N: usize> Field for FloatField<N> {
     fn codegen(&self, generator: &mut dyn Generator, _: &CodegenData) {
         generator.add_field(
             self.state.name.borrow().as_str(),
-            match N {
-                4 => FieldKind::F32,
-                8 => FieldKind::F64,
-                _ => unreachable!(),
-            },
-            None,
+            self.kind(),
+            self.format.get().to_metadata().as_deref(),
         );
     }
 }
@@ -0,0 +1,169 @@
+use super::parse_text_value;
+use eframe::egui::Ui;
+use std::cell::Cell;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum ByteOrder {
+    #[default]
+    Little,
+    Big,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Radix {
+    #[default]
+    Dec,
+    Hex,
+    Oct,
+    Bin,
+    Char,
+}
+
+impl Radix {
+    pub const NAMED_VARIANTS: &[(Self, &'static str)] = &[
+        (Self::Dec, "dec"),
+        (Self::Hex, "hex"),
+        (Self::Oct, "oct"),
+        (Self::Bin, "bin"),
+        (Self::Char, "char"),
+    ];
+
+    fn label(&self) -> &'static str {
+        Self::NAMED_VARIANTS
+            .iter()
+            .find_map(|(v, s)| if v == self { Some(*s) } else { None })
+            .unwrap()
+    }
+}
+
+/// How numeric fields are read and displayed.
+/// Stored in the project as the field's metadata, i.e. `"be,hex"`.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct NumberFormat {
+    pub order: ByteOrder,
+    pub radix: Radix,
+}
+
+impl NumberFormat {
+    pub fn from_metadata(metadata: Option<&str>) -> Self {
+        let mut format = Self::default();
+
+        for token in metadata.unwrap_or_default().split(',') {
+            if token == "be" {
+                format.order = ByteOrder::Big;
+            } else if let Some((radix, _)) = Radix::NAMED_VARIANTS.iter().find(|(_, s)| *s == token)
+            {
+                format.radix = *radix;
+            }
+        }
+
+        format
+    }
+
+    pub fn to_metadata(self) -> Option<String> {
+        let mut tokens = vec![];
+        if self.order == ByteOrder::Big {
+            tokens.push("be");
+        }
+        if self.radix != Radix::Dec {
+            tokens.push(self.radix.label());
+        }
+
+        (!tokens.is_empty()).then(|| tokens.join(","))
+    }
+
+    /// Reads first `buf.len()` bytes as an unsigned integer.
+    pub fn read_raw(&self, buf: &[u8]) -> u128 {
+        let mut bytes = [0; 16];
+        match self.order {
+            ByteOrder::Little => {
+                bytes[..buf.len()].copy_from_slice(buf);
+                u128::from_le_bytes(bytes)
+            }
+            ByteOrder::Big => {
+                bytes[16 - buf.len()..].copy_from_slice(buf);
+                u128::from_be_bytes(bytes)
+            }
+        }
+    }
+
+    /// Inverse of [`NumberFormat::read_raw`].
+    pub fn write_raw(&self, raw: u128, size: usize) -> Vec<u8> {
+        match self.order {
+            ByteOrder::Little => raw.to_le_bytes()[..size].to_vec(),
+            ByteOrder::Big => raw.to_be_bytes()[16 - size..].to_vec(),
+        }
+    }
+
+    pub fn format_int(&self, raw: u128, size: usize, signed: bool) -> String {
+        match self.radix {
+            Radix::Dec if signed => sign_extend(raw, size).to_string(),
+            Radix::Dec => raw.to_string(),
+            Radix::Hex => format!("0x{raw:X}"),
+            Radix::Oct => format!("0o{raw:o}"),
+            Radix::Bin => format!("0b{raw:b}"),
+            Radix::Char => match char::from_u32(raw as u32).filter(|_| raw <= u32::MAX as u128) {
+                Some(c) => format!("{c:?}"),
+                None => format!("0x{raw:X}"),
+            },
+        }
+    }
+
+    /// Parses a value in any radix(`0x`, `0o`, `0b` prefixes or a char literal),
+    /// returns `None` if it doesn't fit into `size` bytes.
+    pub fn parse_int(&self, text: &str, size: usize, signed: bool) -> Option<u128> {
+        let text = text.trim();
+        let bits = size as u32 * 8;
+        let mask = u128::MAX >> (128 - bits);
+
+        let prefixed = [("0x", 16), ("0o", 8), ("0b", 2)]
+            .into_iter()
+            .find_map(|(prefix, radix)| Some((text.strip_prefix(prefix)?, radix)));
+
+        let raw = if let Some((digits, radix)) = prefixed {
+            u128::from_str_radix(digits, radix).ok()?
+        } else if let Some(inner) = text.strip_prefix('\'').and_then(|t| t.strip_suffix('\'')) {
+            let inner = parse_text_value(&format!("\"{inner}\""))?;
+            let mut chars = inner.chars();
+            let c = chars.next().filter(|_| chars.next().is_none())?;
+            c as u128
+        } else if signed {
+            let value = text.parse::<i128>().ok()?;
+            let shift = 128 - bits;
+            if (value << shift) >> shift != value {
+                return None;
+            }
+
+            return Some(value as u128 & mask);
+        } else {
+            text.parse::<u128>().ok()?
+        };
+
+        (raw & !mask == 0).then_some(raw)
+    }
+
+    /// Shows byte order options and, if `with_radix` is set, radix options.
+    pub fn menu(state: &Cell<NumberFormat>, ui: &mut Ui, with_radix: bool) {
+        let mut format = state.get();
+
+        ui.horizontal(|ui| {
+            ui.radio_value(&mut format.order, ByteOrder::Little, "Little endian");
+            ui.radio_value(&mut format.order, ByteOrder::Big, "Big endian");
+        });
+
+        if with_radix {
+            ui.horizontal(|ui| {
+                for (radix, label) in Radix::NAMED_VARIANTS {
+                    ui.radio_value(&mut format.radix, *radix, *label);
+                }
+            });
+        }
+
+        state.set(format);
+    }
+}
+
+fn sign_extend(raw: u128, size: usize) -> i128 {
+    let shift = 128 - size as u32 * 8;
+    ((raw << shift) as i128) >> shift
+}
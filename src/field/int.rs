@@ -1,17 +1,19 @@
 use super::{
-    display_field_name, display_field_prelude, display_field_value, next_id, CodegenData, Field,
-    FieldId, FieldKind, FieldResponse, NamedState,
+    display_field_comment, display_field_name, display_field_prelude, display_field_value, next_id,
+    CodegenData, Field, FieldId, FieldKind, FieldResponse, NamedState, NumberFormat,
 };
 use crate::{context::InspectionContext, generator::Generator, process::Process};
 use eframe::{
     egui::{Label, Sense, Ui},
     epaint::{text::LayoutJob, Color32},
 };
+use std::cell::Cell;
 
 pub struct IntField<const N: usize> {
     id: FieldId,
     signed: bool,
     state: NamedState,
+    format: Cell<NumberFormat>,
 }
 
 impl<const N: usize> IntField<N> {
@@ -20,6 +22,7 @@ impl<const N: usize> IntField<N> {
             id: next_id(),
             signed: true,
             state: NamedState::new(name),
+            format: Cell::default(),
         }
     }
 
@@ -28,31 +31,22 @@ impl<const N: usize> IntField<N> {
             id: next_id(),
             signed: false,
             state: NamedState::new(name),
+            format: Cell::default(),
         }
     }
 
-    fn write_value(&self, new: &str, address: usize, proc: &Process) -> bool {
-        macro_rules! do_arm {
-            ($buf:ident, $addr:ident, $proc:ident, $new:ident, $type:ty) => {
-                if let Ok(val) = $new.parse::<$type>() {
-                    $proc.write($addr, &val.to_ne_bytes());
-                    true
-                } else {
-                    false
-                }
-            };
-        }
+    pub fn with_format(self, format: NumberFormat) -> Self {
+        self.format.set(format);
+        self
+    }
 
-        match N {
-            1 if self.signed => do_arm!(buf, address, proc, new, i8),
-            1 if !self.signed => do_arm!(buf, address, proc, new, u8),
-            2 if self.signed => do_arm!(buf, address, proc, new, i16),
-            2 if !self.signed => do_arm!(buf, address, proc, new, u16),
-            4 if self.signed => do_arm!(buf, address, proc, new, i32),
-            4 if !self.signed => do_arm!(buf, address, proc, new, u32),
-            8 if self.signed => do_arm!(buf, address, proc, new, i64),
-            8 if !self.signed => do_arm!(buf, address, proc, new, u64),
-            _ => unreachable!(),
+    fn write_value(&self, new: &str, address: usize, proc: &Process) -> bool {
+        let format = self.format.get();
+        if let Some(raw) = format.parse_int(new, N, self.signed) {
+            proc.write(address, &format.write_raw(raw, N));
+            true
+        } else {
+            false
         }
     }
 }
@@ -70,16 +64,22 @@ impl<const N: usize> Field for IntField<N> {
         Some(self.state.name.borrow().clone())
     }
 
+    fn rename(&self, id: FieldId, name: &str) -> Option<String> {
+        self.state.rename(self.id, id, name)
+    }
+
     fn kind(&self) -> FieldKind {
         match N {
-            1 if self.signed => FieldKind::U8,
-            1 if !self.signed => FieldKind::I8,
-            2 if self.signed => FieldKind::U16,
-            2 if !self.signed => FieldKind::I16,
-            4 if self.signed => FieldKind::U32,
-            4 if !self.signed => FieldKind::I32,
-            8 if self.signed => FieldKind::U64,
-            8 if !self.signed => FieldKind::I64,
+            1 if self.signed => FieldKind::I8,
+            1 if !self.signed => FieldKind::U8,
+            2 if self.signed => FieldKind::I16,
+            2 if !self.signed => FieldKind::U16,
+            4 if self.signed => FieldKind::I32,
+            4 if !self.signed => FieldKind::U32,
+            8 if self.signed => FieldKind::I64,
+            8 if !self.signed => FieldKind::U64,
+            16 if self.signed => FieldKind::I128,
+            16 if !self.signed => FieldKind::U128,
             _ => unreachable!(),
         }
     }
@@ -88,12 +88,16 @@ impl<const N: usize> Field for IntField<N> {
         let mut buf = [0; N];
         let address = ctx.address + ctx.offset;
         ctx.process.read(ctx.address + ctx.offset, &mut buf);
+        let raw = self.format.get().read_raw(&buf);
 
         ui.horizontal(|ui| {
             let mut job = LayoutJob::default();
             display_field_prelude(ui.ctx(), self, ctx, &mut job);
 
-            if ui.add(Label::new(job).sense(Sense::click())).clicked() {
+            let r = ui
+                .add(Label::new(job).sense(Sense::click()))
+                .context_menu(|ui| NumberFormat::menu(&self.format, ui, true));
+            if r.clicked() {
                 ctx.select(self.id);
             }
 
@@ -114,25 +118,11 @@ impl<const N: usize> Field for IntField<N> {
                 ctx,
                 &self.state,
                 Color32::WHITE,
-                |_| match N {
-                    1 if self.signed => (buf[0] as i8).to_string(),
-                    1 if !self.signed => buf[0].to_string(),
-                    2 if self.signed => i16::from_ne_bytes(buf[..].try_into().unwrap()).to_string(),
-                    2 if !self.signed => {
-                        u16::from_ne_bytes(buf[..].try_into().unwrap()).to_string()
-                    }
-                    4 if self.signed => i32::from_ne_bytes(buf[..].try_into().unwrap()).to_string(),
-                    4 if !self.signed => {
-                        u32::from_ne_bytes(buf[..].try_into().unwrap()).to_string()
-                    }
-                    8 if self.signed => i64::from_ne_bytes(buf[..].try_into().unwrap()).to_string(),
-                    8 if !self.signed => {
-                        u64::from_ne_bytes(buf[..].try_into().unwrap()).to_string()
-                    }
-                    _ => unreachable!(),
-                },
+                |_| self.format.get().format_int(raw, N, self.signed),
                 |new| self.write_value(new, address, ctx.process),
             );
+
+            display_field_comment(self, ui, ctx);
         });
 
         ctx.offset += N;
@@ -142,18 +132,8 @@ impl<const N: usize> Field for IntField<N> {
     fn codegen(&self, generator: &mut dyn Generator, _: &CodegenData) {
         generator.add_field(
             self.state.name.borrow().as_str(),
-            match N {
-                1 if self.signed => FieldKind::I8,
-                1 if !self.signed => FieldKind::U8,
-                2 if self.signed => FieldKind::I16,
-                2 if !self.signed => FieldKind::U16,
-                4 if self.signed => FieldKind::I32,
-                4 if !self.signed => FieldKind::U32,
-                8 if self.signed => FieldKind::I64,
-                8 if !self.signed => FieldKind::U64,
-                _ => unreachable!(),
-            },
-            None,
+            self.kind(),
+            self.format.get().to_metadata().as_deref(),
         );
     }
 }
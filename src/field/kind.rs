@@ -1,4 +1,8 @@
-use super::{BoolField, Field, FloatField, HexField, IntField, PointerField, StringPointerField};
+use super::{
+    BoolField, ChainField, ContainerElement, ContainerField, Field, FixedField, FloatField,
+    HexField, IntField, NumberFormat, OwnedStringField, PointerField, SemanticField, StringField,
+    StringPointerField, UnionField, VTableField,
+};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
@@ -8,9 +12,102 @@ pub enum FieldKind {
     I8, I16, I32, I64,
     U8, U16, U32, U64,
     F32, F64,
+    I128, U128,
+    /// IEEE 754 half precision float.
+    F16,
+    /// bfloat16, upper half of `F32`.
+    BF16,
+    /// Signed 32-bit fixed-point number with `N` fractional bits, i.e. `Fixed32(16)` is Q16.16.
+    Fixed32(u8),
+    /// Signed 64-bit fixed-point number with `N` fractional bits.
+    Fixed64(u8),
     Ptr,
+    /// Pointer to UTF-8 string, same as `TextPtr(TextEncoding::Utf8, 64)`.
+    /// Only kept so older projects still load.
     StrPtr,
     Bool,
+    /// Inline string(i.e. `char[N]`) of `N` code units.
+    Text(TextEncoding, usize),
+    /// Pointer to a string of at most `N` code units.
+    TextPtr(TextEncoding, usize),
+    /// libstdc++ `std::string`.
+    StdString,
+    /// Rust `String`.
+    RustString,
+    /// libstdc++ `std::vector<T>`.
+    StdVector,
+    /// Rust `Vec<T>`.
+    RustVec,
+    /// libstdc++ `std::list<T>`.
+    StdList,
+    /// Rust `std::collections::LinkedList<T>`.
+    RustList,
+    /// Pointer to the first node of an intrusive linked list.
+    IntrusiveList,
+    /// Pointer to the virtual function table.
+    VTable,
+    /// Pointer followed through a chain of offsets to a value.
+    Chain,
+    /// Signed 32-bit offset relative to the field.
+    RelPtr32(RelativeTo),
+    /// Signed 64-bit offset relative to the field.
+    RelPtr64(RelativeTo),
+    /// Fields of any kind sharing `N` bytes.
+    Union(usize),
+    /// Windows `GUID`, first three groups are little endian.
+    Guid,
+    /// RFC 4122 UUID, all bytes are in display order.
+    Uuid,
+    /// 64-bit Unix timestamp.
+    UnixTime(TimeUnit),
+    /// `struct timespec`, seconds and nanoseconds.
+    Timespec,
+    /// `struct timeval`, seconds and microseconds.
+    Timeval,
+    /// IPv4 address in network byte order.
+    Ipv4,
+    /// IPv6 address in network byte order.
+    Ipv6,
+    /// Port in network byte order.
+    Port,
+    /// 8-bit per channel colour.
+    Rgba,
+}
+
+/// Unit of the Unix timestamp.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum TimeUnit {
+    Seconds,
+    Millis,
+    Nanos,
+}
+
+impl TimeUnit {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Seconds => "seconds",
+            Self::Millis => "milliseconds",
+            Self::Nanos => "nanoseconds",
+        }
+    }
+}
+
+/// Address that relative pointer's offset is added to.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum RelativeTo {
+    /// Address of the field itself.
+    Field,
+    /// Address right after the field, as in RIP-relative addressing.
+    End,
+}
+
+impl RelativeTo {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Field => "field",
+            Self::End => "field end",
+        }
+    }
 }
 
 impl FieldKind {
@@ -25,6 +122,12 @@ impl FieldKind {
         (Self::U64, "U64"),
         (Self::F32, "F32"),
         (Self::F64, "F64"),
+        (Self::I128, "I128"),
+        (Self::U128, "U128"),
+        (Self::F16, "F16"),
+        (Self::BF16, "BF16"),
+        (Self::Fixed32(16), "Q16.16"),
+        (Self::Fixed64(32), "Q32.32"),
     ];
 
     pub fn label(&self) -> Option<&'static str> {
@@ -32,6 +135,29 @@ impl FieldKind {
             .iter()
             .find_map(|(v, s)| if v == self { Some(*s) } else { None })
     }
+
+    /// Kinds that are displayed using [`NumberFormat`].
+    pub fn is_numeric(&self) -> bool {
+        matches!(
+            self,
+            Self::I8
+                | Self::I16
+                | Self::I32
+                | Self::I64
+                | Self::I128
+                | Self::U8
+                | Self::U16
+                | Self::U32
+                | Self::U64
+                | Self::U128
+                | Self::F16
+                | Self::BF16
+                | Self::F32
+                | Self::F64
+                | Self::Fixed32(_)
+                | Self::Fixed64(_)
+        )
+    }
 }
 
 impl FieldKind {
@@ -39,50 +165,235 @@ impl FieldKind {
     pub fn size(&self) -> usize {
         match self {
             Self::Unk8 | Self::I8 | Self::U8 | Self::Bool => 1,
-            Self::Unk16 | Self::I16 | Self::U16 => 2,
-            Self::Unk32 | Self::I32 | Self::U32 | Self::F32 => 4,
+            Self::Unk16 | Self::I16 | Self::U16 | Self::F16 | Self::BF16 | Self::Port => 2,
+            Self::Unk32
+            | Self::Ipv4
+            | Self::Rgba
+            | Self::I32
+            | Self::U32
+            | Self::F32
+            | Self::Fixed32(_)
+            | Self::RelPtr32(_) => 4,
             // TODO(ItsEthra): Pointer size is... sigh, different for 32-bit processes
-            Self::Unk64 | Self::I64 | Self::U64 | Self::F64 | Self::Ptr | Self::StrPtr => 8,
+            Self::Unk64
+            | Self::I64
+            | Self::U64
+            | Self::F64
+            | Self::Fixed64(_)
+            | Self::UnixTime(_)
+            | Self::Ptr
+            | Self::StrPtr
+            | Self::TextPtr(..)
+            | Self::IntrusiveList
+            | Self::VTable
+            | Self::Chain
+            | Self::RelPtr64(_) => 8,
+            Self::Text(encoding, len) => encoding.unit_size() * len,
+            Self::RustString | Self::StdVector | Self::RustVec | Self::StdList | Self::RustList => {
+                24
+            }
+            Self::StdString => 32,
+            Self::I128
+            | Self::U128
+            | Self::Guid
+            | Self::Uuid
+            | Self::Timespec
+            | Self::Timeval
+            | Self::Ipv6 => 16,
+            Self::Union(size) => *size,
         }
     }
 
     pub fn into_field(self, name: Option<String>) -> Box<dyn Field> {
+        self.into_formatted_field(name, NumberFormat::default())
+    }
+
+    /// Same as [`FieldKind::into_field`], but numeric fields are read and displayed using `format`.
+    pub fn into_formatted_field(
+        self,
+        name: Option<String>,
+        format: NumberFormat,
+    ) -> Box<dyn Field> {
         match self {
             Self::Unk8 => Box::new(HexField::<1>::new()),
             Self::Unk16 => Box::new(HexField::<2>::new()),
             Self::Unk32 => Box::new(HexField::<4>::new()),
             Self::Unk64 => Box::new(HexField::<8>::new()),
-            Self::I8 => Box::new(IntField::<1>::signed(name.unwrap_or_else(|| "int8".into()))),
-            Self::I16 => Box::new(IntField::<2>::signed(
-                name.unwrap_or_else(|| "int16".into()),
-            )),
-            Self::I32 => Box::new(IntField::<4>::signed(
-                name.unwrap_or_else(|| "int32".into()),
+            Self::I8 => Box::new(
+                IntField::<1>::signed(name.unwrap_or_else(|| "int8".into())).with_format(format),
+            ),
+            Self::I16 => Box::new(
+                IntField::<2>::signed(name.unwrap_or_else(|| "int16".into())).with_format(format),
+            ),
+            Self::I32 => Box::new(
+                IntField::<4>::signed(name.unwrap_or_else(|| "int32".into())).with_format(format),
+            ),
+            Self::I64 => Box::new(
+                IntField::<8>::signed(name.unwrap_or_else(|| "int64".into())).with_format(format),
+            ),
+            Self::U8 => Box::new(
+                IntField::<1>::unsigned(name.unwrap_or_else(|| "uint8".into())).with_format(format),
+            ),
+            Self::U16 => Box::new(
+                IntField::<2>::unsigned(name.unwrap_or_else(|| "uint16".into()))
+                    .with_format(format),
+            ),
+            Self::U32 => Box::new(
+                IntField::<4>::unsigned(name.unwrap_or_else(|| "uint32".into()))
+                    .with_format(format),
+            ),
+            Self::U64 => Box::new(
+                IntField::<8>::unsigned(name.unwrap_or_else(|| "uint64".into()))
+                    .with_format(format),
+            ),
+            Self::F32 => Box::new(
+                FloatField::<4>::new(name.unwrap_or_else(|| "float".into())).with_format(format),
+            ),
+            Self::F64 => Box::new(
+                FloatField::<8>::new(name.unwrap_or_else(|| "double".into())).with_format(format),
+            ),
+            Self::I128 => Box::new(
+                IntField::<16>::signed(name.unwrap_or_else(|| "int128".into())).with_format(format),
+            ),
+            Self::U128 => Box::new(
+                IntField::<16>::unsigned(name.unwrap_or_else(|| "uint128".into()))
+                    .with_format(format),
+            ),
+            Self::F16 => Box::new(
+                FloatField::<2>::new(name.unwrap_or_else(|| "half".into())).with_format(format),
+            ),
+            Self::BF16 => Box::new(
+                FloatField::<2>::bfloat(name.unwrap_or_else(|| "bfloat".into()))
+                    .with_format(format),
+            ),
+            Self::Fixed32(frac) => Box::new(
+                FixedField::<4>::new(name.unwrap_or_else(|| "fixed".into()), frac)
+                    .with_format(format),
+            ),
+            Self::Fixed64(frac) => Box::new(
+                FixedField::<8>::new(name.unwrap_or_else(|| "fixed".into()), frac)
+                    .with_format(format),
+            ),
+            Self::Bool => Box::new(BoolField::new(name.unwrap_or_else(|| "boolean".into()))),
+            Self::Guid | Self::Uuid => Box::new(SemanticField::new(
+                name.unwrap_or_else(|| "guid".into()),
+                self,
             )),
-            Self::I64 => Box::new(IntField::<8>::signed(
-                name.unwrap_or_else(|| "int64".into()),
+            Self::UnixTime(_) | Self::Timespec | Self::Timeval => Box::new(SemanticField::new(
+                name.unwrap_or_else(|| "time".into()),
+                self,
             )),
-            Self::U8 => Box::new(IntField::<1>::unsigned(
-                name.unwrap_or_else(|| "uint8".into()),
+            Self::Ipv4 | Self::Ipv6 => Box::new(SemanticField::new(
+                name.unwrap_or_else(|| "address".into()),
+                self,
             )),
-            Self::U16 => Box::new(IntField::<2>::unsigned(
-                name.unwrap_or_else(|| "uint16".into()),
+            Self::Port => Box::new(SemanticField::new(
+                name.unwrap_or_else(|| "port".into()),
+                self,
             )),
-            Self::U32 => Box::new(IntField::<4>::unsigned(
-                name.unwrap_or_else(|| "uint32".into()),
+            Self::Rgba => Box::new(SemanticField::new(
+                name.unwrap_or_else(|| "color".into()),
+                self,
             )),
-            Self::U64 => Box::new(IntField::<8>::unsigned(
-                name.unwrap_or_else(|| "uint64".into()),
+            Self::Ptr => Box::new(PointerField::new(name.unwrap_or_else(|| "pointer".into()))),
+            Self::RelPtr32(_) | Self::RelPtr64(_) => Box::new(PointerField::new_relative(
+                name.unwrap_or_else(|| "rel_pointer".into()),
+                self,
             )),
-            Self::F32 => Box::new(FloatField::<4>::new(name.unwrap_or_else(|| "float".into()))),
-            Self::F64 => Box::new(FloatField::<8>::new(
-                name.unwrap_or_else(|| "double".into()),
+            Self::Union(size) => Box::new(UnionField::new(
+                name.unwrap_or_else(|| "union".into()),
+                size,
             )),
-            Self::Bool => Box::new(BoolField::new(name.unwrap_or_else(|| "boolean".into()))),
-            Self::Ptr => Box::new(PointerField::new(name.unwrap_or_else(|| "pointer".into()))),
+            Self::VTable => Box::new(VTableField::new(name.unwrap_or_else(|| "vtable".into()))),
+            Self::Chain => Box::new(ChainField::new(name.unwrap_or_else(|| "chain".into()))),
             Self::StrPtr => Box::new(StringPointerField::new(
                 name.unwrap_or_else(|| "str_ptr".into()),
+                TextEncoding::Utf8,
+                64,
             )),
+            Self::Text(encoding, len) => Box::new(StringField::new(
+                name.unwrap_or_else(|| "text".into()),
+                encoding,
+                len,
+            )),
+            Self::TextPtr(encoding, len) => Box::new(StringPointerField::new(
+                name.unwrap_or_else(|| "str_ptr".into()),
+                encoding,
+                len,
+            )),
+            Self::StdString | Self::RustString => Box::new(OwnedStringField::new(
+                name.unwrap_or_else(|| "string".into()),
+                self,
+            )),
+            Self::StdVector | Self::RustVec => Box::new(ContainerField::new(
+                name.unwrap_or_else(|| "vector".into()),
+                self,
+                ContainerElement::Kind(Self::Unk64),
+            )),
+            Self::StdList | Self::RustList | Self::IntrusiveList => Box::new(ContainerField::new(
+                name.unwrap_or_else(|| "list".into()),
+                self,
+                ContainerElement::Kind(Self::Unk64),
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TextEncoding {
+    Utf8,
+    Utf16,
+    Utf32,
+}
+
+impl TextEncoding {
+    pub const NAMED_VARIANTS: &[(TextEncoding, &'static str)] = &[
+        (Self::Utf8, "UTF-8"),
+        (Self::Utf16, "UTF-16"),
+        (Self::Utf32, "UTF-32"),
+    ];
+
+    /// Size of a single code unit in bytes.
+    pub fn unit_size(&self) -> usize {
+        match self {
+            Self::Utf8 => 1,
+            Self::Utf16 => 2,
+            Self::Utf32 => 4,
+        }
+    }
+
+    /// Decodes string up to the first null code unit, invalid sequences are replaced.
+    pub fn decode(&self, buf: &[u8]) -> String {
+        let units = buf.chunks_exact(self.unit_size());
+
+        match self {
+            Self::Utf8 => {
+                let end = buf.iter().position(|c| *c == 0).unwrap_or(buf.len());
+                String::from_utf8_lossy(&buf[..end]).into_owned()
+            }
+            Self::Utf16 => String::from_utf16_lossy(
+                &units
+                    .map(|u| u16::from_ne_bytes(u.try_into().unwrap()))
+                    .take_while(|u| *u != 0)
+                    .collect::<Vec<_>>(),
+            ),
+            Self::Utf32 => units
+                .map(|u| u32::from_ne_bytes(u.try_into().unwrap()))
+                .take_while(|u| *u != 0)
+                .map(|u| char::from_u32(u).unwrap_or(char::REPLACEMENT_CHARACTER))
+                .collect(),
+        }
+    }
+
+    /// Encodes string into code units, without null terminator.
+    pub fn encode(&self, text: &str) -> Vec<u8> {
+        match self {
+            Self::Utf8 => text.as_bytes().to_vec(),
+            Self::Utf16 => text.encode_utf16().flat_map(u16::to_ne_bytes).collect(),
+            Self::Utf32 => text
+                .chars()
+                .flat_map(|c| (c as u32).to_ne_bytes())
+                .collect(),
         }
     }
 }
@@ -8,14 +8,37 @@ mod utils;
 pub use utils::*;
 mod float;
 pub use float::*;
+mod fixed;
+pub use fixed::*;
 mod pointer;
 pub use pointer::*;
 mod string_pointer;
 pub use string_pointer::*;
+mod string;
+pub use string::*;
+mod owned_string;
+pub use owned_string::*;
+mod container;
+pub use container::*;
+mod vtable;
+pub use vtable::*;
+mod chain;
+pub use chain::*;
+mod union;
+pub use union::*;
 mod boolean;
 pub use boolean::*;
+mod semantic;
+pub use semantic::*;
+mod format;
+pub use format::*;
 
-use crate::{class::Class, context::InspectionContext, generator::Generator, FID_M};
+use crate::{
+    class::Class,
+    context::InspectionContext,
+    generator::{BaseClass, Generator},
+    FID_M,
+};
 use eframe::{
     egui::{Id, TextFormat, Ui},
     epaint::{Color32, Stroke},
@@ -38,12 +61,44 @@ pub trait Field {
 
     fn draw(&self, ui: &mut Ui, ctx: &mut InspectionContext) -> Option<FieldResponse>;
     fn codegen(&self, generator: &mut dyn Generator, data: &CodegenData);
+
+    /// Renames the field with `id`, either this one or one nested in it, and returns the old name.
+    fn rename(&self, _id: FieldId, _name: &str) -> Option<String> {
+        None
+    }
 }
 
 pub struct CodegenData<'a> {
     pub classes: &'a [Class],
 }
 
+impl<'a> CodegenData<'a> {
+    pub fn base_of(&self, class: &Class) -> Option<BaseClass<'a>> {
+        let base = self.classes.iter().find(|c| Some(c.id()) == class.base())?;
+
+        Some(BaseClass {
+            name: &base.name,
+            size: base.size() + self.base_of(base).map_or(0, |b| b.size),
+        })
+    }
+
+    /// Generates the class along with comments of the class and its fields.
+    pub fn codegen_class(&self, class: &Class, generator: &mut dyn Generator) {
+        if !class.comment.is_empty() {
+            generator.add_comment(&class.comment);
+        }
+
+        generator.begin_class(&class.name, self.base_of(class));
+        for field in class.fields.iter() {
+            if let Some(comment) = class.field_comments.get(&field.id()) {
+                generator.add_comment(comment);
+            }
+            field.codegen(generator, self);
+        }
+        generator.end_class();
+    }
+}
+
 pub struct EditingState {
     address: usize,
     buf: String,
@@ -76,6 +131,11 @@ impl NamedState {
             ..Default::default()
         }
     }
+
+    /// Renames the field owning the state if its id is `id`, see [`Field::rename`].
+    fn rename(&self, own: FieldId, id: FieldId, name: &str) -> Option<String> {
+        (own == id).then(|| self.name.replace(name.to_owned()))
+    }
 }
 
 mod private {
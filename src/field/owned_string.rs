@@ -0,0 +1,147 @@
+use super::{
+    container::{read_usize, BufferParts},
+    display_field_comment, display_field_name, display_field_prelude, display_field_value, next_id,
+    parse_text_value, CodegenData, Field, FieldId, FieldKind, FieldResponse, NamedState,
+};
+use crate::{context::InspectionContext, generator::Generator, process::Process, FID_M};
+use eframe::{
+    egui::{Label, RichText, Sense, Ui},
+    epaint::{text::LayoutJob, Color32},
+};
+
+/// Maximum amount of bytes displayed.
+const MAX_LEN: usize = 256;
+
+/// libstdc++ `std::string` or Rust `String`.
+pub struct OwnedStringField {
+    id: FieldId,
+    kind: FieldKind,
+    state: NamedState,
+}
+
+impl OwnedStringField {
+    pub fn new(name: String, kind: FieldKind) -> Self {
+        Self {
+            id: next_id(),
+            kind,
+            state: NamedState::new(name),
+        }
+    }
+
+    fn parts(&self, process: &Process, address: usize) -> BufferParts {
+        match self.kind {
+            // `{ ptr, len, union { char buf[16]; size_t cap; } }`,
+            // `ptr` points to `buf` when the string is short.
+            FieldKind::StdString => {
+                let ptr = read_usize(process, address);
+                BufferParts {
+                    ptr,
+                    len: read_usize(process, address + 8),
+                    cap: if ptr == address + 16 {
+                        15
+                    } else {
+                        read_usize(process, address + 16)
+                    },
+                    len_offset: 8,
+                }
+            }
+            FieldKind::RustString => BufferParts::rust_vec(process, address),
+            _ => unreachable!(),
+        }
+    }
+
+    fn write_value(&self, new: &str, address: usize, process: &Process) -> bool {
+        let Some(new) = parse_text_value(new) else {
+            return false;
+        };
+
+        let parts = self.parts(process, address);
+        if new.len() > parts.cap {
+            return false;
+        }
+
+        if self.kind == FieldKind::StdString {
+            process.write(parts.ptr, &[new.as_bytes(), &[0]].concat());
+        } else {
+            process.write(parts.ptr, new.as_bytes());
+        }
+        process.write(address + parts.len_offset, &new.len().to_ne_bytes());
+
+        true
+    }
+}
+
+impl Field for OwnedStringField {
+    fn id(&self) -> FieldId {
+        self.id
+    }
+
+    fn name(&self) -> Option<String> {
+        Some(self.state.name.borrow().clone())
+    }
+
+    fn rename(&self, id: FieldId, name: &str) -> Option<String> {
+        self.state.rename(self.id, id, name)
+    }
+
+    fn size(&self) -> usize {
+        self.kind.size()
+    }
+
+    fn kind(&self) -> FieldKind {
+        self.kind
+    }
+
+    fn draw(&self, ui: &mut Ui, ctx: &mut InspectionContext) -> Option<FieldResponse> {
+        let address = ctx.address + ctx.offset;
+        let parts = self.parts(ctx.process, address);
+
+        let mut str_buf = vec![0; parts.len.min(MAX_LEN)];
+        ctx.process.read(parts.ptr, &mut str_buf);
+
+        ui.horizontal(|ui| {
+            let mut job = LayoutJob::default();
+            display_field_prelude(ui.ctx(), self, ctx, &mut job);
+            if ui.add(Label::new(job).sense(Sense::click())).clicked() {
+                ctx.select(self.id);
+            }
+            display_field_name(self, ui, ctx, &self.state, Color32::LIGHT_RED);
+
+            if parts.len <= parts.cap && (parts.len == 0 || ctx.process.can_read(parts.ptr)) {
+                display_field_value(
+                    self,
+                    ui,
+                    ctx,
+                    &self.state,
+                    Color32::LIGHT_BLUE,
+                    |v| {
+                        let str = String::from_utf8_lossy(&str_buf);
+
+                        if v {
+                            format!("{str:?}")
+                        } else {
+                            format!("{str:?} [{}/{}]", parts.len, parts.cap)
+                        }
+                    },
+                    |new| self.write_value(new, address, ctx.process),
+                );
+            } else {
+                ui.add_space(2.);
+                ui.heading(
+                    RichText::new("Invalid String")
+                        .color(Color32::RED)
+                        .font(FID_M),
+                );
+            }
+
+            display_field_comment(self, ui, ctx);
+        });
+
+        ctx.offset += self.size();
+        None
+    }
+
+    fn codegen(&self, generator: &mut dyn Generator, _: &CodegenData) {
+        generator.add_field(self.state.name.borrow().as_str(), self.kind, None);
+    }
+}
@@ -1,13 +1,14 @@
 use super::{
-    create_text_format, display_field_name, display_field_prelude, display_field_value, next_id,
-    CodegenData, Field, FieldId, FieldKind, FieldResponse, NamedState,
+    create_text_format, display_field_comment, display_field_name, display_field_prelude,
+    display_field_value, draw_base_fields, draw_overlays, next_id, CodegenData, Field, FieldId,
+    FieldKind, FieldResponse, NamedState, RelativeTo,
+};
+use crate::{
+    address::parse_address, context::InspectionContext, generator::Generator, process::Process,
+    FID_M,
 };
-use crate::{address::parse_address, context::InspectionContext, generator::Generator, FID_M};
 use eframe::{
-    egui::{
-        collapsing_header::CollapsingState, popup_below_widget, Id, Label, RichText, Sense,
-        TextFormat, Ui,
-    },
+    egui::{popup_below_widget, Id, Label, RichText, Sense, TextFormat, Ui},
     epaint::{text::LayoutJob, Color32},
 };
 use fastrand::Rng;
@@ -15,24 +16,76 @@ use std::{cell::Cell, mem::transmute};
 
 pub struct PointerField {
     id: FieldId,
+    // `Ptr`, `RelPtr32` or `RelPtr64`.
+    kind: FieldKind,
     state: NamedState,
     class_id: Cell<Option<usize>>,
 }
 
 impl PointerField {
     pub fn new(name: String) -> Self {
+        Self::new_relative(name, FieldKind::Ptr)
+    }
+
+    pub fn new_relative(name: String, kind: FieldKind) -> Self {
         Self {
             id: next_id(),
+            kind,
             state: NamedState::new(name),
             class_id: None.into(),
         }
     }
 
-    pub fn new_with_class_id(name: String, class_id: usize) -> Self {
+    pub fn new_with_class_id(name: String, kind: FieldKind, class_id: usize) -> Self {
         Self {
-            id: next_id(),
-            state: NamedState::new(name),
             class_id: Some(class_id).into(),
+            ..Self::new_relative(name, kind)
+        }
+    }
+
+    /// Address the stored offset is relative to, `None` for absolute pointers.
+    fn relative_base(&self, field_address: usize) -> Option<usize> {
+        match self.kind {
+            FieldKind::RelPtr32(RelativeTo::Field) | FieldKind::RelPtr64(RelativeTo::Field) => {
+                Some(field_address)
+            }
+            FieldKind::RelPtr32(RelativeTo::End) | FieldKind::RelPtr64(RelativeTo::End) => {
+                Some(field_address + self.size())
+            }
+            _ => None,
+        }
+    }
+
+    fn read_target(&self, ctx: &InspectionContext) -> usize {
+        let field_address = ctx.address + ctx.offset;
+        let mut buf = [0; 8];
+        ctx.process.read(field_address, &mut buf[..self.size()]);
+
+        match (self.relative_base(field_address), self.size()) {
+            (Some(base), 4) => {
+                let offset = i32::from_ne_bytes(buf[..4].try_into().unwrap());
+                base.wrapping_add_signed(offset as isize)
+            }
+            (Some(base), _) => base.wrapping_add_signed(isize::from_ne_bytes(buf)),
+            (None, _) => usize::from_ne_bytes(buf),
+        }
+    }
+
+    /// Writes pointer to `target`, fails if the offset doesn't fit or the process is read-only.
+    fn write_target(&self, process: &Process, field_address: usize, target: usize) -> bool {
+        match self.relative_base(field_address) {
+            Some(base) => {
+                let offset = target.wrapping_sub(base) as isize;
+                if self.size() == 4 {
+                    let Ok(offset) = i32::try_from(offset) else {
+                        return false;
+                    };
+                    process.write(field_address, &offset.to_ne_bytes())
+                } else {
+                    process.write(field_address, &offset.to_ne_bytes())
+                }
+            }
+            None => process.write(field_address, &target.to_ne_bytes()),
         }
     }
 
@@ -56,7 +109,7 @@ impl PointerField {
         display_field_name(self, ui, ctx, &self.state, Color32::BROWN);
 
         let is_selected = ctx.is_selected(self.id);
-        let paddr = ctx.address + ctx.offset;
+        let (process, paddr) = (ctx.process, ctx.address + ctx.offset);
 
         ui.add_space(4.);
 
@@ -75,8 +128,7 @@ impl PointerField {
             },
             |new| {
                 if let Some(addr) = parse_address(new) {
-                    ctx.process.write(paddr, &addr.to_ne_bytes());
-                    true
+                    self.write_target(process, paddr, addr)
                 } else {
                     false
                 }
@@ -98,7 +150,7 @@ impl PointerField {
         );
 
         let r = ui.add(Label::new(job).sense(Sense::click()));
-        if r.secondary_clicked() {
+        if r.secondary_clicked() && !ctx.imported() {
             ui.memory_mut(|m| m.toggle_popup(Id::new(ctx.current_id)));
         } else if r.clicked() {
             ctx.select(self.id);
@@ -114,6 +166,8 @@ impl PointerField {
                 }
             });
         });
+
+        display_field_comment(self, ui, ctx);
     }
 
     fn show_body(
@@ -146,15 +200,23 @@ impl PointerField {
                 current_id: Id::null(),
                 process: ctx.process,
                 toasts: ctx.toasts,
+                edits: ctx.edits,
+                nodes: ctx.nodes,
                 level_rng: &rng,
                 offset: 0,
                 address,
             };
 
+            let inherited = draw_base_fields(class, ui, &mut inner_ctx);
             #[allow(clippy::single_match)]
-            match class.fields.iter().fold(None, |r, f| {
+            match class.fields.iter().fold(inherited, |r, f| {
                 inner_ctx.current_id = Id::new(rng.u64(..));
-                r.or(f.draw(ui, &mut inner_ctx))
+                r.or(f.draw(ui, &mut inner_ctx)).or(draw_overlays(
+                    &class.overlays,
+                    f.as_ref(),
+                    ui,
+                    &mut inner_ctx,
+                ))
             }) {
                 Some(other) => response = Some(other),
                 None => {}
@@ -178,30 +240,32 @@ impl Field for PointerField {
         // TODO(ItsEthra): When inspecting 32-bit processes
         // size of the pointer would be `4`. But I am not sure
         // if the rest of this app isn't break in this case lol.
-        8
+        self.kind.size()
     }
 
     fn name(&self) -> Option<String> {
         Some(self.state.name.borrow().clone())
     }
 
+    fn rename(&self, id: FieldId, name: &str) -> Option<String> {
+        self.state.rename(self.id, id, name)
+    }
+
     fn kind(&self) -> FieldKind {
-        FieldKind::Ptr
+        self.kind
     }
 
     fn draw(&self, ui: &mut Ui, ctx: &mut InspectionContext) -> Option<FieldResponse> {
         let mut response = None;
 
         // TODO(ItsEthra): Again, pointer size differs in 32-bit processes.
-        let mut buf = [0; 8];
-        ctx.process.read(ctx.address + ctx.offset, &mut buf);
-        let address = usize::from_ne_bytes(buf);
+        let address = self.read_target(ctx);
 
         if self.class_id.get().is_none() {
             self.class_id.set(Some(fastrand::usize(..)));
         }
 
-        let state = CollapsingState::load_with_default_open(ui.ctx(), ctx.current_id, false);
+        let state = ctx.nodes.load(ui.ctx(), ctx.current_id, false);
         let body = state
             .show_header(ui, |ui| self.show_header(ui, ctx, address))
             .body(|ui| self.show_body(ui, ctx, address))
@@ -219,10 +283,10 @@ impl Field for PointerField {
     fn codegen(&self, generator: &mut dyn Generator, data: &CodegenData) {
         generator.add_field(
             self.state.name.borrow().as_str(),
-            FieldKind::Ptr,
+            self.kind,
             data.classes
                 .iter()
-                .find(|c| c.id() == self.class_id.get().unwrap())
+                .find(|c| Some(c.id()) == self.class_id.get())
                 .map(|c| c.name.as_ref()),
         );
     }
@@ -0,0 +1,313 @@
+use super::{
+    display_field_comment, display_field_name, display_field_prelude, display_field_value, next_id,
+    CodegenData, Field, FieldId, FieldKind, FieldResponse, NamedState, TimeUnit, WRITE_REFUSED,
+};
+use crate::{context::InspectionContext, generator::Generator};
+use eframe::{
+    egui::{Label, Sense, Ui},
+    epaint::{text::LayoutJob, Color32},
+};
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+/// Field that decodes common value formats, i.e. GUIDs, timestamps and IP addresses.
+pub struct SemanticField {
+    id: FieldId,
+    state: NamedState,
+    kind: FieldKind,
+}
+
+impl SemanticField {
+    pub fn new(name: String, kind: FieldKind) -> Self {
+        Self {
+            id: next_id(),
+            state: NamedState::new(name),
+            kind,
+        }
+    }
+
+    fn decode(&self, buf: &[u8; 16]) -> String {
+        let i64_at =
+            |offset: usize| i64::from_ne_bytes(buf[offset..offset + 8].try_into().unwrap());
+
+        match self.kind {
+            FieldKind::Guid => format_guid(&guid_swap(buf)),
+            FieldKind::Uuid => format_guid(buf),
+            FieldKind::UnixTime(unit) => {
+                let scale = unit_scale(unit);
+                let value = i64_at(0);
+                format_time(
+                    value.div_euclid(scale),
+                    (value.rem_euclid(scale) * (1_000_000_000 / scale)) as u32,
+                    scale.ilog10() as usize,
+                )
+            }
+            FieldKind::Timespec => match i64_at(8) {
+                nsec @ 0..=999_999_999 => format_time(i64_at(0), nsec as u32, 9),
+                _ => "invalid".to_owned(),
+            },
+            FieldKind::Timeval => match i64_at(8) {
+                usec @ 0..=999_999 => format_time(i64_at(0), usec as u32 * 1000, 6),
+                _ => "invalid".to_owned(),
+            },
+            FieldKind::Ipv4 => Ipv4Addr::from([buf[0], buf[1], buf[2], buf[3]]).to_string(),
+            FieldKind::Ipv6 => Ipv6Addr::from(*buf).to_string(),
+            FieldKind::Port => u16::from_be_bytes([buf[0], buf[1]]).to_string(),
+            FieldKind::Rgba => format!("#{:02X}{:02X}{:02X}{:02X}", buf[0], buf[1], buf[2], buf[3]),
+            _ => unreachable!(),
+        }
+    }
+
+    fn encode(&self, text: &str) -> Option<Vec<u8>> {
+        let text = text.trim();
+
+        Some(match self.kind {
+            FieldKind::Guid => guid_swap(&parse_guid(text)?).to_vec(),
+            FieldKind::Uuid => parse_guid(text)?.to_vec(),
+            // Raw values are accepted as well.
+            FieldKind::UnixTime(unit) => match text.parse::<i64>() {
+                Ok(raw) => raw,
+                Err(_) => {
+                    let scale = unit_scale(unit);
+                    let (secs, nanos) = parse_time(text)?;
+                    secs.checked_mul(scale)?
+                        .checked_add(nanos as i64 / (1_000_000_000 / scale))?
+                }
+            }
+            .to_ne_bytes()
+            .to_vec(),
+            FieldKind::Timespec | FieldKind::Timeval => {
+                let (secs, nanos) = match text.parse::<i64>() {
+                    Ok(secs) => (secs, 0),
+                    Err(_) => parse_time(text)?,
+                };
+                let frac = if self.kind == FieldKind::Timeval {
+                    nanos / 1000
+                } else {
+                    nanos
+                };
+
+                [secs.to_ne_bytes(), (frac as i64).to_ne_bytes()].concat()
+            }
+            FieldKind::Ipv4 => text.parse::<Ipv4Addr>().ok()?.octets().to_vec(),
+            FieldKind::Ipv6 => text.parse::<Ipv6Addr>().ok()?.octets().to_vec(),
+            FieldKind::Port => text.parse::<u16>().ok()?.to_be_bytes().to_vec(),
+            FieldKind::Rgba => {
+                let hex = text.strip_prefix('#').unwrap_or(text);
+                let mut rgba = parse_hex_bytes(hex)?;
+                match rgba.len() {
+                    3 => rgba.push(0xFF),
+                    4 => {}
+                    _ => return None,
+                }
+
+                rgba
+            }
+            _ => unreachable!(),
+        })
+    }
+}
+
+impl Field for SemanticField {
+    fn id(&self) -> FieldId {
+        self.id
+    }
+
+    fn name(&self) -> Option<String> {
+        Some(self.state.name.borrow().clone())
+    }
+
+    fn rename(&self, id: FieldId, name: &str) -> Option<String> {
+        self.state.rename(self.id, id, name)
+    }
+
+    fn size(&self) -> usize {
+        self.kind.size()
+    }
+
+    fn kind(&self) -> FieldKind {
+        self.kind
+    }
+
+    fn draw(&self, ui: &mut Ui, ctx: &mut InspectionContext) -> Option<FieldResponse> {
+        let mut buf = [0; 16];
+        let address = ctx.address + ctx.offset;
+        ctx.process.read(address, &mut buf[..self.size()]);
+
+        ui.horizontal(|ui| {
+            let mut job = LayoutJob::default();
+            display_field_prelude(ui.ctx(), self, ctx, &mut job);
+
+            if ui.add(Label::new(job).sense(Sense::click())).clicked() {
+                ctx.select(self.id);
+            }
+
+            display_field_name(self, ui, ctx, &self.state, Color32::KHAKI);
+
+            if self.kind == FieldKind::Rgba {
+                let mut rgba = [buf[0], buf[1], buf[2], buf[3]];
+                if ui.color_edit_button_srgba_unmultiplied(&mut rgba).changed()
+                    && !ctx.process.write_dragged(address, &rgba)
+                {
+                    ctx.toasts.error(WRITE_REFUSED);
+                }
+            }
+
+            display_field_value(
+                self,
+                ui,
+                ctx,
+                &self.state,
+                Color32::WHITE,
+                |_| self.decode(&buf),
+                |new| match self.encode(new) {
+                    Some(bytes) => {
+                        ctx.process.write(address, &bytes);
+                        true
+                    }
+                    None => false,
+                },
+            );
+
+            display_field_comment(self, ui, ctx);
+        });
+
+        ctx.offset += self.size();
+        None
+    }
+
+    fn codegen(&self, generator: &mut dyn Generator, _: &CodegenData) {
+        generator.add_field(self.state.name.borrow().as_str(), self.kind, None);
+    }
+}
+
+fn unit_scale(unit: TimeUnit) -> i64 {
+    match unit {
+        TimeUnit::Seconds => 1,
+        TimeUnit::Millis => 1_000,
+        TimeUnit::Nanos => 1_000_000_000,
+    }
+}
+
+/// Converts between memory layout of Windows `GUID` and display order, works both ways.
+fn guid_swap(buf: &[u8]) -> [u8; 16] {
+    let mut out: [u8; 16] = buf[..16].try_into().unwrap();
+    out[..4].reverse();
+    out[4..6].reverse();
+    out[6..8].reverse();
+    out
+}
+
+fn format_guid(b: &[u8]) -> String {
+    let hex = |range: std::ops::Range<usize>| {
+        b[range]
+            .iter()
+            .map(|b| format!("{b:02X}"))
+            .collect::<String>()
+    };
+
+    format!(
+        "{{{}-{}-{}-{}-{}}}",
+        hex(0..4),
+        hex(4..6),
+        hex(6..8),
+        hex(8..10),
+        hex(10..16)
+    )
+}
+
+fn parse_guid(text: &str) -> Option<[u8; 16]> {
+    let hex = text
+        .chars()
+        .filter(|c| !matches!(c, '{' | '}' | '-'))
+        .collect::<String>();
+
+    parse_hex_bytes(&hex)?.try_into().ok()
+}
+
+fn parse_hex_bytes(hex: &str) -> Option<Vec<u8>> {
+    if !hex.is_ascii() {
+        return None;
+    }
+
+    hex.as_bytes()
+        .chunks(2)
+        .map(|pair| match pair {
+            [_, _] => u8::from_str_radix(std::str::from_utf8(pair).ok()?, 16).ok(),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Formats time as `YYYY-MM-DD HH:MM:SS.fff UTC` with `digits` fractional digits.
+fn format_time(secs: i64, nanos: u32, digits: usize) -> String {
+    let (year, month, day) = civil_from_days(secs.div_euclid(86400));
+    let time = secs.rem_euclid(86400);
+
+    let mut out = format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02}",
+        time / 3600,
+        time % 3600 / 60,
+        time % 60
+    );
+    if digits > 0 {
+        out += &format!(".{:0digits$}", nanos / 10u32.pow(9 - digits as u32));
+    }
+    out + " UTC"
+}
+
+/// Parses time in format of [`format_time`], `T` can be used as a separator, time and UTC are optional.
+fn parse_time(text: &str) -> Option<(i64, u32)> {
+    let text = text.strip_suffix("UTC").unwrap_or(text).trim();
+    let (date, time) = text.split_once([' ', 'T']).unwrap_or((text, "00:00:00"));
+
+    let mut date = date.splitn(3, '-').map(str::parse::<i64>);
+    let (year, month, day) = (date.next()?.ok()?, date.next()?.ok()?, date.next()?.ok()?);
+    // Far larger years would overflow `days_from_civil`.
+    if year.abs() > 1_000_000 || !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    let (time, frac) = time.split_once('.').unwrap_or((time, ""));
+    let mut time = time.splitn(3, ':').map(str::parse::<i64>);
+    let (hours, minutes, seconds) = (time.next()?.ok()?, time.next()?.ok()?, time.next()?.ok()?);
+    if hours >= 24 || minutes >= 60 || seconds >= 60 || hours < 0 || minutes < 0 || seconds < 0 {
+        return None;
+    }
+
+    let nanos = if frac.is_empty() {
+        0
+    } else if frac.len() <= 9 && frac.bytes().all(|b| b.is_ascii_digit()) {
+        frac.parse::<u32>().ok()? * 10u32.pow(9 - frac.len() as u32)
+    } else {
+        return None;
+    };
+
+    let secs = days_from_civil(year, month, day)
+        .checked_mul(86400)?
+        .checked_add(hours * 3600 + minutes * 60 + seconds)?;
+    Some((secs, nanos))
+}
+
+// Both conversions are from http://howardhinnant.github.io/date_algorithms.html
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let doe = days.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+
+    (yoe + era * 400 + (month <= 2) as i64, month, day)
+}
+
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let doy = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+
+    era * 146097 + doe - 719468
+}
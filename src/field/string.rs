@@ -0,0 +1,101 @@
+use super::{
+    display_field_comment, display_field_name, display_field_prelude, display_field_value, next_id,
+    parse_text_value, CodegenData, Field, FieldId, FieldKind, FieldResponse, NamedState,
+    TextEncoding,
+};
+use crate::{context::InspectionContext, generator::Generator};
+use eframe::{
+    egui::{Label, Sense, Ui},
+    epaint::{text::LayoutJob, Color32},
+};
+
+/// Inline string, i.e. `char[N]`, `char16_t[N]` or `char32_t[N]`.
+pub struct StringField {
+    id: FieldId,
+    state: NamedState,
+    encoding: TextEncoding,
+    // Length in code units.
+    len: usize,
+}
+
+impl StringField {
+    pub fn new(name: String, encoding: TextEncoding, len: usize) -> Self {
+        Self {
+            id: next_id(),
+            state: NamedState::new(name),
+            encoding,
+            len,
+        }
+    }
+}
+
+impl Field for StringField {
+    fn id(&self) -> FieldId {
+        self.id
+    }
+
+    fn name(&self) -> Option<String> {
+        Some(self.state.name.borrow().clone())
+    }
+
+    fn rename(&self, id: FieldId, name: &str) -> Option<String> {
+        self.state.rename(self.id, id, name)
+    }
+
+    fn size(&self) -> usize {
+        self.len * self.encoding.unit_size()
+    }
+
+    fn kind(&self) -> FieldKind {
+        FieldKind::Text(self.encoding, self.len)
+    }
+
+    fn draw(&self, ui: &mut Ui, ctx: &mut InspectionContext) -> Option<FieldResponse> {
+        let mut buf = vec![0; self.size()];
+        let address = ctx.address + ctx.offset;
+        ctx.process.read(address, &mut buf);
+
+        ui.horizontal(|ui| {
+            let mut job = LayoutJob::default();
+            display_field_prelude(ui.ctx(), self, ctx, &mut job);
+
+            if ui.add(Label::new(job).sense(Sense::click())).clicked() {
+                ctx.select(self.id);
+            }
+
+            display_field_name(self, ui, ctx, &self.state, Color32::LIGHT_RED);
+            display_field_value(
+                self,
+                ui,
+                ctx,
+                &self.state,
+                Color32::LIGHT_BLUE,
+                |_| format!("{:?}", self.encoding.decode(&buf)),
+                |new| {
+                    let Some(mut bytes) = parse_text_value(new).map(|s| self.encoding.encode(&s))
+                    else {
+                        return false;
+                    };
+
+                    if bytes.len() > buf.len() {
+                        return false;
+                    }
+
+                    // Pad the rest of the array with zeroes.
+                    bytes.resize(buf.len(), 0);
+                    ctx.process.write(address, &bytes);
+                    true
+                },
+            );
+
+            display_field_comment(self, ui, ctx);
+        });
+
+        ctx.offset += self.size();
+        None
+    }
+
+    fn codegen(&self, generator: &mut dyn Generator, _: &CodegenData) {
+        generator.add_field(self.state.name.borrow().as_str(), self.kind(), None);
+    }
+}
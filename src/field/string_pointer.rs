@@ -6,20 +6,25 @@ use eframe::{
 use crate::FID_M;
 
 use super::{
-    display_field_name, display_field_prelude, display_field_value, next_id, Field, FieldId,
-    FieldKind, NamedState,
+    display_field_comment, display_field_name, display_field_prelude, display_field_value, next_id,
+    parse_text_value, Field, FieldId, FieldKind, NamedState, TextEncoding,
 };
 
 pub struct StringPointerField {
     id: FieldId,
     state: NamedState,
+    encoding: TextEncoding,
+    // Maximum number of code units, including null terminator.
+    max_len: usize,
 }
 
 impl StringPointerField {
-    pub fn new(name: String) -> Self {
+    pub fn new(name: String, encoding: TextEncoding, max_len: usize) -> Self {
         Self {
             id: next_id(),
             state: NamedState::new(name),
+            encoding,
+            max_len,
         }
     }
 }
@@ -33,13 +38,17 @@ impl Field for StringPointerField {
         Some(self.state.name.borrow().clone())
     }
 
+    fn rename(&self, id: FieldId, name: &str) -> Option<String> {
+        self.state.rename(self.id, id, name)
+    }
+
     fn size(&self) -> usize {
         // TODO: The size of the pointer would be 4 bytes on x86
         8
     }
 
     fn kind(&self) -> super::FieldKind {
-        FieldKind::StrPtr
+        FieldKind::TextPtr(self.encoding, self.max_len)
     }
 
     fn draw(
@@ -52,7 +61,7 @@ impl Field for StringPointerField {
         ctx.process.read(ctx.address + ctx.offset, &mut buf);
         let address = usize::from_ne_bytes(buf);
 
-        let mut str_buf = [0; 64];
+        let mut str_buf = vec![0; self.max_len * self.encoding.unit_size()];
         ctx.process.read(address, &mut str_buf);
 
         ui.horizontal(|ui| {
@@ -70,11 +79,7 @@ impl Field for StringPointerField {
                     &self.state,
                     Color32::LIGHT_BLUE,
                     |v| {
-                        let str_end = str_buf
-                            .iter()
-                            .position(|c| *c == b'\0')
-                            .unwrap_or(str_buf.len());
-                        let str = std::string::String::from_utf8_lossy(&str_buf[..str_end]);
+                        let str = self.encoding.decode(&str_buf);
 
                         if v {
                             format!("{str:?}")
@@ -82,7 +87,22 @@ impl Field for StringPointerField {
                             format!("-> {str:?}")
                         }
                     },
-                    |_| false,
+                    |new| {
+                        let Some(mut bytes) =
+                            parse_text_value(new).map(|s| self.encoding.encode(&s))
+                        else {
+                            return false;
+                        };
+
+                        // Null terminator has to fit too.
+                        bytes.resize(bytes.len() + self.encoding.unit_size(), 0);
+                        if bytes.len() > str_buf.len() {
+                            return false;
+                        }
+
+                        ctx.process.write(address, &bytes);
+                        true
+                    },
                 )
             } else {
                 ui.add_space(2.);
@@ -92,12 +112,14 @@ impl Field for StringPointerField {
                         .font(FID_M),
                 );
             }
+
+            display_field_comment(self, ui, ctx);
         });
         ctx.offset += self.size();
         None
     }
 
     fn codegen(&self, generator: &mut dyn crate::generator::Generator, _: &super::CodegenData) {
-        generator.add_field(self.state.name.borrow().as_str(), FieldKind::StrPtr, None);
+        generator.add_field(self.state.name.borrow().as_str(), self.kind(), None);
     }
 }
@@ -0,0 +1,235 @@
+use super::{
+    create_text_format, display_field_comment, display_field_name, display_field_prelude, next_id,
+    CodegenData, Field, FieldId, FieldKind, FieldResponse, NamedState,
+};
+use crate::{context::InspectionContext, generator::Generator};
+use eframe::{
+    egui::{popup_below_widget, Label, Sense, Ui},
+    epaint::{text::LayoutJob, Color32},
+};
+use std::cell::RefCell;
+
+/// Kinds that can be used as union alternatives or overlays.
+pub const ALTERNATIVE_KINDS: &[FieldKind] = &[
+    FieldKind::Bool,
+    FieldKind::I8,
+    FieldKind::I16,
+    FieldKind::I32,
+    FieldKind::I64,
+    FieldKind::U8,
+    FieldKind::U16,
+    FieldKind::U32,
+    FieldKind::U64,
+    FieldKind::F32,
+    FieldKind::F64,
+    FieldKind::Ptr,
+];
+
+/// Several fields sharing the same bytes.
+pub struct UnionField {
+    id: FieldId,
+    state: NamedState,
+    size: usize,
+    alternatives: RefCell<Vec<Box<dyn Field>>>,
+}
+
+impl UnionField {
+    pub fn new(name: String, size: usize) -> Self {
+        Self::with_alternatives(name, size, vec![])
+    }
+
+    pub fn with_alternatives(name: String, size: usize, alternatives: Vec<Box<dyn Field>>) -> Self {
+        Self {
+            id: next_id(),
+            state: NamedState::new(name),
+            size,
+            alternatives: alternatives.into(),
+        }
+    }
+
+    fn show_header(&self, ui: &mut Ui, ctx: &mut InspectionContext) {
+        let mut job = LayoutJob::default();
+        display_field_prelude(ui.ctx(), self, ctx, &mut job);
+
+        if ui.add(Label::new(job).sense(Sense::click())).clicked() {
+            ctx.select(self.id);
+        }
+
+        display_field_name(self, ui, ctx, &self.state, Color32::LIGHT_BLUE);
+
+        let mut job = LayoutJob::default();
+        job.append(
+            &format!(
+                "union[{}] {{{}}}",
+                self.size,
+                self.alternatives.borrow().len()
+            ),
+            4.,
+            create_text_format(ctx.is_selected(self.id), Color32::LIGHT_GRAY),
+        );
+
+        let popup_id = ctx.current_id.with("_alternatives");
+        let r = ui
+            .add(Label::new(job).sense(Sense::click()))
+            .on_hover_text("Right click to add or remove alternatives");
+        if r.secondary_clicked() && !ctx.imported() {
+            ui.memory_mut(|m| m.toggle_popup(popup_id));
+        } else if r.clicked() {
+            ctx.select(self.id);
+        }
+
+        popup_below_widget(ui, popup_id, &r, |ui| {
+            ui.set_width(120.);
+            ui.vertical_centered_justified(|ui| {
+                let alternatives = &mut *self.alternatives.borrow_mut();
+
+                for kind in ALTERNATIVE_KINDS.iter().filter(|k| k.size() <= self.size) {
+                    if ui.button(format!("+ {kind:?}")).clicked() {
+                        let name = format!("alt{}", alternatives.len());
+                        alternatives.push(kind.into_field(Some(name)));
+                    }
+                }
+
+                ui.separator();
+                let mut removed = None;
+                for (i, alt) in alternatives.iter().enumerate() {
+                    if ui
+                        .button(format!("- {}", alt.name().unwrap_or_default()))
+                        .clicked()
+                    {
+                        removed = Some(i);
+                    }
+                }
+
+                if let Some(i) = removed {
+                    alternatives.remove(i);
+                }
+            });
+        });
+
+        display_field_comment(self, ui, ctx);
+    }
+
+    fn show_body(&self, ui: &mut Ui, ctx: &mut InspectionContext) -> Option<FieldResponse> {
+        // Alternatives aren't part of any class, so they can't be selected.
+        let mut inner_ctx = InspectionContext {
+            class_list: ctx.class_list,
+            parent_id: ctx.current_id,
+            selection: ctx.selection,
+            current_container: ctx.current_container,
+            current_id: ctx.current_id,
+            process: ctx.process,
+            toasts: ctx.toasts,
+            edits: ctx.edits,
+            nodes: ctx.nodes,
+            level_rng: ctx.level_rng,
+            offset: ctx.offset,
+            address: ctx.address,
+        };
+
+        let mut response = None;
+        for (i, alt) in self.alternatives.borrow().iter().enumerate() {
+            inner_ctx.current_id = ctx.current_id.with(i);
+            inner_ctx.offset = ctx.offset;
+            response = response.or(alt.draw(ui, &mut inner_ctx));
+        }
+
+        response
+    }
+}
+
+impl Field for UnionField {
+    fn id(&self) -> FieldId {
+        self.id
+    }
+
+    fn name(&self) -> Option<String> {
+        Some(self.state.name.borrow().clone())
+    }
+
+    fn rename(&self, id: FieldId, name: &str) -> Option<String> {
+        self.state.rename(self.id, id, name).or_else(|| {
+            self.alternatives
+                .borrow()
+                .iter()
+                .find_map(|alt| alt.rename(id, name))
+        })
+    }
+
+    fn size(&self) -> usize {
+        self.size
+    }
+
+    fn kind(&self) -> FieldKind {
+        FieldKind::Union(self.size)
+    }
+
+    fn draw(&self, ui: &mut Ui, ctx: &mut InspectionContext) -> Option<FieldResponse> {
+        let state = ctx.nodes.load(ui.ctx(), ctx.current_id, true);
+        let body = state
+            .show_header(ui, |ui| self.show_header(ui, ctx))
+            .body(|ui| self.show_body(ui, ctx))
+            .2;
+
+        ctx.offset += self.size;
+        body.and_then(|inner| inner.inner)
+    }
+
+    fn codegen(&self, generator: &mut dyn Generator, data: &CodegenData) {
+        let alternatives = self.alternatives.borrow();
+        if alternatives.is_empty() {
+            generator.add_offset(self.size);
+            return;
+        }
+
+        generator.begin_union(self.state.name.borrow().as_str(), self.size);
+        for alt in alternatives.iter() {
+            alt.codegen(generator, data);
+        }
+        generator.end_union();
+    }
+}
+
+/// Temporary interpretation of the field's bytes as another kind, never saved.
+pub struct Overlay {
+    pub field_id: FieldId,
+    pub field: Box<dyn Field>,
+}
+
+/// Draws overlays of the `field` right under it, must be called after the field was drawn.
+pub fn draw_overlays(
+    overlays: &[Overlay],
+    field: &dyn Field,
+    ui: &mut Ui,
+    ctx: &mut InspectionContext,
+) -> Option<FieldResponse> {
+    let mut response = None;
+
+    let mut inner_ctx = InspectionContext {
+        class_list: ctx.class_list,
+        parent_id: ctx.current_id,
+        selection: ctx.selection,
+        current_container: ctx.current_container,
+        current_id: ctx.current_id,
+        process: ctx.process,
+        toasts: ctx.toasts,
+        edits: ctx.edits,
+        nodes: ctx.nodes,
+        level_rng: ctx.level_rng,
+        offset: 0,
+        address: ctx.address,
+    };
+
+    for (i, overlay) in overlays
+        .iter()
+        .filter(|o| o.field_id == field.id())
+        .enumerate()
+    {
+        inner_ctx.current_id = ctx.current_id.with("_overlay").with(i);
+        inner_ctx.offset = ctx.offset - field.size();
+
+        response = response.or(overlay.field.draw(ui, &mut inner_ctx));
+    }
+
+    response
+}
@@ -1,11 +1,16 @@
-use super::{create_text_format, EditingState, Field, HexField, NamedState};
-use crate::{app::is_valid_ident, context::InspectionContext, FID_M};
+use super::{
+    create_text_format, draw_overlays, EditingState, Field, FieldResponse, HexField, NamedState,
+};
+use crate::{app::is_valid_ident, class::Class, context::InspectionContext, history::Edit, FID_M};
 use eframe::{
-    egui::{Context, FontSelection, Key, Label, Modifiers, Sense, TextEdit, Ui},
+    egui::{Context, FontSelection, Key, Label, Modifiers, RichText, Sense, TextEdit, Ui},
     epaint::{text::LayoutJob, Color32, Stroke},
 };
 use std::fmt::Display;
 
+/// Shown when a value is edited while the process is read-only.
+pub const WRITE_REFUSED: &str = "The process is read-only, values can't be written";
+
 pub fn display_field_prelude(
     egui_ctx: &Context,
     field: &dyn Field,
@@ -42,6 +47,14 @@ pub fn display_field_prelude(
         8.,
         create_text_format(ctx.is_selected(field.id()), Color32::LIGHT_GREEN),
     );
+
+    if ctx.process.is_frozen(ctx.address + ctx.offset) {
+        job.append(
+            "🔒",
+            4.,
+            create_text_format(ctx.is_selected(field.id()), Color32::LIGHT_BLUE),
+        );
+    }
 }
 
 pub fn display_field_value<T: Display>(
@@ -83,7 +96,10 @@ pub fn display_field_value<T: Display>(
             if r.clicked_elsewhere() {
                 *editing_value = None;
             } else if r.lost_focus() {
-                if !write_new_value(buf) {
+                if ctx.process.is_read_only() {
+                    ctx.toasts.error(WRITE_REFUSED);
+                    *editing_value = None;
+                } else if !write_new_value(buf) {
                     ctx.toasts.error("Invalid value");
                     *should_focus = true;
                 } else {
@@ -103,7 +119,9 @@ pub fn display_field_value<T: Display>(
     );
 
     let r = ui.add(Label::new(job).sense(Sense::click()));
-    if r.secondary_clicked() {
+    if r.secondary_clicked() && ctx.process.is_read_only() {
+        ctx.toasts.error(WRITE_REFUSED);
+    } else if r.secondary_clicked() {
         *editing_value = Some(EditingState::new(
             ctx.address + ctx.offset,
             displayed_value(true).to_string(),
@@ -159,6 +177,15 @@ pub fn display_field_name(
                 state.focused_id.set(Some(ctx.current_id));
             } else {
                 state.renaming_id.set(None);
+                // Name goes back until the history applies the rename, so it can be undone.
+                let new = std::mem::replace(name, state.saved_name.take());
+                if new != *name {
+                    ctx.edits.push(Edit::RenameField {
+                        class: ctx.current_container,
+                        field: field.id(),
+                        name: new,
+                    });
+                }
             }
         }
     } else {
@@ -170,7 +197,7 @@ pub fn display_field_name(
         );
 
         let r = ui.add(Label::new(job).sense(Sense::click()));
-        if r.secondary_clicked() {
+        if r.secondary_clicked() && !ctx.imported() {
             *state.saved_name.borrow_mut() = state.name.borrow().clone();
             state.renaming_id.set(Some(ctx.current_id));
             state.focused_id.set(Some(ctx.current_id));
@@ -180,6 +207,28 @@ pub fn display_field_name(
     }
 }
 
+/// Draws the first line of the field's comment dimmed at the end of the row,
+/// the whole comment is shown on hover.
+pub fn display_field_comment(field: &dyn Field, ui: &mut Ui, ctx: &InspectionContext) {
+    let Some(comment) = ctx
+        .class_list
+        .by_id(ctx.current_container)
+        .and_then(|c| c.field_comments.get(&field.id()))
+    else {
+        return;
+    };
+
+    let first = comment.lines().next().unwrap_or_default();
+    let mut text = format!("// {}", first.chars().take(60).collect::<String>());
+    if first.chars().count() > 60 || comment.lines().nth(1).is_some() {
+        text += "...";
+    }
+
+    ui.add_space(8.);
+    ui.label(RichText::new(text).color(Color32::GRAY).font(FID_M))
+        .on_hover_text(comment);
+}
+
 pub fn allocate_padding(mut n: usize) -> Vec<Box<dyn Field>> {
     let mut fields = vec![];
 
@@ -205,3 +254,99 @@ pub fn allocate_padding(mut n: usize) -> Vec<Box<dyn Field>> {
 
     fields
 }
+
+/// Parses text typed into the edit box. Quoted text is unescaped the same way
+/// it was displayed(i.e. `{:?}`), anything else is taken as is.
+pub fn parse_text_value(text: &str) -> Option<String> {
+    let Some(inner) = text.strip_prefix('"').and_then(|t| t.strip_suffix('"')) else {
+        return Some(text.to_owned());
+    };
+
+    let mut out = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+
+        match chars.next()? {
+            'n' => out.push('\n'),
+            'r' => out.push('\r'),
+            't' => out.push('\t'),
+            '0' => out.push('\0'),
+            '\\' => out.push('\\'),
+            '"' => out.push('"'),
+            '\'' => out.push('\''),
+            'u' => {
+                let code = chars.as_str().strip_prefix('{')?;
+                let end = code.find('}')?;
+                out.push(char::from_u32(u32::from_str_radix(&code[..end], 16).ok()?)?);
+                chars = code[end + 1..].chars();
+            }
+            _ => return None,
+        }
+    }
+
+    Some(out)
+}
+
+/// Draws fields inherited from the base classes of `class` under a collapsed header,
+/// own fields of the class start right after them.
+pub fn draw_base_fields(
+    class: &Class,
+    ui: &mut Ui,
+    ctx: &mut InspectionContext,
+) -> Option<FieldResponse> {
+    let base = class.base().and_then(|id| ctx.class_list.by_id(id))?;
+    let id = ctx.current_id.with("_base").with(base.id());
+    let size = ctx.class_list.class_size(base.id());
+
+    // Inherited fields belong to the base class, so they are selected and edited there.
+    let mut inner_ctx = InspectionContext {
+        class_list: ctx.class_list,
+        parent_id: ctx.current_id,
+        selection: ctx.selection,
+        current_container: base.id(),
+        current_id: id,
+        process: ctx.process,
+        toasts: ctx.toasts,
+        edits: ctx.edits,
+        nodes: ctx.nodes,
+        level_rng: ctx.level_rng,
+        offset: ctx.offset,
+        address: ctx.address,
+    };
+
+    let response = ctx
+        .nodes
+        .load(ui.ctx(), id, false)
+        .show_header(ui, |ui| {
+            ui.label(
+                RichText::new(format!("{} [{size}]", base.name))
+                    .color(Color32::LIGHT_GRAY)
+                    .font(FID_M),
+            )
+        })
+        .body(|ui| {
+            let mut response = draw_base_fields(base, ui, &mut inner_ctx);
+            for (i, field) in base.fields.iter().enumerate() {
+                inner_ctx.current_id = id.with(i);
+                response = response
+                    .or(field.draw(ui, &mut inner_ctx))
+                    .or(draw_overlays(
+                        &base.overlays,
+                        field.as_ref(),
+                        ui,
+                        &mut inner_ctx,
+                    ));
+            }
+            response
+        })
+        .2
+        .and_then(|r| r.inner);
+
+    ctx.selection = inner_ctx.selection;
+    ctx.offset += size;
+    response
+}
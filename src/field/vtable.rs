@@ -0,0 +1,280 @@
+use super::{
+    container::read_usize, create_text_format, display_field_comment, display_field_name,
+    display_field_prelude, display_field_value, next_id, CodegenData, Field, FieldId, FieldKind,
+    FieldResponse, NamedState,
+};
+use crate::{app::is_valid_ident, context::InspectionContext, generator::Generator, history::Edit};
+use eframe::{
+    egui::{popup_below_widget, DragValue, Label, Sense, Ui},
+    epaint::{text::LayoutJob, Color32},
+};
+use serde::{Deserialize, Serialize};
+use std::cell::{Cell, RefCell};
+
+/// Maximum amount of slots detected or displayed.
+const MAX_SLOTS: usize = 512;
+
+/// Function in the virtual table as stored in projects and passed to generators.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VTableSlot {
+    pub name: String,
+    /// C-like function type, i.e. `void(int, float)`.
+    pub signature: String,
+}
+
+impl VTableSlot {
+    pub fn new(index: usize) -> Self {
+        Self {
+            name: format!("vfunc{index}"),
+            signature: "void()".into(),
+        }
+    }
+
+    /// Splits signature into return type and arguments.
+    pub fn split_signature(&self) -> (&str, &str) {
+        match self.signature.split_once('(') {
+            Some((ret, args)) => (ret.trim(), args.trim_end().trim_end_matches(')')),
+            None => (self.signature.trim(), ""),
+        }
+    }
+}
+
+struct Slot {
+    state: NamedState,
+    signature: RefCell<String>,
+}
+
+impl Slot {
+    fn new(slot: VTableSlot) -> Self {
+        Self {
+            state: NamedState::new(slot.name),
+            signature: slot.signature.into(),
+        }
+    }
+}
+
+/// Pointer to the virtual table, its slots are displayed as the body.
+pub struct VTableField {
+    id: FieldId,
+    state: NamedState,
+    slots: RefCell<Vec<Slot>>,
+    // Slots are detected once when the table is first seen.
+    detected: Cell<bool>,
+}
+
+impl VTableField {
+    pub fn new(name: String) -> Self {
+        Self {
+            id: next_id(),
+            state: NamedState::new(name),
+            slots: RefCell::default(),
+            detected: Cell::new(false),
+        }
+    }
+
+    pub fn with_slots(name: String, slots: Vec<VTableSlot>) -> Self {
+        Self {
+            detected: Cell::new(!slots.is_empty()),
+            slots: RefCell::new(slots.into_iter().map(Slot::new).collect()),
+            ..Self::new(name)
+        }
+    }
+
+    fn resize(&self, len: usize) {
+        let slots = &mut *self.slots.borrow_mut();
+        while slots.len() < len {
+            slots.push(Slot::new(VTableSlot::new(slots.len())));
+        }
+        slots.truncate(len);
+    }
+
+    /// Counts consecutive entries that point into readable memory.
+    fn detect(&self, ctx: &InspectionContext, table: usize) {
+        if self.detected.get() || !ctx.process.can_read(table) {
+            return;
+        }
+
+        let len = (0..MAX_SLOTS)
+            .take_while(|i| {
+                let target = read_usize(ctx.process, table + i * 8);
+                target != 0 && ctx.process.can_read(target)
+            })
+            .count();
+
+        self.resize(len.max(1));
+        self.detected.set(true);
+    }
+
+    fn show_header(&self, ui: &mut Ui, ctx: &mut InspectionContext, table: usize) {
+        let mut job = LayoutJob::default();
+        display_field_prelude(ui.ctx(), self, ctx, &mut job);
+
+        if ui.add(Label::new(job).sense(Sense::click())).clicked() {
+            ctx.select(self.id);
+        }
+
+        display_field_name(self, ui, ctx, &self.state, Color32::BROWN);
+
+        let mut job = LayoutJob::default();
+        job.append(
+            &format!("-> {table:X}"),
+            4.,
+            create_text_format(ctx.is_selected(self.id), Color32::YELLOW),
+        );
+        job.append(
+            &format!("[{} slots]", self.slots.borrow().len()),
+            4.,
+            create_text_format(ctx.is_selected(self.id), Color32::LIGHT_GRAY),
+        );
+
+        let popup_id = ctx.current_id.with("_slots");
+        let r = ui.add(Label::new(job).sense(Sense::click()));
+        if r.secondary_clicked() && !ctx.imported() {
+            ui.memory_mut(|m| m.toggle_popup(popup_id));
+        } else if r.clicked() {
+            ctx.select(self.id);
+        }
+
+        popup_below_widget(ui, popup_id, &r, |ui| {
+            ui.set_width(120.);
+            ui.horizontal(|ui| {
+                let mut len = self.slots.borrow().len();
+                ui.label("Slots");
+                if ui
+                    .add(DragValue::new(&mut len).clamp_range(1..=MAX_SLOTS))
+                    .changed()
+                {
+                    self.resize(len);
+                }
+            });
+        });
+
+        display_field_comment(self, ui, ctx);
+    }
+
+    fn show_body(&self, ui: &mut Ui, ctx: &mut InspectionContext, table: usize) {
+        // Slots aren't part of any class, so they can't be selected
+        // and their renames don't go through the history.
+        let mut renames = vec![];
+        let mut inner_ctx = InspectionContext {
+            class_list: ctx.class_list,
+            parent_id: ctx.current_id,
+            selection: ctx.selection,
+            current_container: ctx.current_container,
+            current_id: ctx.current_id,
+            process: ctx.process,
+            toasts: ctx.toasts,
+            edits: &mut renames,
+            nodes: ctx.nodes,
+            level_rng: ctx.level_rng,
+            offset: 0,
+            address: table,
+        };
+
+        for (i, slot) in self.slots.borrow().iter().enumerate() {
+            inner_ctx.current_id = ctx.current_id.with(i);
+            inner_ctx.offset = i * 8;
+            let target = read_usize(inner_ctx.process, table + i * 8);
+
+            ui.horizontal(|ui| {
+                let mut job = LayoutJob::default();
+                display_field_prelude(ui.ctx(), self, &mut inner_ctx, &mut job);
+                ui.label(job);
+
+                display_field_name(self, ui, &mut inner_ctx, &slot.state, Color32::LIGHT_BLUE);
+                if let Some(Edit::RenameField { name, .. }) = inner_ctx.edits.pop() {
+                    *slot.state.name.borrow_mut() = name;
+                }
+
+                let mut job = LayoutJob::default();
+                job.append(
+                    &format!("-> {target:X}"),
+                    4.,
+                    create_text_format(false, Color32::YELLOW),
+                );
+                if let Some(symbol) = inner_ctx.process.describe_address(target) {
+                    job.append(&symbol, 4., create_text_format(false, Color32::GRAY));
+                }
+                ui.label(job);
+
+                ui.add_space(4.);
+                display_field_value(
+                    self,
+                    ui,
+                    &mut inner_ctx,
+                    &slot.state,
+                    Color32::LIGHT_GRAY,
+                    |_| slot.signature.borrow().clone(),
+                    |new| {
+                        let valid = new
+                            .split_once('(')
+                            .map(|(ret, args)| !ret.trim().is_empty() && args.ends_with(')'))
+                            .unwrap_or(false);
+                        if valid {
+                            *slot.signature.borrow_mut() = new.trim().to_owned();
+                        }
+                        valid
+                    },
+                );
+            });
+        }
+    }
+}
+
+impl Field for VTableField {
+    fn id(&self) -> FieldId {
+        self.id
+    }
+
+    fn name(&self) -> Option<String> {
+        Some(self.state.name.borrow().clone())
+    }
+
+    fn rename(&self, id: FieldId, name: &str) -> Option<String> {
+        self.state.rename(self.id, id, name)
+    }
+
+    fn size(&self) -> usize {
+        8
+    }
+
+    fn kind(&self) -> FieldKind {
+        FieldKind::VTable
+    }
+
+    fn draw(&self, ui: &mut Ui, ctx: &mut InspectionContext) -> Option<FieldResponse> {
+        let table = read_usize(ctx.process, ctx.address + ctx.offset);
+        self.detect(ctx, table);
+
+        let state = ctx.nodes.load(ui.ctx(), ctx.current_id, false);
+        state
+            .show_header(ui, |ui| self.show_header(ui, ctx, table))
+            .body(|ui| self.show_body(ui, ctx, table));
+
+        ctx.offset += self.size();
+        None
+    }
+
+    fn codegen(&self, generator: &mut dyn Generator, _: &CodegenData) {
+        let slots = self
+            .slots
+            .borrow()
+            .iter()
+            .enumerate()
+            .map(|(i, slot)| {
+                let name = slot.state.name.borrow();
+                VTableSlot {
+                    // Slot might be in the middle of renaming.
+                    name: if is_valid_ident(&name) {
+                        name.clone()
+                    } else {
+                        VTableSlot::new(i).name
+                    },
+                    signature: slot.signature.borrow().clone(),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        generator.add_vtable(self.state.name.borrow().as_str(), &slots);
+    }
+}
@@ -0,0 +1,39 @@
+/// This module contains the thread that keeps frozen values in place.
+use crate::process::Process;
+use parking_lot::RwLock;
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+/// Default interval between rewrites in milliseconds.
+pub const DEFAULT_FREEZE_INTERVAL: u64 = 100;
+
+/// Value that is written to the same address over and over.
+pub struct FrozenValue {
+    pub address: usize,
+    pub bytes: Vec<u8>,
+    /// Where the value came from, i.e. `Player.health`.
+    pub name: String,
+}
+
+/// Starts the thread that rewrites frozen values of the attached process every
+/// `interval` milliseconds. It runs as long as the program does.
+pub fn spawn_freezer(process: Arc<RwLock<Option<Process>>>, interval: Arc<AtomicU64>) {
+    thread::spawn(move || loop {
+        thread::sleep(Duration::from_millis(
+            interval.load(Ordering::Relaxed).max(1),
+        ));
+
+        // Process is locked for writing only while attaching or detaching.
+        if let Some(process) = process.try_read() {
+            if let Some(process) = process.as_ref() {
+                process.rewrite_frozen();
+            }
+        }
+    });
+}
@@ -1,32 +1,79 @@
-use super::Generator;
-use crate::field::FieldKind;
-use std::{borrow::Cow, mem::take};
+use super::{format_comment, numeric_comment, BaseClass, ElementType, Generator};
+use crate::field::{chain_expression, FieldKind, TextEncoding, VTableSlot, ELEMENT_KINDS};
+use std::{borrow::Cow, collections::BTreeSet, mem::take};
 
 pub struct CppGenerator {
+    includes: BTreeSet<&'static str>,
     predecls: String,
     main: String,
     offset: usize,
     last_offset: usize,
+    // Name and size of the union members are currently added to.
+    union: Option<(String, usize)>,
+    // Comment of the next class or member.
+    comment: Option<String>,
 }
 
 impl Default for CppGenerator {
     fn default() -> Self {
         Self {
-            predecls: format!(
-                "// Generated by YClass {}\n// Made by @ItsEthra\n\n#include <cstdint>\n\n",
-                env!("YCLASS_VERSION")
-            ),
+            includes: BTreeSet::from(["cstdint"]),
+            predecls: "".to_owned(),
             main: "".to_owned(),
             offset: 0,
             last_offset: 0,
+            union: None,
+            comment: None,
         }
     }
 }
 
+impl CppGenerator {
+    fn add_padding(&mut self) {
+        if self.offset != self.last_offset {
+            self.main += &format!(
+                "    char _pad0x{:x}[0x{:x}];\n",
+                self.offset,
+                self.offset - self.last_offset
+            );
+        }
+    }
+
+    fn add_member(&mut self, line: &str, size: usize) {
+        // Union alternatives all start at the same offset.
+        if self.union.is_some() {
+            if let Some(comment) = self.comment.take() {
+                self.main += &format_comment(&comment, "        //");
+            }
+            self.main += &format!("        {}\n", line.replace('\n', "\n    "));
+            return;
+        }
+
+        self.add_padding();
+        if let Some(comment) = self.comment.take() {
+            self.main += &format_comment(&comment, "    //");
+        }
+        self.main += &format!("    {line}\n");
+
+        self.offset += size;
+        self.last_offset = self.offset;
+    }
+}
+
 impl Generator for CppGenerator {
-    fn begin_class(&mut self, name: &str) {
+    fn begin_class(&mut self, name: &str, base: Option<BaseClass>) {
         self.predecls += &format!("class {name};\n");
-        self.main += &format!("class {name} {{\npublic:\n");
+        if let Some(comment) = self.comment.take() {
+            self.main += &format_comment(&comment, "//");
+        }
+
+        if let Some(base) = base {
+            self.main += &format!("class {name} : public {} {{\npublic:\n", base.name);
+            self.offset = base.size;
+            self.last_offset = base.size;
+        } else {
+            self.main += &format!("class {name} {{\npublic:\n");
+        }
     }
 
     fn end_class(&mut self) {
@@ -36,27 +83,143 @@ impl Generator for CppGenerator {
     }
 
     fn add_field(&mut self, name: &str, kind: FieldKind, metadata: Option<&str>) {
-        let size = kind.size();
-        if self.offset != self.last_offset {
-            self.main += &format!(
-                "    char _pad0x{:x}[0x{:x}];\n",
-                self.offset,
-                self.offset - self.last_offset
+        let line = match kind {
+            FieldKind::Text(_, len) => format!("{} {name}[{len}];", kind_to_type(kind, metadata)),
+            FieldKind::StdString => {
+                self.includes.insert("string");
+                format!("std::string {name};")
+            }
+            FieldKind::RustString => format!("uintptr_t {name}[3]; // String"),
+            FieldKind::RelPtr32(to) | FieldKind::RelPtr64(to) => format!(
+                "int{}_t {name}; // {}* relative to {}",
+                kind.size() * 8,
+                metadata.unwrap_or("void"),
+                to.label()
+            ),
+            FieldKind::Guid => format!("uint8_t {name}[16]; // GUID"),
+            FieldKind::Uuid => format!("uint8_t {name}[16]; // UUID"),
+            FieldKind::UnixTime(unit) => {
+                format!("int64_t {name}; // Unix time, {}", unit.label())
+            }
+            FieldKind::Timespec => {
+                self.includes.insert("ctime");
+                format!("timespec {name};")
+            }
+            FieldKind::Timeval => {
+                self.includes.insert("sys/time.h");
+                format!("timeval {name};")
+            }
+            FieldKind::Ipv4 => format!("uint8_t {name}[4]; // IPv4"),
+            FieldKind::Ipv6 => format!("uint8_t {name}[16]; // IPv6"),
+            FieldKind::Port => format!("uint16_t {name}; // port, big endian"),
+            FieldKind::Rgba => format!("uint8_t {name}[4]; // RGBA"),
+            _ if kind.is_numeric() => format!(
+                "{} {name};{}",
+                kind_to_type(kind, None),
+                numeric_comment(kind, metadata, None)
+            ),
+            _ => format!("{} {name};", kind_to_type(kind, metadata)),
+        };
+
+        self.add_member(&line, kind.size());
+    }
+
+    fn add_container(&mut self, name: &str, kind: FieldKind, element: ElementType) {
+        let element = element_to_type(element);
+        let line = match kind {
+            FieldKind::StdVector => {
+                self.includes.insert("vector");
+                format!("std::vector<{element}> {name};")
+            }
+            FieldKind::StdList => {
+                self.includes.insert("list");
+                format!("std::list<{element}> {name};")
+            }
+            FieldKind::RustVec => format!("uintptr_t {name}[3]; // Vec<{element}>"),
+            FieldKind::RustList => format!("uintptr_t {name}[3]; // LinkedList<{element}>"),
+            FieldKind::IntrusiveList => format!("{element}* {name}; // Intrusive list"),
+            _ => unreachable!(),
+        };
+
+        self.add_member(&line, kind.size());
+    }
+
+    fn add_vtable(&mut self, name: &str, slots: &[VTableSlot]) {
+        // Only the primary vtable can be expressed with virtual methods,
+        // its pointer is added by the compiler.
+        if self.offset == 0 {
+            if let Some(comment) = self.comment.take() {
+                self.main += &format_comment(&comment, "    //");
+            }
+            for slot in slots {
+                let (ret, args) = slot.split_signature();
+                self.main += &format!("    virtual {ret} {}({args});\n", slot.name);
+            }
+
+            self.offset += FieldKind::VTable.size();
+            self.last_offset = self.offset;
+        } else {
+            let comment = slots
+                .iter()
+                .map(|s| s.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ");
+            self.add_member(
+                &format!("void** {name}; // vtable: {comment}"),
+                FieldKind::VTable.size(),
             );
         }
+    }
 
-        self.main += &format!("    {} {name};\n", kind_to_type(kind, metadata));
+    fn add_chain(&mut self, name: &str, offsets: &[usize], value: FieldKind) {
+        let this = format!("this+{:#X}", self.offset);
+        let line = format!(
+            "// {name}: {} -> {}\n    void* {name};",
+            chain_expression(&this, offsets),
+            kind_to_type(value, None)
+        );
 
-        self.offset += size;
-        self.last_offset = self.offset;
+        self.add_member(&line, FieldKind::Chain.size());
+    }
+
+    fn begin_union(&mut self, name: &str, size: usize) {
+        self.add_padding();
+        if let Some(comment) = self.comment.take() {
+            self.main += &format_comment(&comment, "    //");
+        }
+        self.main += "    union {\n";
+        self.union = Some((name.to_owned(), size));
+    }
+
+    fn end_union(&mut self) {
+        if let Some((name, size)) = self.union.take() {
+            self.main += &format!("    }} {name};\n");
+
+            self.offset += size;
+            self.last_offset = self.offset;
+        }
     }
 
     fn add_offset(&mut self, offset: usize) {
-        self.offset += offset;
+        if self.union.is_none() {
+            self.offset += offset;
+        }
+    }
+
+    fn add_comment(&mut self, comment: &str) {
+        self.comment = Some(comment.to_owned());
     }
 
     fn finilize(&mut self) -> String {
-        take(&mut self.predecls) + "\n" + &take(&mut self.main)
+        let mut header = format!(
+            "// Generated by YClass {}\n// Made by @ItsEthra\n\n",
+            env!("YCLASS_VERSION")
+        );
+        for include in take(&mut self.includes) {
+            header += &format!("#include <{include}>\n");
+        }
+
+        header + "\n" + &take(&mut self.predecls) + "\n" + &take(&mut self.main)
     }
 }
 
@@ -73,8 +236,103 @@ fn kind_to_type(kind: FieldKind, metadata: Option<&str>) -> Cow<'static, str> {
         FieldKind::U64 => "uint64_t".into(),
         FieldKind::F32 => "float".into(),
         FieldKind::F64 => "double".into(),
+        FieldKind::I128 => "__int128".into(),
+        FieldKind::U128 => "unsigned __int128".into(),
+        FieldKind::F16 => "_Float16".into(),
+        FieldKind::BF16 => "__bf16".into(),
+        FieldKind::Fixed32(_) => "int32_t".into(),
+        FieldKind::Fixed64(_) => "int64_t".into(),
         FieldKind::Ptr => format!("{}*", metadata.unwrap_or("void")).into(),
         FieldKind::StrPtr => "const char*".into(),
         FieldKind::Bool => "bool".into(),
+        FieldKind::Text(encoding, _) => char_type(encoding).into(),
+        FieldKind::TextPtr(encoding, _) => format!("const {}*", char_type(encoding)).into(),
+        FieldKind::StdString
+        | FieldKind::RustString
+        | FieldKind::StdVector
+        | FieldKind::RustVec
+        | FieldKind::StdList
+        | FieldKind::RustList
+        | FieldKind::IntrusiveList
+        | FieldKind::VTable
+        | FieldKind::Chain
+        | FieldKind::RelPtr32(_)
+        | FieldKind::RelPtr64(_)
+        | FieldKind::Union(_)
+        | FieldKind::Guid
+        | FieldKind::Uuid
+        | FieldKind::UnixTime(_)
+        | FieldKind::Timespec
+        | FieldKind::Timeval
+        | FieldKind::Ipv4
+        | FieldKind::Ipv6
+        | FieldKind::Port
+        | FieldKind::Rgba => unreachable!(),
+    }
+}
+
+fn element_to_type(element: ElementType) -> Cow<str> {
+    match element {
+        ElementType::Kind(FieldKind::Unk8) => "uint8_t".into(),
+        ElementType::Kind(FieldKind::Unk16) => "uint16_t".into(),
+        ElementType::Kind(FieldKind::Unk32) => "uint32_t".into(),
+        ElementType::Kind(FieldKind::Unk64) => "uint64_t".into(),
+        ElementType::Kind(kind) if ELEMENT_KINDS.contains(&kind) => kind_to_type(kind, None),
+        // Other kinds can't be elements, they're read as Unk64.
+        ElementType::Kind(_) => "uint64_t".into(),
+        ElementType::Class(name) => name.into(),
+    }
+}
+
+fn char_type(encoding: TextEncoding) -> &'static str {
+    match encoding {
+        TextEncoding::Utf8 => "char",
+        TextEncoding::Utf16 => "char16_t",
+        TextEncoding::Utf32 => "char32_t",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn comments_of_virtual_methods_and_union_members_are_emitted() {
+        let mut generator = CppGenerator::default();
+        generator.begin_class("Player", None);
+        generator.add_comment("Methods of the player");
+        generator.add_vtable("vtable", &[VTableSlot::new(0)]);
+        generator.begin_union("value", 4);
+        generator.add_comment("Walking speed");
+        generator.add_field("speed", FieldKind::F32, None);
+        generator.end_union();
+        generator.add_field("health", FieldKind::I32, None);
+        generator.end_class();
+
+        let code = generator.finilize();
+        assert!(
+            code.contains("    // Methods of the player\n    virtual"),
+            "{code}"
+        );
+        assert!(
+            code.contains("        // Walking speed\n        float speed;"),
+            "{code}"
+        );
+        assert!(code.contains("} value;\n    int32_t health;"), "{code}");
+    }
+
+    #[test]
+    fn elements_of_other_kinds_are_unk64() {
+        let mut generator = CppGenerator::default();
+        generator.begin_class("Player", None);
+        generator.add_container(
+            "items",
+            FieldKind::StdVector,
+            ElementType::Kind(FieldKind::Guid),
+        );
+        generator.end_class();
+
+        let code = generator.finilize();
+        assert!(code.contains("std::vector<uint64_t> items;"), "{code}");
     }
 }
@@ -1,4 +1,4 @@
-use crate::field::FieldKind;
+use crate::field::{ByteOrder, FieldKind, NumberFormat, VTableSlot};
 
 mod rust;
 pub use rust::*;
@@ -6,15 +6,67 @@ mod cpp;
 pub use cpp::*;
 
 pub trait Generator {
-    fn begin_class(&mut self, name: &str);
+    fn begin_class(&mut self, name: &str, base: Option<BaseClass>);
     fn end_class(&mut self);
 
     fn add_field(&mut self, name: &str, kind: FieldKind, metadata: Option<&str>);
+    fn add_container(&mut self, name: &str, kind: FieldKind, element: ElementType);
+    fn add_vtable(&mut self, name: &str, slots: &[VTableSlot]);
+    fn add_chain(&mut self, name: &str, offsets: &[usize], value: FieldKind);
+    /// Every field added until [`Generator::end_union`] is an alternative of the union.
+    fn begin_union(&mut self, name: &str, size: usize);
+    fn end_union(&mut self);
     fn add_offset(&mut self, offset: usize);
+    /// Comment of the class or the field that is added next.
+    fn add_comment(&mut self, comment: &str);
 
     fn finilize(&mut self) -> String;
 }
 
+/// Trailing comment of numeric fields for things their type can't express,
+/// i.e. ` // Q16.16, big endian`. Empty if there is nothing to note.
+fn numeric_comment(kind: FieldKind, metadata: Option<&str>, type_note: Option<&str>) -> String {
+    let mut notes = vec![];
+    notes.extend(type_note.map(str::to_owned));
+
+    if let FieldKind::Fixed32(frac) | FieldKind::Fixed64(frac) = kind {
+        notes.push(format!("Q{}.{frac}", kind.size() * 8 - frac as usize));
+    }
+
+    if NumberFormat::from_metadata(metadata).order == ByteOrder::Big {
+        notes.push("big endian".to_owned());
+    }
+
+    if notes.is_empty() {
+        String::new()
+    } else {
+        format!(" // {}", notes.join(", "))
+    }
+}
+
+/// Prefixes every line of `comment`, i.e. with `    ///`.
+fn format_comment(comment: &str, prefix: &str) -> String {
+    comment
+        .lines()
+        .map(|line| format!("{}\n", format!("{prefix} {line}").trim_end()))
+        .collect()
+}
+
+/// Class the generated class inherits from.
+#[derive(Debug, Clone, Copy)]
+pub struct BaseClass<'a> {
+    pub name: &'a str,
+    /// Size in bytes including its own base classes.
+    pub size: usize,
+}
+
+/// Type of the elements stored in a container field.
+#[derive(Debug, Clone, Copy)]
+pub enum ElementType<'a> {
+    Kind(FieldKind),
+    Class(&'a str),
+}
+
 #[derive(Debug, Default, Clone, Copy, PartialEq)]
 pub enum AvailableGenerator {
     #[default]
@@ -1,9 +1,16 @@
-use super::Generator;
-use crate::field::FieldKind;
+use super::{format_comment, numeric_comment, BaseClass, ElementType, Generator};
+use crate::field::{chain_expression, FieldKind, TextEncoding, VTableSlot, ELEMENT_KINDS};
 use std::borrow::Cow;
 
 pub struct RustGenerator {
     text: String,
+    class: String,
+    // Vtable structs and unions are emitted after the class that uses them.
+    types: String,
+    // Name, size and members of the union currently being generated.
+    union: Option<(String, usize, String)>,
+    // Comment of the next class or member.
+    comment: Option<String>,
     last_offset: usize,
     offset: usize,
 }
@@ -15,41 +22,167 @@ impl Default for RustGenerator {
                 "// Generated by YClass {}\n// Made by @ItsEthra\n\n",
                 env!("YCLASS_VERSION")
             ),
+            class: String::new(),
+            types: String::new(),
+            union: None,
+            comment: None,
             last_offset: 0,
             offset: 0,
         }
     }
 }
 
+impl RustGenerator {
+    fn add_member(&mut self, line: &str, size: usize) {
+        // Union alternatives all start at the same offset.
+        if let Some((_, _, members)) = &mut self.union {
+            *members += &format!("    {line}\n");
+            return;
+        }
+
+        if self.offset != self.last_offset {
+            self.text += &format!(
+                "    _pad_0x{:x}: [u8; 0x{:x}],\n",
+                self.offset,
+                self.offset - self.last_offset
+            );
+        }
+
+        if let Some(comment) = self.comment.take() {
+            self.text += &format_comment(&comment, "    ///");
+        }
+        self.text += &format!("    {line}\n");
+
+        self.offset += size;
+        self.last_offset = self.offset;
+    }
+}
+
 impl Generator for RustGenerator {
-    fn begin_class(&mut self, name: &str) {
+    fn begin_class(&mut self, name: &str, base: Option<BaseClass>) {
+        if let Some(comment) = self.comment.take() {
+            self.text += &format_comment(&comment, "///");
+        }
         self.text += &format!("#[repr(C)]\npub struct {name} {{\n");
+        self.class = name.to_owned();
+
+        if let Some(base) = base {
+            self.add_member(&format!("pub base: {},", base.name), base.size);
+        }
     }
 
     fn end_class(&mut self) {
         self.text += "}\n\n";
+        self.text += &std::mem::take(&mut self.types);
         self.offset = 0;
         self.last_offset = 0;
     }
 
     fn add_field(&mut self, name: &str, kind: FieldKind, metadata: Option<&str>) {
-        let size = kind.size();
-        if self.offset != self.last_offset {
-            self.text += &format!(
-                "    _pad_0x{:x}: [u8; 0x{:x}],\n",
-                self.offset,
-                self.offset - self.last_offset
-            );
+        let line = match kind {
+            FieldKind::StdString => format!("pub {name}: [usize; 4], // std::string"),
+            FieldKind::RelPtr32(to) | FieldKind::RelPtr64(to) => format!(
+                "pub {name}: i{}, // *const {} relative to {}",
+                kind.size() * 8,
+                metadata.unwrap_or("()"),
+                to.label()
+            ),
+            FieldKind::Guid => format!("pub {name}: [u8; 16], // GUID"),
+            FieldKind::Uuid => format!("pub {name}: [u8; 16], // UUID"),
+            FieldKind::UnixTime(unit) => {
+                format!("pub {name}: i64, // Unix time, {}", unit.label())
+            }
+            FieldKind::Timespec => format!("pub {name}: [i64; 2], // timespec"),
+            FieldKind::Timeval => format!("pub {name}: [i64; 2], // timeval"),
+            FieldKind::Ipv4 => format!("pub {name}: [u8; 4], // IPv4"),
+            FieldKind::Ipv6 => format!("pub {name}: [u8; 16], // IPv6"),
+            FieldKind::Port => format!("pub {name}: u16, // port, big endian"),
+            FieldKind::Rgba => format!("pub {name}: [u8; 4], // RGBA"),
+            _ if kind.is_numeric() => {
+                let note = match kind {
+                    FieldKind::F16 => Some("f16"),
+                    FieldKind::BF16 => Some("bf16"),
+                    _ => None,
+                };
+
+                format!(
+                    "pub {name}: {},{}",
+                    kind_to_type(kind, None),
+                    numeric_comment(kind, metadata, note)
+                )
+            }
+            _ => format!("pub {name}: {},", kind_to_type(kind, metadata)),
+        };
+
+        self.add_member(&line, kind.size());
+    }
+
+    fn add_container(&mut self, name: &str, kind: FieldKind, element: ElementType) {
+        let element = element_to_type(element);
+        let line = match kind {
+            FieldKind::RustVec => format!("pub {name}: Vec<{element}>,"),
+            FieldKind::RustList => {
+                format!("pub {name}: std::collections::LinkedList<{element}>,")
+            }
+            FieldKind::StdVector => {
+                format!("pub {name}: [*const {element}; 3], // std::vector<{element}>")
+            }
+            FieldKind::StdList => format!("pub {name}: [usize; 3], // std::list<{element}>"),
+            FieldKind::IntrusiveList => {
+                format!("pub {name}: Option<&'static {element}>, // Intrusive list")
+            }
+            _ => unreachable!(),
+        };
+
+        self.add_member(&line, kind.size());
+    }
+
+    fn add_vtable(&mut self, name: &str, slots: &[VTableSlot]) {
+        let vtable = format!("{}{}", self.class, capitalize(name));
+        self.types += &format!("#[repr(C)]\npub struct {vtable} {{\n");
+        for slot in slots {
+            self.types += &format!("    pub {}: usize, // {}\n", slot.name, slot.signature);
         }
+        self.types += "}\n\n";
+
+        self.add_member(
+            &format!("pub {name}: &'static {vtable},"),
+            FieldKind::VTable.size(),
+        );
+    }
 
-        self.text += &format!("    pub {name}: {},\n", kind_to_type(kind, metadata));
+    fn add_chain(&mut self, name: &str, offsets: &[usize], value: FieldKind) {
+        let this = format!("self+{:#X}", self.offset);
+        let line = format!(
+            "// {name}: {} -> {}\n    pub {name}: *const u8,",
+            chain_expression(&this, offsets),
+            kind_to_type(value, None)
+        );
 
-        self.offset += size;
-        self.last_offset = self.offset;
+        self.add_member(&line, FieldKind::Chain.size());
+    }
+
+    fn begin_union(&mut self, name: &str, size: usize) {
+        self.union = Some((name.to_owned(), size, String::new()));
+    }
+
+    fn end_union(&mut self) {
+        if let Some((name, size, members)) = self.union.take() {
+            let union = format!("{}{}", self.class, capitalize(&name));
+            self.types += &format!("#[repr(C)]\npub union {union} {{\n{members}}}\n\n");
+
+            self.add_member(&format!("pub {name}: {union},"), size);
+        }
     }
 
     fn add_offset(&mut self, offset: usize) {
-        self.offset += offset;
+        if self.union.is_none() {
+            self.offset += offset;
+        }
+    }
+
+    fn add_comment(&mut self, comment: &str) {
+        self.comment = Some(comment.to_owned());
     }
 
     fn finilize(&mut self) -> String {
@@ -70,8 +203,65 @@ fn kind_to_type(kind: FieldKind, metadata: Option<&str>) -> Cow<'static, str> {
         FieldKind::U64 => "u64".into(),
         FieldKind::F32 => "f32".into(),
         FieldKind::F64 => "f64".into(),
+        FieldKind::I128 => "i128".into(),
+        FieldKind::U128 => "u128".into(),
+        FieldKind::F16 | FieldKind::BF16 => "u16".into(),
+        FieldKind::Fixed32(_) => "i32".into(),
+        FieldKind::Fixed64(_) => "i64".into(),
         FieldKind::Ptr => format!("Option<&'static {}>", metadata.unwrap_or("()")).into(),
         FieldKind::StrPtr => "*const u8".into(),
         FieldKind::Bool => "bool".into(),
+        FieldKind::Text(encoding, len) => format!("[{}; {len}]", char_type(encoding)).into(),
+        FieldKind::TextPtr(encoding, _) => format!("*const {}", char_type(encoding)).into(),
+        FieldKind::RustString => "String".into(),
+        FieldKind::StdString
+        | FieldKind::StdVector
+        | FieldKind::RustVec
+        | FieldKind::StdList
+        | FieldKind::RustList
+        | FieldKind::IntrusiveList
+        | FieldKind::VTable
+        | FieldKind::Chain
+        | FieldKind::RelPtr32(_)
+        | FieldKind::RelPtr64(_)
+        | FieldKind::Union(_)
+        | FieldKind::Guid
+        | FieldKind::Uuid
+        | FieldKind::UnixTime(_)
+        | FieldKind::Timespec
+        | FieldKind::Timeval
+        | FieldKind::Ipv4
+        | FieldKind::Ipv6
+        | FieldKind::Port
+        | FieldKind::Rgba => unreachable!(),
     }
 }
+
+fn element_to_type(element: ElementType) -> Cow<str> {
+    match element {
+        ElementType::Kind(FieldKind::Unk8) => "u8".into(),
+        ElementType::Kind(FieldKind::Unk16) => "u16".into(),
+        ElementType::Kind(FieldKind::Unk32) => "u32".into(),
+        ElementType::Kind(FieldKind::Unk64) => "u64".into(),
+        ElementType::Kind(kind) if ELEMENT_KINDS.contains(&kind) => kind_to_type(kind, None),
+        // Other kinds can't be elements, they're read as Unk64.
+        ElementType::Kind(_) => "u64".into(),
+        ElementType::Class(name) => name.into(),
+    }
+}
+
+fn char_type(encoding: TextEncoding) -> &'static str {
+    match encoding {
+        TextEncoding::Utf8 => "u8",
+        TextEncoding::Utf16 => "u16",
+        TextEncoding::Utf32 => "u32",
+    }
+}
+
+fn capitalize(name: &str) -> String {
+    let mut chars = name.trim_start_matches('_').chars();
+    chars
+        .next()
+        .map(|c| c.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
@@ -1,10 +1,11 @@
 use crate::{
     app::is_valid_ident,
     class::{Class, ClassId},
+    history::Edit,
     state::StateRef,
 };
 use eframe::{
-    egui::{Button, Context, Key, ScrollArea, SelectableLabel, SidePanel, TextEdit},
+    egui::{Button, Context, Key, RichText, ScrollArea, SelectableLabel, SidePanel, TextEdit},
     epaint::vec2,
 };
 use std::mem::take;
@@ -12,6 +13,8 @@ use std::mem::take;
 enum RequestedAction {
     Delete(ClassId),
     ToggleSelection(ClassId),
+    SetBase(ClassId, Option<ClassId>),
+    Rename(ClassId, String),
 }
 
 struct ClassEditState {
@@ -62,7 +65,16 @@ impl ClassListPanel {
             ui.horizontal_top(|ui| {
                 let size = vec2(ui.available_width(), 18.);
                 if ui.add_sized(size, Button::new("Remove empty")).clicked() {
-                    state.class_list.remove_empty();
+                    let edit = Edit::Group(
+                        state
+                            .class_list
+                            .empty_classes()
+                            .into_iter()
+                            .map(Edit::RemoveClass)
+                            .collect(),
+                    );
+                    state.history.apply(&mut state.class_list, edit);
+                    state.selection = None;
                 }
             });
 
@@ -87,7 +99,11 @@ impl ClassListPanel {
                     state.toasts.error("Not a valid class name");
                     self.should_focus_edit = true;
                 } else {
-                    state.class_list.add_class(take(&mut self.new_class_buf));
+                    let class = Class::new(fastrand::usize(..), take(&mut self.new_class_buf));
+                    let index = state.class_list.classes().len();
+                    state
+                        .history
+                        .apply(&mut state.class_list, Edit::InsertClass { index, class });
                     state.dummy = false;
                 }
             }
@@ -113,6 +129,13 @@ impl ClassListPanel {
                         }
                     }
 
+                    let names = state
+                        .class_list
+                        .classes()
+                        .iter()
+                        .map(|c| (c.id(), c.name.clone()))
+                        .collect::<Vec<_>>();
+
                     for class in state.class_list.classes_mut() {
                         if let Some((edit_buf, request_focus)) =
                             self.edit_state.as_mut().and_then(|s| map_state(class, s))
@@ -138,17 +161,23 @@ impl ClassListPanel {
                                     state.toasts.error("Not a valid class name");
                                     *request_focus = true;
                                 } else {
-                                    class.name = take(edit_buf);
+                                    action =
+                                        Some(RequestedAction::Rename(class.id(), take(edit_buf)));
                                     self.edit_state = None;
                                     state.dummy = false;
                                 }
                             }
                         } else {
+                            // Imported classes are dimmed.
+                            let mut text = RichText::new(&class.name);
+                            if class.library.is_some() {
+                                text = text.weak();
+                            }
                             let r = ui.add_sized(
                                 vec2(ui.available_width(), 24.),
                                 SelectableLabel::new(
                                     selected.map(|j| class.id() == j).unwrap_or_default(),
-                                    &class.name,
+                                    text,
                                 ),
                             );
 
@@ -156,8 +185,29 @@ impl ClassListPanel {
                                 action = Some(RequestedAction::ToggleSelection(class.id()));
                             }
 
+                            let hover = class
+                                .library
+                                .as_ref()
+                                .map(|library| format!("Imported from {library}, read-only"))
+                                .into_iter()
+                                .chain(
+                                    class
+                                        .base()
+                                        .and_then(|b| names.iter().find(|(id, _)| *id == b))
+                                        .map(|(_, base)| format!("Inherits {base}")),
+                                )
+                                .chain(Some(class.comment.clone()).filter(|c| !c.is_empty()))
+                                .collect::<Vec<_>>();
+                            let r = if hover.is_empty() {
+                                r
+                            } else {
+                                r.on_hover_text(hover.join("\n\n"))
+                            };
+
+                            let imported = class.library.is_some();
                             r.context_menu(|ui| {
                                 ui.set_width(80.);
+                                ui.set_enabled(!imported);
 
                                 ui.vertical_centered_justified(|ui| {
                                     if ui.button("Rename").clicked() {
@@ -176,12 +226,73 @@ impl ClassListPanel {
                                         action = Some(RequestedAction::Delete(class.id()));
                                     }
                                 });
+
+                                ui.menu_button("Comment", |ui| {
+                                    if ui
+                                        .add(
+                                            TextEdit::multiline(&mut class.comment)
+                                                .desired_rows(4)
+                                                .hint_text("Notes about the class"),
+                                        )
+                                        .changed()
+                                    {
+                                        state.dummy = false;
+                                    }
+                                });
+
+                                ui.menu_button("Base class", |ui| {
+                                    if ui.radio(class.base().is_none(), "None").clicked() {
+                                        ui.close_menu();
+                                        action = Some(RequestedAction::SetBase(class.id(), None));
+                                    }
+
+                                    for (id, name) in
+                                        names.iter().filter(|(id, _)| *id != class.id())
+                                    {
+                                        if ui.radio(class.base() == Some(*id), name).clicked() {
+                                            ui.close_menu();
+                                            action = Some(RequestedAction::SetBase(
+                                                class.id(),
+                                                Some(*id),
+                                            ));
+                                        }
+                                    }
+                                });
                             });
                         }
                     }
 
                     match action.take()? {
-                        RequestedAction::Delete(cid) => state.class_list.delete_by_id(cid),
+                        RequestedAction::Delete(cid) => {
+                            state
+                                .history
+                                .apply(&mut state.class_list, Edit::RemoveClass(cid));
+                            state.selection = None;
+                            state.dummy = false;
+                        }
+                        RequestedAction::Rename(class, name) => {
+                            if state
+                                .class_list
+                                .by_name(&name)
+                                .is_some_and(|c| c.id() != class)
+                            {
+                                state
+                                    .toasts
+                                    .error("Class with the same name already exists");
+                            } else {
+                                let edit = Edit::RenameClass { class, name };
+                                state.history.apply(&mut state.class_list, edit);
+                            }
+                        }
+                        RequestedAction::SetBase(class, base) => {
+                            if state.class_list.can_inherit(class, base) {
+                                let edit = Edit::SetBase { class, base };
+                                state.history.apply(&mut state.class_list, edit);
+                                state.dummy = false;
+                            } else {
+                                state.toasts.error("Class can't inherit itself");
+                            }
+                        }
                         RequestedAction::ToggleSelection(cid) => {
                             let selected = state.class_list.selected_mut();
                             if *selected == Some(cid) {
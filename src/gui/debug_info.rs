@@ -0,0 +1,197 @@
+use crate::{project::dwarf::DebugInfo, state::StateRef};
+use eframe::egui::{Button, Context, RichText, ScrollArea, TextEdit, Window};
+use std::collections::HashSet;
+
+/// Lets the user pick types and global variables of debug info to import.
+pub struct DebugInfoWindow {
+    state: StateRef,
+    info: Option<DebugInfo>,
+    /// File the debug info was read from.
+    source: String,
+    /// Added to addresses of global variables,
+    /// `None` if the file isn't loaded in the attached process.
+    bias: Option<usize>,
+    filter: String,
+    show_globals: bool,
+    types: HashSet<String>,
+    globals: HashSet<usize>,
+}
+
+impl DebugInfoWindow {
+    pub fn new(state: StateRef) -> Self {
+        Self {
+            state,
+            info: None,
+            source: String::new(),
+            bias: None,
+            filter: String::new(),
+            show_globals: false,
+            types: HashSet::new(),
+            globals: HashSet::new(),
+        }
+    }
+
+    pub fn open(&mut self, info: DebugInfo, source: String, bias: Option<usize>) {
+        *self = Self {
+            info: Some(info),
+            source,
+            bias,
+            ..Self::new(self.state)
+        };
+    }
+
+    pub fn show(&mut self, ctx: &Context) {
+        let Some(info) = self.info.as_ref() else {
+            return;
+        };
+
+        let mut open = true;
+        let mut import = false;
+        Window::new("Import debug info")
+            .open(&mut open)
+            .default_width(360.)
+            .show(ctx, |ui| {
+                ui.label(format!("Read from {}", self.source));
+                ui.add_space(4.);
+
+                ui.horizontal(|ui| {
+                    ui.selectable_value(
+                        &mut self.show_globals,
+                        false,
+                        format!("Types ({})", info.types.len()),
+                    );
+                    ui.selectable_value(
+                        &mut self.show_globals,
+                        true,
+                        format!("Global variables ({})", info.globals.len()),
+                    );
+                });
+                TextEdit::singleline(&mut self.filter)
+                    .desired_width(f32::INFINITY)
+                    .hint_text("Filter by name")
+                    .show(ui);
+                ui.add_space(4.);
+
+                let filter = self.filter.to_lowercase();
+                let matches = |name: &str| name.to_lowercase().contains(&filter);
+                let row_height = ui.spacing().interact_size.y;
+
+                if self.show_globals {
+                    if self.bias.is_none() {
+                        ui.label(
+                            RichText::new(
+                                "Not loaded in the attached process, addresses aren't set",
+                            )
+                            .weak(),
+                        );
+                    }
+
+                    let rows = (0..info.globals.len())
+                        .filter(|&i| matches(&info.globals[i].name))
+                        .collect::<Vec<_>>();
+                    ScrollArea::vertical().max_height(320.).show_rows(
+                        ui,
+                        row_height,
+                        rows.len(),
+                        |ui, range| {
+                            for &i in &rows[range] {
+                                let global = &info.globals[i];
+                                let mut picked = self.globals.contains(&i);
+                                ui.horizontal(|ui| {
+                                    if ui.checkbox(&mut picked, &global.name).changed() {
+                                        toggle(&mut self.globals, i, picked);
+                                    }
+                                    let bias = self.bias.unwrap_or_default();
+                                    let address = global.address.wrapping_add(bias);
+                                    ui.label(
+                                        RichText::new(format!("{} at {address:#X}", global.class))
+                                            .weak(),
+                                    );
+                                });
+                            }
+                        },
+                    );
+                } else {
+                    let rows = (0..info.types.len())
+                        .filter(|&i| matches(&info.types[i].name))
+                        .collect::<Vec<_>>();
+                    ScrollArea::vertical().max_height(320.).show_rows(
+                        ui,
+                        row_height,
+                        rows.len(),
+                        |ui, range| {
+                            for &i in &rows[range] {
+                                let ty = &info.types[i];
+                                let mut picked = self.types.contains(&ty.name);
+                                ui.horizontal(|ui| {
+                                    if ui.checkbox(&mut picked, &ty.name).changed() {
+                                        toggle(&mut self.types, ty.name.clone(), picked);
+                                    }
+                                    ui.label(
+                                        RichText::new(format!(
+                                            "{} of {:#X} bytes",
+                                            ty.kind, ty.size
+                                        ))
+                                        .weak(),
+                                    );
+                                });
+                            }
+                        },
+                    );
+                }
+
+                ui.add_space(4.);
+                let count = self.types.len() + self.globals.len();
+                if ui
+                    .add_enabled(count != 0, Button::new(format!("Import {count} picked")))
+                    .clicked()
+                {
+                    import = true;
+                }
+            });
+
+        if import {
+            self.import();
+            open = false;
+        }
+        if !open {
+            self.info = None;
+        }
+    }
+
+    /// Imports the picked types and types of the picked globals,
+    /// then sets addresses of their classes.
+    fn import(&self) {
+        let Some(info) = self.info.as_ref() else {
+            return;
+        };
+
+        let globals = self
+            .globals
+            .iter()
+            .map(|&i| &info.globals[i])
+            .collect::<Vec<_>>();
+        let mut picked = self.types.clone();
+        picked.extend(globals.iter().map(|g| g.class.clone()));
+
+        let state = &mut *self.state.borrow_mut();
+        state.import(info.import(&picked));
+
+        let Some(bias) = self.bias else {
+            return;
+        };
+        for global in globals {
+            if let Some(class) = state.class_list.by_name(&global.class) {
+                class.address.set(global.address.wrapping_add(bias));
+            }
+        }
+    }
+}
+
+fn toggle<T: Eq + std::hash::Hash>(set: &mut HashSet<T>, value: T, picked: bool) {
+    if picked {
+        set.insert(value);
+    } else {
+        set.remove(&value);
+    }
+}
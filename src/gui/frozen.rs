@@ -0,0 +1,104 @@
+use crate::{journal::format_bytes, state::StateRef, FID_M};
+use eframe::{
+    egui::{Button, Context, DragValue, Grid, RichText, ScrollArea, Window},
+    epaint::Color32,
+};
+use std::sync::atomic::Ordering;
+
+pub struct FrozenWindow {
+    state: StateRef,
+}
+
+impl FrozenWindow {
+    pub fn new(state: StateRef) -> Self {
+        Self { state }
+    }
+
+    pub fn toggle(&mut self) {
+        let windows = &mut self.state.borrow_mut().windows;
+        windows.frozen = !windows.frozen;
+    }
+
+    pub fn show(&mut self, ctx: &Context) {
+        let state = &mut *self.state.borrow_mut();
+        if !state.windows.frozen {
+            return;
+        }
+
+        let process = state.process.clone();
+        let process = process.read();
+
+        let mut shown = true;
+        Window::new("Frozen values")
+            .open(&mut shown)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Rewrite every");
+
+                    let mut interval = state.freeze_interval.load(Ordering::Relaxed);
+                    let r = ui.add(
+                        DragValue::new(&mut interval)
+                            .clamp_range(1..=10000)
+                            .suffix(" ms"),
+                    );
+                    if r.changed() {
+                        state.freeze_interval.store(interval, Ordering::Relaxed);
+                    }
+                    if r.drag_released() || r.lost_focus() {
+                        state.config.freeze_interval = Some(interval);
+                        state.save_config();
+                    }
+                });
+
+                let Some(process) = process.as_ref() else {
+                    ui.label("Attach to a process to freeze values.");
+                    return;
+                };
+
+                let empty = process.frozen().is_empty();
+                if ui
+                    .add_enabled(!empty, Button::new("Unfreeze all"))
+                    .clicked()
+                {
+                    process.frozen().clear();
+                }
+
+                ui.separator();
+
+                let mut unfrozen = None;
+                ScrollArea::vertical().max_height(400.).show(ui, |ui| {
+                    Grid::new("_frozen").striped(true).show(ui, |ui| {
+                        ui.label("Name");
+                        ui.label("Address");
+                        ui.label("Value");
+                        ui.end_row();
+
+                        for (i, value) in process.frozen().iter().enumerate() {
+                            ui.label(&value.name);
+                            ui.label(
+                                RichText::new(
+                                    process
+                                        .describe_address(value.address)
+                                        .unwrap_or_else(|| format!("{:X}", value.address)),
+                                )
+                                .font(FID_M)
+                                .color(Color32::LIGHT_GREEN),
+                            )
+                            .on_hover_text(format!("{:X}", value.address));
+                            ui.label(RichText::new(format_bytes(&value.bytes)).font(FID_M));
+
+                            if ui.button("Unfreeze").clicked() {
+                                unfrozen = Some(i);
+                            }
+                            ui.end_row();
+                        }
+                    });
+                });
+
+                if let Some(i) = unfrozen {
+                    process.frozen().remove(i);
+                }
+            });
+        state.windows.frozen = shown;
+    }
+}
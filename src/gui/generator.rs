@@ -7,7 +7,6 @@ use eframe::{
 pub struct GeneratorWindow {
     current_generator: AvailableGenerator,
     state: StateRef,
-    shown: bool,
     output: Option<String>,
 }
 
@@ -15,23 +14,24 @@ impl GeneratorWindow {
     pub fn new(state: StateRef) -> Self {
         Self {
             state,
-            shown: false,
             output: None,
             current_generator: AvailableGenerator::default(),
         }
     }
 
     pub fn toggle(&mut self) {
-        self.shown = !self.shown;
+        let windows = &mut self.state.borrow_mut().windows;
+        windows.generator = !windows.generator;
     }
 
     pub fn show(&mut self, ctx: &Context) {
-        if !self.shown {
+        let mut shown = self.state.borrow().windows.generator;
+        if !shown {
             return;
         }
 
         Window::new("Class generator")
-            .open(&mut self.shown)
+            .open(&mut shown)
             .show(ctx, |ui| {
                 ComboBox::new("_generator", "Current generator")
                     .selected_text(self.current_generator.label())
@@ -54,12 +54,9 @@ impl GeneratorWindow {
                             classes: state.class_list.classes(),
                         };
 
-                        for class in state.class_list.classes() {
-                            gen.begin_class(&class.name);
-                            for field in class.fields.iter() {
-                                field.codegen(&mut *gen, &data);
-                            }
-                            gen.end_class();
+                        // C++ needs base classes to be defined first.
+                        for class in state.class_list.ordered_by_base() {
+                            data.codegen_class(class, &mut *gen);
                         }
                         self.output = Some(gen.finilize());
                     }
@@ -81,5 +78,6 @@ impl GeneratorWindow {
                         .show(ui);
                 }
             });
+        self.state.borrow_mut().windows.generator = shown;
     }
 }
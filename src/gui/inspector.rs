@@ -1,9 +1,14 @@
 use crate::{
-    address::parse_address, context::InspectionContext, field::FieldResponse, state::StateRef,
+    address::parse_address,
+    class::Class,
+    context::InspectionContext,
+    field::{draw_base_fields, draw_overlays, FieldResponse},
+    history::Edit,
+    state::StateRef,
     FID_M,
 };
 use eframe::{
-    egui::{collapsing_header::CollapsingState, CentralPanel, Context, Id, ScrollArea, Ui},
+    egui::{CentralPanel, Context, Id, ScrollArea, Ui},
     epaint::FontId,
 };
 use fastrand::Rng;
@@ -45,12 +50,20 @@ impl InspectorPanel {
                     }
                 }
 
-                CollapsingState::load_with_default_open(ctx, Id::new("_inspector_panel"), true)
+                let panel = self
+                    .state
+                    .borrow()
+                    .nodes
+                    .load(ctx, Id::new("_inspector_panel"), true);
+                panel
                     .show_header(ui, |ui| {
                         let state = &mut *self.state.borrow_mut();
                         let active_class = state.class_list.selected_class()?;
 
-                        ui.label(format!("{} - ", active_class.name));
+                        let r = ui.label(format!("{} - ", active_class.name));
+                        if !active_class.comment.is_empty() {
+                            r.on_hover_text(&active_class.comment);
+                        }
                         ui.spacing_mut().text_edit_width = self
                             .address_buffer
                             .chars()
@@ -86,6 +99,7 @@ impl InspectorPanel {
         let rng = Rng::with_seed(0);
 
         let process_lock = state.process.read();
+        let mut edits = vec![];
         let mut ctx = InspectionContext {
             address: state.class_list.selected_class()?.address.get(),
             current_container: state.class_list.selected()?,
@@ -93,6 +107,8 @@ impl InspectorPanel {
             class_list: &state.class_list,
             selection: state.selection,
             toasts: &mut state.toasts,
+            edits: &mut edits,
+            nodes: &state.nodes,
             current_id: Id::new(0),
             parent_id: Id::new(0),
             level_rng: &rng,
@@ -108,9 +124,15 @@ impl InspectorPanel {
             .hscroll(true)
             .enable_scrolling(self.allow_scroll)
             .show(ui, |ui| {
-                match class.fields.iter().fold(None, |r, f| {
+                let inherited = draw_base_fields(class, ui, &mut ctx);
+                match class.fields.iter().fold(inherited, |r, f| {
                     ctx.current_id = Id::new(rng.u64(..));
-                    r.or(f.draw(ui, &mut ctx))
+                    r.or(f.draw(ui, &mut ctx)).or(draw_overlays(
+                        &class.overlays,
+                        f.as_ref(),
+                        ui,
+                        &mut ctx,
+                    ))
                 }) {
                     Some(FieldResponse::NewClass(name, id)) => new_class = Some((name, id)),
                     Some(FieldResponse::LockScroll) => self.allow_scroll = false,
@@ -120,8 +142,16 @@ impl InspectorPanel {
             });
         state.selection = ctx.selection;
 
+        for edit in edits {
+            state.history.apply(&mut state.class_list, edit);
+        }
+
         if let Some((name, id)) = new_class {
-            state.class_list.add_class_with_id(name, id);
+            let index = state.class_list.classes().len();
+            let class = Class::new(id, state.class_list.unique_name(name));
+            state
+                .history
+                .apply(&mut state.class_list, Edit::InsertClass { index, class });
         }
 
         Some(())
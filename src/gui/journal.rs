@@ -0,0 +1,156 @@
+use crate::{
+    journal::{export_patch, format_bytes, parse_patch},
+    state::StateRef,
+    FID_M,
+};
+use eframe::{
+    egui::{Button, Context, Grid, RichText, ScrollArea, Window},
+    epaint::Color32,
+};
+use std::fs;
+
+const READ_ONLY: &str = "The process is read-only, nothing was reverted";
+
+pub struct JournalWindow {
+    state: StateRef,
+}
+
+impl JournalWindow {
+    pub fn new(state: StateRef) -> Self {
+        Self { state }
+    }
+
+    pub fn toggle(&mut self) {
+        let windows = &mut self.state.borrow_mut().windows;
+        windows.journal = !windows.journal;
+    }
+
+    pub fn show(&mut self, ctx: &Context) {
+        let state = &mut *self.state.borrow_mut();
+        if !state.windows.journal {
+            return;
+        }
+
+        let process = state.process.clone();
+        let process = process.read();
+
+        let mut shown = true;
+        Window::new("Write journal")
+            .open(&mut shown)
+            .show(ctx, |ui| {
+                let Some(process) = process.as_ref() else {
+                    ui.label("Attach to a process to see written values.");
+                    return;
+                };
+
+                ui.horizontal(|ui| {
+                    let empty = process.journal().entries().is_empty();
+
+                    if ui.add_enabled(!empty, Button::new("Revert all")).clicked()
+                        && !process.revert_all()
+                    {
+                        state.toasts.error(READ_ONLY);
+                    }
+
+                    if ui
+                        .add_enabled(!empty, Button::new("Export patch"))
+                        .clicked()
+                    {
+                        if let Some(path) = rfd::FileDialog::new()
+                            .set_title("Export patch")
+                            .add_filter("YClass patch", &["ycpatch"])
+                            .save_file()
+                        {
+                            let patch =
+                                export_patch(process.journal().entries(), process.modules());
+                            if let Err(e) = fs::write(path, patch) {
+                                state
+                                    .toasts
+                                    .error(format!("Failed to export the patch. {e}"));
+                            }
+                        }
+                    }
+
+                    if ui.button("Apply patch").clicked() {
+                        if let Some(path) = rfd::FileDialog::new()
+                            .set_title("Apply patch")
+                            .add_filter("YClass patch", &["ycpatch"])
+                            .pick_file()
+                        {
+                            match fs::read_to_string(path)
+                                .map_err(|e| e.to_string())
+                                .and_then(|text| parse_patch(&text))
+                            {
+                                Ok(patch) => {
+                                    let (applied, total) =
+                                        (process.apply_patch(&patch), patch.len());
+                                    state.toasts.info(format!(
+                                        "Applied {applied} of {total} writes, the rest didn't match"
+                                    ));
+                                }
+                                Err(e) => _ = state.toasts.error(format!("Invalid patch. {e}")),
+                            }
+                        }
+                    }
+
+                    if ui
+                        .add_enabled(!empty, Button::new("Forget all"))
+                        .on_hover_text("Clears the journal without reverting anything")
+                        .clicked()
+                    {
+                        process.journal().take();
+                    }
+                });
+
+                ui.separator();
+
+                let mut reverted = None;
+                ScrollArea::vertical().max_height(400.).show(ui, |ui| {
+                    Grid::new("_journal").striped(true).show(ui, |ui| {
+                        ui.label("Time");
+                        ui.label("Address");
+                        ui.label("Old");
+                        ui.label("New");
+                        ui.end_row();
+
+                        let journal = process.journal();
+                        for (i, entry) in journal.entries().iter().enumerate().rev() {
+                            ui.label(RichText::new(entry.clock()).font(FID_M));
+                            ui.label(
+                                RichText::new(
+                                    process
+                                        .describe_address(entry.address)
+                                        .unwrap_or_else(|| format!("{:X}", entry.address)),
+                                )
+                                .font(FID_M)
+                                .color(Color32::LIGHT_GREEN),
+                            )
+                            .on_hover_text(format!("{:X}", entry.address));
+                            ui.label(
+                                RichText::new(format_bytes(&entry.old))
+                                    .font(FID_M)
+                                    .color(Color32::GRAY),
+                            );
+                            ui.label(RichText::new(format_bytes(&entry.new)).font(FID_M));
+
+                            if ui
+                                .button("Revert")
+                                .on_hover_text("Newer writes overlapping this one are reverted too")
+                                .clicked()
+                            {
+                                reverted = Some(i);
+                            }
+                            ui.end_row();
+                        }
+                    });
+                });
+
+                if let Some(i) = reverted {
+                    if !process.revert(i) {
+                        state.toasts.error(READ_ONLY);
+                    }
+                }
+            });
+        state.windows.journal = shown;
+    }
+}
@@ -10,5 +10,13 @@ mod generator;
 pub use generator::*;
 mod spider;
 pub use spider::*;
+mod journal;
+pub use journal::*;
+mod frozen;
+pub use frozen::*;
+mod debug_info;
+pub use debug_info::*;
+mod recovery;
+pub use recovery::*;
 mod utils;
 pub use utils::*;
@@ -0,0 +1,69 @@
+use crate::{
+    recovery::{self, Recovery},
+    state::StateRef,
+};
+use eframe::{
+    egui::{Align2, Context, Window},
+    epaint::vec2,
+};
+
+pub struct RecoveryWindow {
+    state: StateRef,
+    recovery: Option<Recovery>,
+}
+
+impl RecoveryWindow {
+    pub fn new(state: StateRef, recovery: Option<Recovery>) -> Self {
+        Self { state, recovery }
+    }
+
+    /// `true` until the user decides what to do with the recovered project,
+    /// the recovery file must not be overwritten before that.
+    pub fn pending(&self) -> bool {
+        self.recovery.is_some()
+    }
+
+    pub fn show(&mut self, ctx: &Context) {
+        let Some(recovery) = self.recovery.as_ref() else {
+            return;
+        };
+
+        let mut decided = false;
+        Window::new("Recover project")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(Align2::CENTER_CENTER, vec2(0., 0.))
+            .show(ctx, |ui| {
+                let name = recovery
+                    .project
+                    .as_ref()
+                    .and_then(|p| p.file_name())
+                    .map(|n| n.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| "the unsaved project".into());
+                let minutes = recovery
+                    .saved_at
+                    .elapsed()
+                    .map(|d| d.as_secs() / 60)
+                    .unwrap_or_default();
+
+                ui.label("YClass didn't shut down cleanly last time.");
+                ui.label(format!("Restore {name} autosaved {minutes} minute(s) ago?"));
+
+                ui.horizontal(|ui| {
+                    if ui.button("Restore").clicked() {
+                        self.state.borrow_mut().recover(recovery);
+                        decided = true;
+                    }
+
+                    if ui.button("Discard").clicked() {
+                        recovery::discard();
+                        decided = true;
+                    }
+                });
+            });
+
+        if decided {
+            self.recovery = None;
+        }
+    }
+}
@@ -1,17 +1,20 @@
 use super::{FilterMode, ScannerReport, ScannerState, SearchResult};
 use crate::{
     address::parse_address,
-    field::FieldKind,
+    class::ClassList,
+    field::{ChainField, Field, FieldKind},
     gui::{
         spider::{bytes_to_value, parse_kind_to_value, SearchOptions},
         TextEditBind, TextEditFromStrBind,
     },
+    history::Edit,
     process::Process,
+    session::SpiderSettings,
     state::StateRef,
     value::Value,
 };
 use eframe::{
-    egui::{Button, ComboBox, Context, RichText, TextEdit, Ui, Window},
+    egui::{Button, ComboBox, Context, RichText, TextBuffer, TextEdit, Ui, Window},
     epaint::{vec2, Color32, FontId},
 };
 use egui_extras::{Column, TableBuilder};
@@ -37,15 +40,16 @@ impl DisplayMode {
         }
 
         match self {
-            Self::Normal => match_val!(value, "{}", U8, I8, U16, I16, U32, I32, U64, I64, F32, F64),
-            Self::Hex => match_val!(value, "{:X}", U8, I8, U16, I16, U32, I32, U64, I64),
+            Self::Normal => value.to_string(),
+            Self::Hex => {
+                match_val!(value, "{:X}", U8, I8, U16, I16, U32, I32, U64, I64, I128, U128)
+            }
         }
     }
 }
 
 pub struct SpiderWindow {
     state: StateRef,
-    shown: bool,
 
     max_levels: TextEditFromStrBind<usize>,
     struct_size: TextEditFromStrBind<usize>,
@@ -79,15 +83,18 @@ impl SpiderWindow {
             value_buf: String::new(),
             scanner_status: None,
             results: vec![],
-            shown: false,
             state,
         }
     }
 
     pub fn toggle(&mut self) {
-        self.shown = !self.shown;
+        let shown = {
+            let windows = &mut self.state.borrow_mut().windows;
+            windows.spider = !windows.spider;
+            windows.spider
+        };
 
-        if self.shown && self.base_address.value().is_none() {
+        if shown && self.base_address.value().is_none() {
             let address = self
                 .state
                 .borrow()
@@ -105,9 +112,35 @@ impl SpiderWindow {
         }
     }
 
+    fn settings(&self) -> SpiderSettings {
+        SpiderSettings {
+            max_levels: self.max_levels.as_str().to_owned(),
+            struct_size: self.struct_size.as_str().to_owned(),
+            alignment: self.alignment.as_str().to_owned(),
+            field_kind: self.field_kind,
+            base_address: self.base_address.as_str().to_owned(),
+            value: self.value_buf.clone(),
+        }
+    }
+
+    /// Restores settings saved in a project, invalid inputs are restored as they were.
+    pub fn restore(&mut self, settings: &SpiderSettings) {
+        let bind = |text: &str| TextEditFromStrBind::new_from_str_with(text, text.parse().ok());
+        self.max_levels = bind(&settings.max_levels);
+        self.struct_size = bind(&settings.struct_size);
+        self.alignment = bind(&settings.alignment);
+        self.field_kind = settings.field_kind;
+
+        self.base_address = TextEditBind::new(|s| parse_address(s).ok_or(()));
+        if let Some(address) = parse_address(&settings.base_address) {
+            self.base_address
+                .set(address, settings.base_address.as_str());
+        }
+        self.value_buf = settings.value.clone();
+    }
+
     pub fn show(&mut self, ctx: &Context) -> eyre::Result<Option<()>> {
-        // I promise not to use self.show anywhere else.
-        let shown = unsafe { &mut (*(self as *mut Self)).shown };
+        let mut shown = self.state.borrow().windows.spider;
 
         match self.scanner.try_take() {
             ScannerReport::Finshed(time, mut results) => {
@@ -123,8 +156,8 @@ impl SpiderWindow {
             ScannerReport::Idle => {}
         }
 
-        Window::new("Structure spider")
-            .open(shown)
+        let result = Window::new("Structure spider")
+            .open(&mut shown)
             .show(ctx, |ui| {
                 let state = &mut *self.state.borrow_mut();
 
@@ -248,8 +281,15 @@ impl SpiderWindow {
 
                             // This looks a bit nasty but *shrug*
                             let mut as_hex = matches!(self.display, DisplayMode::Hex);
-                            if !matches!(self.field_kind, FieldKind::F32 | FieldKind::F64)
-                                && ui.checkbox(&mut as_hex, "Show as hex").changed()
+                            if !matches!(
+                                self.field_kind,
+                                FieldKind::F16
+                                    | FieldKind::BF16
+                                    | FieldKind::F32
+                                    | FieldKind::F64
+                                    | FieldKind::Fixed32(_)
+                                    | FieldKind::Fixed64(_)
+                            ) && ui.checkbox(&mut as_hex, "Show as hex").changed()
                             {
                                 if as_hex {
                                     self.display = DisplayMode::Hex;
@@ -270,16 +310,30 @@ impl SpiderWindow {
 
                     ui.separator();
 
-                    self.display_results(process, ui);
+                    if let Some(result) = self.display_results(process, ui) {
+                        let edit = self.add_to_class(&state.class_list, result)?;
+                        state.history.apply(&mut state.class_list, edit);
+                        state.dummy = false;
+                    }
                 }
 
                 Ok(())
             })
             .and_then(|v| v.inner)
-            .transpose()
+            .transpose();
+
+        // Settings only change while the window is shown.
+        let state = &mut *self.state.borrow_mut();
+        state.windows.spider = shown;
+        if shown {
+            state.session.spider = Some(self.settings());
+        }
+
+        result
     }
 
-    fn display_results(&mut self, process: &Process, ui: &mut Ui) {
+    /// Returns index of the result that should be added to the selected class.
+    fn display_results(&mut self, process: &Process, ui: &mut Ui) -> Option<usize> {
         const DATA_HEIGHT: f32 = 14.;
         ui.style_mut().override_font_id = Some(FontId::monospace(DATA_HEIGHT));
 
@@ -289,11 +343,12 @@ impl SpiderWindow {
             .cloned()
         else {
             ui.heading("Invalid base address");
-            return;
+            return None;
         };
 
         let levels = *self.max_levels.value().unwrap().unwrap();
         let w = ui.available_width() / (levels + 2) as f32 - 4.;
+        let mut add_result = None;
 
         TableBuilder::new(ui)
             .striped(true)
@@ -331,20 +386,77 @@ impl SpiderWindow {
                         process.read(address + offset, &mut buf[..]);
                         address = usize::from_ne_bytes(buf);
                     }
-                    process.read(address + result.offset, &mut buf[..]);
+
+                    let mut buf = [0; 16];
+                    let size = result.last_value.kind().size();
+                    process.read(address + result.offset, &mut buf[..size]);
 
                     // Display current value
                     let current = bytes_to_value(&buf, result.last_value.kind());
                     let text = self.display.format(current);
                     row.col(|ui| {
-                        if current != result.last_value {
-                            ui.label(RichText::new(text).color(Color32::KHAKI));
+                        let r = if current != result.last_value {
+                            ui.label(RichText::new(text).color(Color32::KHAKI))
                         } else {
-                            ui.label(text);
-                        }
+                            ui.label(text)
+                        };
+
+                        r.on_hover_text("Right click to add to the selected class")
+                            .context_menu(|ui| {
+                                if ui.button("Add to class").clicked() {
+                                    add_result = Some(idx);
+                                    ui.close_menu();
+                                }
+                            });
                     });
                 })
-            })
+            });
+
+        add_result
+    }
+
+    /// Adds result to the selected class as a pointer chain if it has parent offsets
+    /// or as a plain value otherwise. Base address should point into the class.
+    fn add_to_class(&self, class_list: &ClassList, result: usize) -> eyre::Result<Edit> {
+        let result = &self.results[result];
+        let base = self
+            .base_address
+            .value_clone()
+            .and_then(|v| v.ok())
+            .ok_or(eyre::eyre!("Base address is required"))?;
+
+        let class = class_list
+            .selected_class()
+            .ok_or(eyre::eyre!("Select a class first"))?;
+        let address = class.address.get();
+        // Own fields of the class start after the inherited ones.
+        let inherited = class.base().map_or(0, |b| class_list.class_size(b));
+        let relative = base
+            .checked_sub(address)
+            .ok_or(eyre::eyre!("Base address is outside of the selected class"))?;
+
+        let kind = result.last_value.kind();
+        let (offset, field): (usize, Box<dyn Field>) = match result.parent_offsets.split_first() {
+            Some((first, rest)) => {
+                let offsets = rest.iter().copied().chain([result.offset]).collect();
+                (
+                    relative + first,
+                    Box::new(ChainField::with_chain(
+                        format!("chain_{:x}", relative + first),
+                        offsets,
+                        kind,
+                    )),
+                )
+            }
+            None => (relative + result.offset, kind.into_field(None)),
+        };
+
+        let offset = offset
+            .checked_sub(inherited)
+            .ok_or(eyre::eyre!("Value is inside of the base class"))?;
+        class
+            .put_field(offset, field)
+            .ok_or(eyre::eyre!("Selected class is too small"))
     }
 
     fn collect_options(&self) -> eyre::Result<SearchOptions> {
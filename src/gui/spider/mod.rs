@@ -3,7 +3,11 @@ pub use gui::*;
 mod scanner;
 pub(crate) use scanner::*;
 
-use crate::{field::FieldKind, process::Process, value::Value};
+use crate::{
+    field::FieldKind,
+    process::Process,
+    value::{bf16_to_f32, f16_to_f32, f32_to_bf16, f32_to_f16, f64_to_fixed, Value},
+};
 use std::sync::Arc;
 
 #[derive(PartialEq, Clone, Copy)]
@@ -73,7 +77,8 @@ impl SearchResult {
         p.read(address.saturating_add(self.offset), &mut buf[..]);
         address = usize::from_ne_bytes(buf);
 
-        p.read(address, &mut buf[..]);
+        let mut buf = [0; 16];
+        p.read(address, &mut buf[..self.last_value.kind().size()]);
 
         let current_value = bytes_to_value(&buf, self.last_value.kind());
         let result = match filter {
@@ -92,7 +97,7 @@ impl SearchResult {
     }
 }
 
-fn bytes_to_value(arr: &[u8; 8], kind: FieldKind) -> Value {
+pub(crate) fn bytes_to_value(arr: &[u8; 16], kind: FieldKind) -> Value {
     macro_rules! into_value {
         ($s:ident, $type:ty) => {
             <$type>::from_ne_bytes(arr[..std::mem::size_of::<$type>()].try_into().unwrap()).into()
@@ -110,11 +115,21 @@ fn bytes_to_value(arr: &[u8; 8], kind: FieldKind) -> Value {
         FieldKind::U64 => into_value!(s, u64),
         FieldKind::F32 => into_value!(s, f32),
         FieldKind::F64 => into_value!(s, f64),
+        FieldKind::I128 => into_value!(s, i128),
+        FieldKind::U128 => into_value!(s, u128),
+        FieldKind::F16 => Value::F16(f16_to_f32(u16::from_ne_bytes([arr[0], arr[1]]))),
+        FieldKind::BF16 => Value::BF16(bf16_to_f32(u16::from_ne_bytes([arr[0], arr[1]]))),
+        FieldKind::Fixed32(frac) => {
+            Value::Fixed32(i32::from_ne_bytes(arr[..4].try_into().unwrap()), frac)
+        }
+        FieldKind::Fixed64(frac) => {
+            Value::Fixed64(i64::from_ne_bytes(arr[..8].try_into().unwrap()), frac)
+        }
         _ => unreachable!(),
     }
 }
 
-fn parse_kind_to_value(kind: FieldKind, s: &str) -> eyre::Result<Value> {
+pub(crate) fn parse_kind_to_value(kind: FieldKind, s: &str) -> eyre::Result<Value> {
     macro_rules! into_value {
         ($s:ident, $type:ty) => {
             if let Some(tail) = $s.strip_prefix("0x") {
@@ -146,6 +161,32 @@ fn parse_kind_to_value(kind: FieldKind, s: &str) -> eyre::Result<Value> {
             .parse::<f64>()
             .map_err(|e| eyre::eyre!("Value: {e}"))?
             .into(),
+        FieldKind::I128 => into_value!(s, i128),
+        FieldKind::U128 => into_value!(s, u128),
+        // Rounded, so it compares equal to the values read from memory.
+        FieldKind::F16 => Value::F16(f16_to_f32(f32_to_f16(
+            s.parse::<f32>().map_err(|e| eyre::eyre!("Value: {e}"))?,
+        ))),
+        FieldKind::BF16 => Value::BF16(bf16_to_f32(f32_to_bf16(
+            s.parse::<f32>().map_err(|e| eyre::eyre!("Value: {e}"))?,
+        ))),
+        FieldKind::Fixed32(frac) | FieldKind::Fixed64(frac) => {
+            let raw = s
+                .parse::<f64>()
+                .ok()
+                .and_then(|v| f64_to_fixed(v, frac))
+                .ok_or(eyre::eyre!("Value: out of range"))?;
+
+            if let FieldKind::Fixed32(_) = kind {
+                Value::Fixed32(
+                    raw.try_into()
+                        .map_err(|_| eyre::eyre!("Value: out of range"))?,
+                    frac,
+                )
+            } else {
+                Value::Fixed64(raw, frac)
+            }
+        }
         _ => unreachable!(),
     })
 }
@@ -86,16 +86,15 @@ fn recursive_first_search(
         };
 
     for address in (start..start + opts.struct_size).step_by(opts.alignment) {
-        let mut buf = [0; 8];
-        process.read().as_ref().unwrap().read(address, &mut buf[..]);
-
-        if address % 8 == 0
-            && process
-                .read()
-                .as_ref()
-                .unwrap()
-                .can_read(usize::from_ne_bytes(buf))
-        {
+        let mut buf = [0; 16];
+        process
+            .read()
+            .as_ref()
+            .unwrap()
+            .read(address, &mut buf[..opts.value.kind().size().max(8)]);
+        let pointer = usize::from_ne_bytes(buf[..8].try_into().unwrap());
+
+        if address % 8 == 0 && process.read().as_ref().unwrap().can_read(pointer) {
             rayon::spawn({
                 let results = results.clone();
                 let offsets = opts.offsets.clone();
@@ -111,7 +110,7 @@ fn recursive_first_search(
                             offsets: Arc::new(
                                 offsets.iter().copied().chain([address - start]).collect(),
                             ),
-                            address: usize::from_ne_bytes(buf),
+                            address: pointer,
                             struct_size: opts.struct_size,
                             alignment: opts.alignment,
                             depth: opts.depth - 1,
@@ -1,14 +1,22 @@
-use super::{GeneratorWindow, ProcessAttachWindow, SpiderWindow};
+use super::{
+    DebugInfoWindow, FrozenWindow, GeneratorWindow, JournalWindow, ProcessAttachWindow,
+    SpiderWindow, TextEditFromStrBind,
+};
 use crate::{
     class::ClassList,
-    field::FieldKind,
+    field::{FieldId, FieldKind, RelativeTo, TextEncoding, TimeUnit, ALTERNATIVE_KINDS},
+    project::{cpp, dwarf::DebugInfo, reclass, rust, Imported},
+    session::Session,
     state::{GlobalState, StateRef},
 };
 use eframe::{
-    egui::{style::Margin, Button, Context, Frame, RichText, TopBottomPanel, Ui, WidgetText},
+    egui::{
+        style::Margin, Button, Context, Frame, RichText, TextEdit, TopBottomPanel, Ui, WidgetText,
+    },
     epaint::{vec2, Color32, Rounding},
 };
 use memflex::external::ProcessIterator;
+use std::{fs, path::Path};
 
 macro_rules! create_change_field_type_group {
     ($ui:ident, $r:ident, $fg:ident, $bg:ident, $($size:ident),*) => {
@@ -34,12 +42,28 @@ pub enum ToolBarResponse {
     Remove(usize),
     Insert(usize),
     ChangeKind(FieldKind),
+    AddOverlay(FieldKind),
+    ClearOverlays,
+    SetComment(String),
+    Undo,
+    Redo,
+    ToggleFreeze,
 }
 
 pub struct ToolBarPanel {
     ps_attach_window: ProcessAttachWindow,
     generator_window: GeneratorWindow,
     spider_window: SpiderWindow,
+    journal_window: JournalWindow,
+    frozen_window: FrozenWindow,
+    debug_info_window: DebugInfoWindow,
+    text_encoding: TextEncoding,
+    text_len: TextEditFromStrBind<usize>,
+    union_size: TextEditFromStrBind<usize>,
+    fixed_frac: TextEditFromStrBind<u8>,
+    // Comment being edited and the field it belongs to.
+    comment_buf: String,
+    comment_field: Option<FieldId>,
     state: StateRef,
 }
 
@@ -50,6 +74,15 @@ impl ToolBarPanel {
             ps_attach_window: ProcessAttachWindow::new(state),
             generator_window: GeneratorWindow::new(state),
             spider_window: SpiderWindow::new(state),
+            journal_window: JournalWindow::new(state),
+            frozen_window: FrozenWindow::new(state),
+            debug_info_window: DebugInfoWindow::new(state),
+            text_encoding: TextEncoding::Utf8,
+            text_len: TextEditFromStrBind::new_from_str_with("32", Some(32)),
+            union_size: TextEditFromStrBind::new_from_str_with("8", Some(8)),
+            fixed_frac: TextEditFromStrBind::new_from_str_with("16", Some(16)),
+            comment_buf: String::new(),
+            comment_field: None,
         }
     }
 
@@ -62,6 +95,9 @@ impl ToolBarPanel {
         }
 
         self.generator_window.show(ctx);
+        self.journal_window.show(ctx);
+        self.frozen_window.show(ctx);
+        self.debug_info_window.show(ctx);
         if let Err(e) = self.spider_window.show(ctx) {
             self.state.borrow_mut().toasts.error(e.to_string());
         }
@@ -85,6 +121,7 @@ impl ToolBarPanel {
                     ui.visuals_mut().widgets.inactive.rounding = Rounding::none();
 
                     ui.menu_button("Project", |ui| self.project_menu(ui));
+                    ui.menu_button("Edit", |ui| self.edit_menu(ui, &mut response));
                     ui.menu_button("Process", |ui| self.process_menu(ui, &mut response));
 
                     if ui.button("Generator").clicked() {
@@ -95,6 +132,22 @@ impl ToolBarPanel {
                         self.spider_window.toggle();
                     }
 
+                    if ui
+                        .button("Frozen")
+                        .on_hover_text("Values that are kept in place")
+                        .clicked()
+                    {
+                        self.frozen_window.toggle();
+                    }
+
+                    if ui
+                        .button("Journal")
+                        .on_hover_text("Values written by editing fields")
+                        .clicked()
+                    {
+                        self.journal_window.toggle();
+                    }
+
                     ui.add_space(4.);
                     ui.separator();
                     ui.add_space(4.);
@@ -146,6 +199,21 @@ impl ToolBarPanel {
                     .response
                     .on_hover_text("Inserts N bytes");
 
+                    ui.menu_button("Comment", |ui| self.comment_menu(ui, &mut response))
+                        .response
+                        .on_hover_text("Note about the selected field");
+
+                    let freeze = self.state.borrow().hotkeys.format("freeze", ctx);
+                    if ui
+                        .button("Freeze")
+                        .on_hover_text(format!(
+                            "Keeps the selected field at its current value or unfreezes it({freeze})"
+                        ))
+                        .clicked()
+                    {
+                        response = Some(ToolBarResponse::ToggleFreeze);
+                    }
+
                     ui.add_space(2.);
                     ui.separator();
                     ui.add_space(2.);
@@ -165,6 +233,17 @@ impl ToolBarPanel {
             self.ps_attach_window.toggle();
         }
 
+        // Text edits have their own undo.
+        if !ctx.wants_keyboard_input() {
+            if state.hotkeys.pressed("undo", input) {
+                *response = Some(ToolBarResponse::Undo);
+            } else if state.hotkeys.pressed("redo", input) {
+                *response = Some(ToolBarResponse::Redo);
+            } else if state.hotkeys.pressed("freeze", input) {
+                *response = Some(ToolBarResponse::ToggleFreeze);
+            }
+        }
+
         if state.hotkeys.pressed("attach_recent", input) {
             if let Some(name) = state.config.last_attached_process_name.as_ref().cloned() {
                 attach_to_process(state, &name, response);
@@ -178,11 +257,21 @@ impl ToolBarPanel {
         if ui.button("New project").clicked() {
             state.save_project(None);
             state.class_list = ClassList::default();
+            state.history.clear();
+            state.project_read_only = false;
+            state.includes.clear();
+            state.sync_read_only();
+            state.restore_session(Session {
+                windows: state.windows,
+                ..Default::default()
+            });
             ui.close_menu();
         }
 
         if ui.button("Open project").clicked() {
-            state.open_project();
+            if state.open_project() {
+                self.restore_session(state);
+            }
             ui.close_menu();
         }
 
@@ -205,6 +294,7 @@ impl ToolBarPanel {
 
                 if let Some(path) = to_open {
                     if state.open_project_path(&path) {
+                        self.restore_session(state);
                         ui.close_menu();
                     } else {
                         state.config.recent_projects.as_mut().unwrap().remove(&path);
@@ -222,6 +312,191 @@ impl ToolBarPanel {
             state.save_project_as();
             ui.close_menu();
         }
+
+        ui.separator();
+
+        if ui
+            .button("Include project...")
+            .on_hover_text("Import classes of another project, they stay read-only")
+            .clicked()
+        {
+            if let Some(path) = rfd::FileDialog::new()
+                .set_title("Include project")
+                .add_filter("YClass project", &["yclass"])
+                .pick_file()
+            {
+                // Relative paths keep working when the projects are moved together.
+                let dir = state.last_opened_project.as_deref().and_then(Path::parent);
+                let include = dir
+                    .and_then(|dir| path.strip_prefix(dir).ok())
+                    .unwrap_or(&path)
+                    .to_string_lossy()
+                    .into_owned();
+
+                if state.includes.contains(&include) {
+                    state.toasts.error("Project is already included");
+                } else {
+                    let mut includes = state.includes.clone();
+                    includes.push(include);
+                    state.set_includes(includes);
+                }
+            }
+            ui.close_menu();
+        }
+
+        if !state.includes.is_empty() {
+            ui.menu_button("Remove include...", |ui| {
+                let mut to_remove = None;
+                for (i, include) in state.includes.iter().enumerate() {
+                    if ui.button(include).clicked() {
+                        to_remove = Some(i);
+                    }
+                }
+
+                if let Some(i) = to_remove {
+                    let mut includes = state.includes.clone();
+                    includes.remove(i);
+                    state.set_includes(includes);
+                    ui.close_menu();
+                }
+            });
+        }
+
+        ui.menu_button("Import", |ui| {
+            if ui.button("C/C++ header...").clicked() {
+                import_file(state, "C/C++ header", &["h", "hpp", "hxx", "hh"], |data| {
+                    Ok(cpp::import(&String::from_utf8_lossy(&data)))
+                });
+                ui.close_menu();
+            }
+
+            if ui.button("Rust structs...").clicked() {
+                import_file(state, "Rust source", &["rs"], |data| {
+                    Ok(rust::import(&String::from_utf8_lossy(&data)))
+                });
+                ui.close_menu();
+            }
+
+            if ui.button("ReClass.NET project...").clicked() {
+                import_file(state, "ReClass.NET project", &["rcnet"], |data| {
+                    reclass::import_rcnet(&data)
+                });
+                ui.close_menu();
+            }
+
+            if ui.button("ReClassEx project...").clicked() {
+                import_file(state, "ReClassEx project", &["reclass"], |data| {
+                    reclass::import_reclass(&String::from_utf8_lossy(&data))
+                });
+                ui.close_menu();
+            }
+
+            ui.separator();
+
+            let process = state.process.clone();
+            let process = process.read();
+            let executable = process.as_ref().and_then(|p| p.executable());
+
+            if ui
+                .add_enabled(
+                    executable.is_some(),
+                    Button::new("Debug info of the process"),
+                )
+                .on_hover_text("Types from DWARF debug info of the attached process's executable")
+                .on_disabled_hover_text("Attach to a process first")
+                .clicked()
+            {
+                if let Some(module) = executable {
+                    self.open_debug_info(state, module.path(), |info| {
+                        Some(info.load_bias(module.base))
+                    });
+                }
+                ui.close_menu();
+            }
+
+            if ui
+                .button("Debug info file...")
+                .on_hover_text(
+                    "Types from DWARF debug info of an ELF file, i.e. a separate debug file",
+                )
+                .clicked()
+            {
+                if let Some(path) = rfd::FileDialog::new()
+                    .set_title("Import debug info")
+                    .pick_file()
+                {
+                    // Separate debug files are named after their module, i.e. `game.debug`,
+                    // the executable is the most likely one otherwise.
+                    let stem = path.file_stem().map(|s| s.to_string_lossy().into_owned());
+                    let module = process.as_ref().and_then(|p| {
+                        p.modules()
+                            .iter()
+                            .find(|m| Some(&m.name) == stem.as_ref())
+                            .or_else(|| p.executable())
+                    });
+                    self.open_debug_info(state, &path, |info| {
+                        module.map(|m| info.load_bias(m.base))
+                    });
+                }
+                ui.close_menu();
+            }
+        });
+    }
+
+    fn open_debug_info(
+        &mut self,
+        state: &mut GlobalState,
+        path: &Path,
+        bias: impl FnOnce(&DebugInfo) -> Option<usize>,
+    ) {
+        match fs::read(path)
+            .map_err(Into::into)
+            .and_then(|data| DebugInfo::read(&data))
+        {
+            Ok(info) if info.types.is_empty() => {
+                state
+                    .toasts
+                    .error("Debug info has no structs, classes or unions");
+            }
+            Ok(info) => {
+                let bias = bias(&info);
+                self.debug_info_window
+                    .open(info, path.display().to_string(), bias);
+            }
+            Err(e) => {
+                state.toasts.error(format!(
+                    "Failed to read debug info of {}. {e}",
+                    path.display()
+                ));
+            }
+        }
+    }
+
+    /// Restores spider settings of the just opened project.
+    fn restore_session(&mut self, state: &GlobalState) {
+        if let Some(settings) = state.session.spider.as_ref() {
+            self.spider_window.restore(settings);
+        }
+    }
+
+    fn edit_menu(&mut self, ui: &mut Ui, response: &mut Option<ToolBarResponse>) {
+        ui.set_width(160.);
+
+        let state = &*self.state.borrow();
+
+        ui.add_enabled_ui(state.history.can_undo(), |ui| {
+            if shortcut_button(ui, state, "undo", "Undo") {
+                *response = Some(ToolBarResponse::Undo);
+                ui.close_menu();
+            }
+        });
+
+        ui.add_enabled_ui(state.history.can_redo(), |ui| {
+            if shortcut_button(ui, state, "redo", "Redo") {
+                *response = Some(ToolBarResponse::Redo);
+                ui.close_menu();
+            }
+        });
     }
 
     fn process_menu(&mut self, ui: &mut Ui, response: &mut Option<ToolBarResponse>) {
@@ -247,6 +522,27 @@ impl ToolBarPanel {
             *response = Some(ToolBarResponse::ProcessDetach);
             ui.close_menu();
         }
+
+        ui.separator();
+
+        let mut read_only = state.config.read_only.unwrap_or_default();
+        if ui
+            .checkbox(&mut read_only, "Read-only")
+            .on_hover_text("Never write to memory, in any project")
+            .changed()
+        {
+            state.config.read_only = Some(read_only);
+            state.save_config();
+            state.sync_read_only();
+        }
+
+        if ui
+            .checkbox(&mut state.project_read_only, "Read-only project")
+            .on_hover_text("Never write to memory while this project is open")
+            .changed()
+        {
+            state.sync_read_only();
+        }
     }
 
     fn status_ui(&mut self, ui: &mut Ui, response: &mut Option<ToolBarResponse>) {
@@ -260,7 +556,12 @@ impl ToolBarPanel {
             .map(|p| (p.name(), p.id()))
         {
             match proc_name {
-                Ok(name) => _ = ui.label(format!("Status: Attached to {} - {}", name, proc_id)),
+                Ok(name) => {
+                    ui.label(format!("Status: Attached to {} - {}", name, proc_id));
+                    if self.state.borrow().read_only() {
+                        ui.label(RichText::new(" [READ-ONLY]").color(Color32::LIGHT_RED));
+                    }
+                }
                 Err(e) => {
                     self.state
                         .borrow_mut()
@@ -271,6 +572,19 @@ impl ToolBarPanel {
             };
         } else {
             ui.label("Status: Detached");
+
+            // Process of the opened project is only suggested, it might be a different one by now.
+            let name = self.state.borrow().session.process_name.clone();
+            if let Some(name) = name {
+                ui.add_space(4.);
+                if ui
+                    .button(format!("Attach to {name}"))
+                    .on_hover_text("Process the project was inspected in")
+                    .clicked()
+                {
+                    attach_to_process(&mut self.state.borrow_mut(), &name, response);
+                }
+            }
         }
     }
 
@@ -280,17 +594,21 @@ impl ToolBarPanel {
         ui.separator();
         ui.add_space(2.);
 
-        create_change_field_type_group!(ui, response, BLACK, LIGHT_GREEN, U8, U16, U32, U64);
+        create_change_field_type_group!(ui, response, BLACK, LIGHT_GREEN, U8, U16, U32, U64, U128);
 
         ui.separator();
         ui.add_space(2.);
 
-        create_change_field_type_group!(ui, response, BLACK, LIGHT_BLUE, I8, I16, I32, I64);
+        create_change_field_type_group!(ui, response, BLACK, LIGHT_BLUE, I8, I16, I32, I64, I128);
 
         ui.separator();
         ui.add_space(2.);
 
-        create_change_field_type_group!(ui, response, BLACK, LIGHT_RED, F32, F64);
+        create_change_field_type_group!(ui, response, BLACK, LIGHT_RED, F16, BF16, F32, F64);
+
+        ui.menu_button("Fixed", |ui| self.fixed_menu(ui, response))
+            .response
+            .on_hover_text("Signed fixed-point number, i.e. Q16.16");
 
         ui.separator();
         ui.add_space(2.);
@@ -300,7 +618,225 @@ impl ToolBarPanel {
         ui.separator();
         ui.add_space(2.);
 
-        create_change_field_type_group!(ui, response, BLACK, BROWN, Ptr, StrPtr);
+        create_change_field_type_group!(ui, response, BLACK, BROWN, Ptr, VTable, Chain);
+
+        ui.menu_button("Relative", |ui| {
+            ui.set_width(120.);
+
+            ui.vertical_centered_justified(|ui| {
+                for (kind, label) in [
+                    (FieldKind::RelPtr32(RelativeTo::Field), "i32 from field"),
+                    (FieldKind::RelPtr32(RelativeTo::End), "i32 from end"),
+                    (FieldKind::RelPtr64(RelativeTo::Field), "i64 from field"),
+                    (FieldKind::RelPtr64(RelativeTo::End), "i64 from end"),
+                ] {
+                    if ui.button(label).clicked() {
+                        *response = Some(ToolBarResponse::ChangeKind(kind));
+                        ui.close_menu();
+                    }
+                }
+            });
+        })
+        .response
+        .on_hover_text("Pointer stored as an offset from the field or its end(RIP-relative)");
+
+        ui.menu_button("Union", |ui| self.union_menu(ui, response))
+            .response
+            .on_hover_text("Union of fields or temporary overlay of the selected field");
+
+        ui.menu_button("Text", |ui| self.text_kind_menu(ui, response))
+            .response
+            .on_hover_text("Inline or pointed to string");
+
+        ui.menu_button("Semantic", |ui| {
+            ui.set_width(120.);
+
+            ui.vertical_centered_justified(|ui| {
+                for (kind, label) in [
+                    (FieldKind::Guid, "GUID"),
+                    (FieldKind::Uuid, "UUID"),
+                    (FieldKind::UnixTime(TimeUnit::Seconds), "Unix time(s)"),
+                    (FieldKind::UnixTime(TimeUnit::Millis), "Unix time(ms)"),
+                    (FieldKind::UnixTime(TimeUnit::Nanos), "Unix time(ns)"),
+                    (FieldKind::Timespec, "timespec"),
+                    (FieldKind::Timeval, "timeval"),
+                    (FieldKind::Ipv4, "IPv4"),
+                    (FieldKind::Ipv6, "IPv6"),
+                    (FieldKind::Port, "Port"),
+                    (FieldKind::Rgba, "RGBA"),
+                ] {
+                    if ui.button(label).clicked() {
+                        *response = Some(ToolBarResponse::ChangeKind(kind));
+                        ui.close_menu();
+                    }
+                }
+            });
+        })
+        .response
+        .on_hover_text("Identifiers, timestamps, network addresses and colours");
+
+        ui.menu_button("Container", |ui| {
+            ui.set_width(120.);
+
+            ui.vertical_centered_justified(|ui| {
+                for (kind, label) in [
+                    (FieldKind::StdString, "std::string"),
+                    (FieldKind::StdVector, "std::vector"),
+                    (FieldKind::StdList, "std::list"),
+                    (FieldKind::RustString, "String"),
+                    (FieldKind::RustVec, "Vec"),
+                    (FieldKind::RustList, "LinkedList"),
+                    (FieldKind::IntrusiveList, "Intrusive list"),
+                ] {
+                    if ui.button(label).clicked() {
+                        *response = Some(ToolBarResponse::ChangeKind(kind));
+                        ui.close_menu();
+                    }
+                }
+            });
+        })
+        .response
+        .on_hover_text("Right click the element type of a field to change it");
+    }
+
+    fn comment_menu(&mut self, ui: &mut Ui, response: &mut Option<ToolBarResponse>) {
+        ui.set_width(240.);
+
+        let state = self.state.borrow();
+        let Some(selection) = state.selection else {
+            ui.label("Select a field first");
+            return;
+        };
+
+        if self.comment_field != Some(selection.field_id) {
+            self.comment_field = Some(selection.field_id);
+            self.comment_buf = state
+                .class_list
+                .by_id(selection.container_id)
+                .and_then(|c| c.field_comments.get(&selection.field_id))
+                .cloned()
+                .unwrap_or_default();
+        }
+        drop(state);
+
+        ui.add(
+            TextEdit::multiline(&mut self.comment_buf)
+                .desired_rows(4)
+                .hint_text("How was this field identified?"),
+        );
+        ui.vertical_centered_justified(|ui| {
+            if ui.button("Apply").clicked() {
+                *response = Some(ToolBarResponse::SetComment(
+                    self.comment_buf.trim().to_owned(),
+                ));
+                ui.close_menu();
+            }
+        });
+    }
+
+    fn union_menu(&mut self, ui: &mut Ui, response: &mut Option<ToolBarResponse>) {
+        ui.set_width(120.);
+
+        ui.horizontal(|ui| {
+            ui.add(TextEdit::singleline(&mut self.union_size).desired_width(48.));
+            ui.label("Size");
+        });
+
+        let size = self
+            .union_size
+            .value()
+            .and_then(|v| v.ok())
+            .copied()
+            .filter(|size| *size > 0);
+
+        ui.add_enabled_ui(size.is_some(), |ui| {
+            ui.vertical_centered_justified(|ui| {
+                if ui.button("Create union").clicked() {
+                    *response = Some(ToolBarResponse::ChangeKind(FieldKind::Union(size.unwrap())));
+                    ui.close_menu();
+                }
+            });
+        });
+
+        ui.separator();
+        ui.label("Overlay");
+        ui.vertical_centered_justified(|ui| {
+            for kind in ALTERNATIVE_KINDS {
+                if ui.button(format!("{kind:?}")).clicked() {
+                    *response = Some(ToolBarResponse::AddOverlay(*kind));
+                    ui.close_menu();
+                }
+            }
+
+            if ui.button("Clear overlays").clicked() {
+                *response = Some(ToolBarResponse::ClearOverlays);
+                ui.close_menu();
+            }
+        });
+    }
+
+    fn fixed_menu(&mut self, ui: &mut Ui, response: &mut Option<ToolBarResponse>) {
+        ui.set_width(120.);
+
+        ui.horizontal(|ui| {
+            ui.add(TextEdit::singleline(&mut self.fixed_frac).desired_width(48.));
+            ui.label("Fractional bits");
+        });
+
+        let frac = self.fixed_frac.value().and_then(|v| v.ok()).copied();
+
+        ui.vertical_centered_justified(|ui| {
+            for (kind, label) in [
+                (FieldKind::Fixed32(frac.unwrap_or_default()), "32-bit"),
+                (FieldKind::Fixed64(frac.unwrap_or_default()), "64-bit"),
+            ] {
+                let valid = frac.is_some_and(|frac| (frac as usize) < kind.size() * 8);
+                if ui.add_enabled(valid, Button::new(label)).clicked() {
+                    *response = Some(ToolBarResponse::ChangeKind(kind));
+                    ui.close_menu();
+                }
+            }
+        });
+    }
+
+    fn text_kind_menu(&mut self, ui: &mut Ui, response: &mut Option<ToolBarResponse>) {
+        ui.set_width(120.);
+
+        for (encoding, label) in TextEncoding::NAMED_VARIANTS {
+            ui.radio_value(&mut self.text_encoding, *encoding, *label);
+        }
+
+        ui.horizontal(|ui| {
+            ui.add(TextEdit::singleline(&mut self.text_len).desired_width(48.));
+            ui.label("Length");
+        });
+
+        let len = self
+            .text_len
+            .value()
+            .and_then(|v| v.ok())
+            .copied()
+            .filter(|len| *len > 0);
+
+        ui.add_enabled_ui(len.is_some(), |ui| {
+            ui.vertical_centered_justified(|ui| {
+                if ui.button("Inline").clicked() {
+                    *response = Some(ToolBarResponse::ChangeKind(FieldKind::Text(
+                        self.text_encoding,
+                        len.unwrap(),
+                    )));
+                    ui.close_menu();
+                }
+
+                if ui.button("Pointer").clicked() {
+                    *response = Some(ToolBarResponse::ChangeKind(FieldKind::TextPtr(
+                        self.text_encoding,
+                        len.unwrap(),
+                    )));
+                    ui.close_menu();
+                }
+            });
+        });
     }
 }
 
@@ -314,6 +850,31 @@ fn shortcut_button(
         .clicked()
 }
 
+/// Picks a file of the format and adds the classes read from it to the project.
+fn import_file(
+    state: &mut GlobalState,
+    format: &str,
+    extensions: &[&str],
+    import: impl FnOnce(Vec<u8>) -> eyre::Result<Imported>,
+) {
+    let Some(path) = rfd::FileDialog::new()
+        .set_title(&format!("Import {format}"))
+        .add_filter(format, extensions)
+        .pick_file()
+    else {
+        return;
+    };
+
+    match fs::read(&path).map_err(Into::into).and_then(import) {
+        Ok(imported) => state.import(imported),
+        Err(e) => {
+            _ = state
+                .toasts
+                .error(format!("Failed to import {format}. {e}"))
+        }
+    }
+}
+
 fn attach_to_process(state: &mut GlobalState, name: &str, response: &mut Option<ToolBarResponse>) {
     let last_proc = match ProcessIterator::new() {
         Ok(mut piter) => piter.find(|pe| pe.name.eq_ignore_ascii_case(name)),
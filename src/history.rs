@@ -0,0 +1,291 @@
+/// This module contains undo/redo history of structural changes to the classes.
+use crate::{
+    class::{Class, ClassId, ClassList},
+    field::{Field, FieldId},
+};
+use std::mem::replace;
+
+/// How many edits can be undone.
+const MAX_EDITS: usize = 256;
+
+/// Reversible structural change of the class list.
+pub enum Edit {
+    /// Replaces `len` fields of the class starting at `pos` with `fields`.
+    Splice {
+        class: ClassId,
+        pos: usize,
+        len: usize,
+        fields: Vec<Box<dyn Field>>,
+    },
+    InsertClass {
+        index: usize,
+        class: Class,
+    },
+    RemoveClass(ClassId),
+    RenameClass {
+        class: ClassId,
+        name: String,
+    },
+    /// Renames the field of the class, alternatives of unions included.
+    RenameField {
+        class: ClassId,
+        field: FieldId,
+        name: String,
+    },
+    SetBase {
+        class: ClassId,
+        base: Option<ClassId>,
+    },
+    /// Edits applied in order, i.e. removal of several classes.
+    Group(Vec<Edit>),
+}
+
+impl Edit {
+    /// Whether the edit changes nothing, i.e. reverts of edits whose target is gone.
+    fn is_empty(&self) -> bool {
+        matches!(self, Edit::Group(edits) if edits.is_empty())
+    }
+
+    /// Applies the edit and returns the edit that reverts it.
+    fn apply(self, list: &mut ClassList) -> Edit {
+        match self {
+            Edit::Splice {
+                class,
+                pos,
+                len,
+                fields,
+            } => {
+                let Some(target) = list.by_id_mut(class) else {
+                    return Edit::Group(vec![]);
+                };
+
+                let end = (pos + len).min(target.fields.len());
+                let inserted = fields.len();
+                let removed = target.fields.splice(pos..end, fields).collect();
+
+                Edit::Splice {
+                    class,
+                    pos,
+                    len: inserted,
+                    fields: removed,
+                }
+            }
+            Edit::InsertClass { index, class } => {
+                let id = class.id();
+                list.insert_class(index, class);
+                Edit::RemoveClass(id)
+            }
+            Edit::RemoveClass(id) => {
+                let inheritors = list
+                    .classes()
+                    .iter()
+                    .filter(|c| c.base() == Some(id))
+                    .map(|c| c.id())
+                    .collect::<Vec<_>>();
+
+                let Some((index, class)) = list.remove_class(id) else {
+                    return Edit::Group(vec![]);
+                };
+
+                // Class has to be back before its inheritors can point at it again.
+                let mut revert = vec![Edit::InsertClass { index, class }];
+                revert.extend(inheritors.into_iter().map(|class| Edit::SetBase {
+                    class,
+                    base: Some(id),
+                }));
+                Edit::Group(revert)
+            }
+            Edit::RenameClass { class, name } => match list.by_id_mut(class) {
+                Some(target) => Edit::RenameClass {
+                    class,
+                    name: replace(&mut target.name, name),
+                },
+                None => Edit::Group(vec![]),
+            },
+            Edit::RenameField { class, field, name } => {
+                let old = list.by_id(class).and_then(|target| {
+                    target
+                        .fields
+                        .iter()
+                        .chain(target.overlays.iter().map(|o| &o.field))
+                        .find_map(|f| f.rename(field, &name))
+                });
+
+                match old {
+                    Some(name) => Edit::RenameField { class, field, name },
+                    None => Edit::Group(vec![]),
+                }
+            }
+            Edit::SetBase { class, base } => {
+                let Some(old) = list.by_id(class).map(|c| c.base()) else {
+                    return Edit::Group(vec![]);
+                };
+
+                list.set_base(class, base);
+                Edit::SetBase { class, base: old }
+            }
+            Edit::Group(edits) => {
+                let mut reverts = edits
+                    .into_iter()
+                    .map(|edit| edit.apply(list))
+                    .filter(|edit| !edit.is_empty())
+                    .collect::<Vec<_>>();
+                reverts.reverse();
+                Edit::Group(reverts)
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct History {
+    undo: Vec<Edit>,
+    redo: Vec<Edit>,
+}
+
+impl History {
+    /// Applies the edit so it can be undone later, edits that change nothing are dropped.
+    pub fn apply(&mut self, list: &mut ClassList, edit: Edit) {
+        let revert = edit.apply(list);
+        if revert.is_empty() {
+            return;
+        }
+
+        self.undo.push(revert);
+        if self.undo.len() > MAX_EDITS {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+    }
+
+    /// Returns `false` if there is nothing to undo.
+    pub fn undo(&mut self, list: &mut ClassList) -> bool {
+        let Some(edit) = self.undo.pop() else {
+            return false;
+        };
+
+        let revert = edit.apply(list);
+        if !revert.is_empty() {
+            self.redo.push(revert);
+        }
+        true
+    }
+
+    /// Returns `false` if there is nothing to redo.
+    pub fn redo(&mut self, list: &mut ClassList) -> bool {
+        let Some(edit) = self.redo.pop() else {
+            return false;
+        };
+
+        let revert = edit.apply(list);
+        if !revert.is_empty() {
+            self.undo.push(revert);
+        }
+        true
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Forgets every edit, i.e. when another project is opened.
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::{IntField, UnionField};
+
+    /// Names of the fields, classes start with unnamed hex fields.
+    fn names(list: &ClassList, class: ClassId) -> Vec<String> {
+        list.by_id(class)
+            .unwrap()
+            .fields
+            .iter()
+            .filter_map(|f| f.name())
+            .collect()
+    }
+
+    #[test]
+    fn field_rename_is_undone() {
+        let mut list = ClassList::EMPTY;
+        let class = list.add_class("Player".into());
+        let field = IntField::<4>::signed("health".into());
+        let id = field.id();
+        list.by_id_mut(class).unwrap().fields.push(Box::new(field));
+
+        let mut history = History::default();
+        let edit = Edit::RenameField {
+            class,
+            field: id,
+            name: "hp".into(),
+        };
+        history.apply(&mut list, edit);
+        assert_eq!(names(&list, class), ["hp"]);
+
+        assert!(history.undo(&mut list));
+        assert_eq!(names(&list, class), ["health"]);
+        assert!(history.redo(&mut list));
+        assert_eq!(names(&list, class), ["hp"]);
+    }
+
+    #[test]
+    fn union_alternatives_are_renamed() {
+        let mut list = ClassList::EMPTY;
+        let class = list.add_class("Value".into());
+        let alternative = IntField::<4>::unsigned("bits".into());
+        let id = alternative.id();
+        let union = UnionField::with_alternatives("u".into(), 4, vec![Box::new(alternative)]);
+        list.by_id_mut(class).unwrap().fields.push(Box::new(union));
+
+        let mut history = History::default();
+        let edit = Edit::RenameField {
+            class,
+            field: id,
+            name: "raw".into(),
+        };
+        history.apply(&mut list, edit);
+        assert!(history.undo(&mut list));
+        assert!(history.redo(&mut list));
+
+        let fields = &list.by_id(class).unwrap().fields;
+        let union = fields.last().unwrap();
+        assert_eq!(union.rename(id, "check").as_deref(), Some("raw"));
+    }
+
+    #[test]
+    fn edits_of_missing_fields_are_dropped() {
+        let mut list = ClassList::EMPTY;
+        let class = list.add_class("Player".into());
+        let field = IntField::<4>::signed("health".into());
+        let id = field.id();
+        list.by_id_mut(class).unwrap().fields.push(Box::new(field));
+
+        let mut history = History::default();
+        let rename = |name: &str| Edit::RenameField {
+            class,
+            field: id,
+            name: name.into(),
+        };
+        history.apply(&mut list, rename("hp"));
+        assert!(history.undo(&mut list));
+
+        let missing = IntField::<4>::signed("armor".into()).id();
+        let edit = Edit::RenameField {
+            class,
+            field: missing,
+            name: "shield".into(),
+        };
+        history.apply(&mut list, edit);
+        assert!(!history.can_undo());
+        assert!(history.can_redo());
+    }
+}
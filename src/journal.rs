@@ -0,0 +1,286 @@
+/// This module contains the journal of values written by editing fields and patches made out of it.
+use crate::symbols::Module;
+use std::{
+    fmt::Write,
+    ops::Range,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// How many writes are remembered, the oldest ones are forgotten first.
+const MAX_ENTRIES: usize = 4096;
+
+pub struct JournalEntry {
+    pub address: usize,
+    pub old: Vec<u8>,
+    pub new: Vec<u8>,
+    pub time: SystemTime,
+}
+
+impl JournalEntry {
+    /// Time of the write as `HH:MM:SS UTC`.
+    pub fn clock(&self) -> String {
+        let secs = self
+            .time
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default()
+            % 86400;
+
+        format!(
+            "{:02}:{:02}:{:02} UTC",
+            secs / 3600,
+            secs % 3600 / 60,
+            secs % 60
+        )
+    }
+}
+
+#[derive(Default)]
+pub struct Journal {
+    entries: Vec<JournalEntry>,
+    /// Whether the last entry was written by a drag that may still go on.
+    dragging: bool,
+}
+
+impl Journal {
+    pub fn push(&mut self, entry: JournalEntry) {
+        self.dragging = false;
+        if self.entries.len() >= MAX_ENTRIES {
+            self.entries.remove(0);
+        }
+        self.entries.push(entry);
+    }
+
+    /// Records a write of a drag, i.e. of a colour picker that writes every frame.
+    /// Writes of the same drag are merged into one entry.
+    pub fn push_dragged(&mut self, entry: JournalEntry) {
+        if let Some(last) = self.entries.last_mut().filter(|_| self.dragging) {
+            if last.address == entry.address && last.new.len() == entry.new.len() {
+                if let Ok(elapsed) = entry.time.duration_since(last.time) {
+                    if elapsed.as_secs() < 1 {
+                        last.new = entry.new;
+                        last.time = entry.time;
+                        return;
+                    }
+                }
+            }
+        }
+
+        self.push(entry);
+        self.dragging = true;
+    }
+
+    /// Entries from the oldest to the newest.
+    pub fn entries(&self) -> &[JournalEntry] {
+        &self.entries[..]
+    }
+
+    /// Removes the entry and the newer ones overlapping it, or overlapping those,
+    /// they're returned from the newest to the oldest so they can be reverted in order.
+    pub fn remove(&mut self, index: usize) -> Vec<JournalEntry> {
+        if index >= self.entries.len() {
+            return vec![];
+        }
+
+        self.dragging = false;
+        let range = |e: &JournalEntry| e.address..e.address.saturating_add(e.new.len());
+        let mut removed = vec![self.entries.remove(index)];
+        let mut ranges = vec![range(&removed[0])];
+        let mut i = index;
+        while i < self.entries.len() {
+            let range = range(&self.entries[i]);
+            if ranges.iter().any(|r| overlap(r, &range)) {
+                ranges.push(range);
+                removed.push(self.entries.remove(i));
+            } else {
+                i += 1;
+            }
+        }
+
+        removed.reverse();
+        removed
+    }
+
+    pub fn take(&mut self) -> Vec<JournalEntry> {
+        self.dragging = false;
+        std::mem::take(&mut self.entries)
+    }
+}
+
+fn overlap(a: &Range<usize>, b: &Range<usize>) -> bool {
+    a.start < b.end && b.start < a.end
+}
+
+/// Where a patch is applied. Addresses inside of modules are kept relative
+/// to the module so the patch survives ASLR.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PatchAddress {
+    Absolute(usize),
+    Module(String, usize),
+}
+
+impl PatchAddress {
+    pub fn resolve(&self, modules: &[Module]) -> Option<usize> {
+        match self {
+            Self::Absolute(address) => Some(*address),
+            Self::Module(name, offset) => modules
+                .iter()
+                .find(|m| m.name == *name)
+                .map(|m| m.base + offset),
+        }
+    }
+}
+
+/// Single write of a patch, it's only applied if memory still contains `old` bytes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PatchEntry {
+    pub address: PatchAddress,
+    pub old: Vec<u8>,
+    pub new: Vec<u8>,
+}
+
+/// Formats writes as a patch, one write per line: `module+0x10: 00 01 -> 02 03`.
+pub fn export_patch(entries: &[JournalEntry], modules: &[Module]) -> String {
+    let mut out = "# YClass patch\n".to_owned();
+
+    for entry in entries {
+        let address = match modules.iter().find(|m| m.contains(entry.address)) {
+            Some(module) => format!("{}+{:#X}", module.name, entry.address - module.base),
+            None => format!("{:#X}", entry.address),
+        };
+
+        _ = writeln!(
+            out,
+            "{address}: {} -> {}",
+            format_bytes(&entry.old),
+            format_bytes(&entry.new)
+        );
+    }
+
+    out
+}
+
+/// Parses patch in the format of [`export_patch`], empty lines and lines starting with `#` are skipped.
+pub fn parse_patch(text: &str) -> Result<Vec<PatchEntry>, String> {
+    text.lines()
+        .enumerate()
+        .map(|(i, line)| (i, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(i, line)| parse_patch_line(line).ok_or(format!("Line {} is invalid", i + 1)))
+        .collect()
+}
+
+fn parse_patch_line(line: &str) -> Option<PatchEntry> {
+    let (address, bytes) = line.split_once(':')?;
+    let (old, new) = bytes.split_once("->")?;
+    let (old, new) = (parse_bytes(old)?, parse_bytes(new)?);
+    if old.len() != new.len() || new.is_empty() {
+        return None;
+    }
+
+    let parse_hex = |text: &str| {
+        let text = text.trim();
+        usize::from_str_radix(text.strip_prefix("0x").unwrap_or(text), 16).ok()
+    };
+    let address = match address.rsplit_once('+') {
+        Some((module, offset)) => {
+            PatchAddress::Module(module.trim().to_owned(), parse_hex(offset)?)
+        }
+        None => PatchAddress::Absolute(parse_hex(address)?),
+    };
+
+    Some(PatchEntry { address, old, new })
+}
+
+pub fn format_bytes(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|b| format!("{b:02X}"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn parse_bytes(text: &str) -> Option<Vec<u8>> {
+    text.split_whitespace()
+        .map(|b| u8::from_str_radix(b, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn entry(address: usize, old: &[u8], new: &[u8]) -> JournalEntry {
+        JournalEntry {
+            address,
+            old: old.to_vec(),
+            new: new.to_vec(),
+            time: UNIX_EPOCH,
+        }
+    }
+
+    #[test]
+    fn exported_patch_is_parsed_back() {
+        let modules = [Module::new("game.exe", 0x1000, 0x100)];
+        let entries = [entry(0x1010, &[0, 1], &[2, 3]), entry(0x2000, &[4], &[5])];
+
+        let patch = export_patch(&entries, &modules);
+        assert_eq!(
+            patch,
+            "# YClass patch\ngame.exe+0x10: 00 01 -> 02 03\n0x2000: 04 -> 05\n"
+        );
+        assert_eq!(
+            parse_patch(&patch).unwrap(),
+            [
+                PatchEntry {
+                    address: PatchAddress::Module("game.exe".into(), 0x10),
+                    old: vec![0, 1],
+                    new: vec![2, 3],
+                },
+                PatchEntry {
+                    address: PatchAddress::Absolute(0x2000),
+                    old: vec![4],
+                    new: vec![5],
+                },
+            ]
+        );
+        assert_eq!(
+            parse_patch("0x10: 00 -> 01 02").unwrap_err(),
+            "Line 1 is invalid"
+        );
+    }
+
+    #[test]
+    fn newer_overlapping_entries_are_removed_too() {
+        let mut journal = Journal::default();
+        journal.push(entry(0x10, &[0, 0], &[1, 1]));
+        journal.push(entry(0x20, &[0], &[2]));
+        journal.push(entry(0x11, &[1, 0], &[3, 3]));
+        journal.push(entry(0x12, &[3], &[4]));
+
+        let removed = journal.remove(0);
+        let addresses = removed.iter().map(|e| e.address).collect::<Vec<_>>();
+        assert_eq!(addresses, [0x12, 0x11, 0x10]);
+        assert_eq!(journal.entries().len(), 1);
+        assert!(journal.remove(1).is_empty());
+    }
+
+    #[test]
+    fn only_writes_of_a_drag_are_merged() {
+        let mut journal = Journal::default();
+        journal.push(entry(0x10, &[0], &[1]));
+        journal.push(entry(0x10, &[1], &[2]));
+        assert_eq!(journal.entries().len(), 2);
+
+        let mut dragged = entry(0x10, &[2], &[3]);
+        journal.push_dragged(entry(0x10, &[2], &[3]));
+        dragged.time += Duration::from_millis(100);
+        dragged.new = vec![4];
+        journal.push_dragged(dragged);
+
+        let last = journal.entries().last().unwrap();
+        assert_eq!(journal.entries().len(), 3);
+        assert_eq!((&last.old[..], &last.new[..]), (&[2][..], &[4][..]));
+    }
+}
@@ -12,12 +12,18 @@ mod class;
 mod config;
 mod context;
 mod field;
+mod freeze;
 mod generator;
 mod gui;
+mod history;
 mod hotkeys;
+mod journal;
 mod process;
 mod project;
+mod recovery;
+mod session;
 mod state;
+mod symbols;
 mod value;
 
 use config::YClassConfig;
@@ -34,6 +40,11 @@ use std::cell::RefCell;
 const FID_M: FontId = FontId::monospace(16.);
 
 fn main() {
+    let args = std::env::args().collect::<Vec<_>>();
+    if args.get(1).map(String::as_str) == Some("merge") {
+        std::process::exit(project::merge::run(&args[2..]));
+    }
+
     eframe::run_native(
         "YClass",
         NativeOptions {
@@ -60,13 +71,19 @@ fn main() {
             hotkeys.register("attach_process", Key::A, Modifiers::ALT);
             hotkeys.register("attach_recent", Key::A, Modifiers::ALT | Modifiers::CTRL);
             hotkeys.register("detach_process", Key::D, Modifiers::ALT);
+            hotkeys.register("undo", Key::Z, Modifiers::CTRL);
+            hotkeys.register("redo", Key::Y, Modifiers::CTRL);
+            hotkeys.register("freeze", Key::F, Modifiers::CTRL);
+
+            let state = GlobalState {
+                config,
+                hotkeys,
+                ..Default::default()
+            };
+            freeze::spawn_freezer(state.process.clone(), state.freeze_interval.clone());
 
             Box::new(app::YClassApp::new(Box::leak(Box::new(RefCell::new(
-                GlobalState {
-                    config,
-                    hotkeys,
-                    ..Default::default()
-                },
+                state,
             )))))
         }),
     )
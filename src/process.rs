@@ -1,7 +1,17 @@
-use crate::config::YClassConfig;
+use crate::{
+    config::YClassConfig,
+    freeze::FrozenValue,
+    journal::{Journal, JournalEntry, PatchEntry},
+    symbols::{collect_modules, Module},
+};
 use libloading::Library;
 use memflex::external::{MemoryRegion, OwnedProcess};
-use std::fs;
+use parking_lot::{Mutex, MutexGuard};
+use std::{
+    fs,
+    sync::atomic::{AtomicBool, Ordering},
+    time::SystemTime,
+};
 
 pub struct ManagedExtension {
     #[allow(dead_code)]
@@ -22,11 +32,20 @@ impl Drop for ManagedExtension {
     }
 }
 
-pub enum Process {
-    Internal((OwnedProcess, Vec<MemoryRegion>)),
+pub enum ProcessBackend {
+    Internal((OwnedProcess, Vec<MemoryRegion>, Vec<Module>)),
     Managed(ManagedExtension),
 }
 
+pub struct Process {
+    pub backend: ProcessBackend,
+    /// Values written by editing fields.
+    journal: Mutex<Journal>,
+    frozen: Mutex<Vec<FrozenValue>>,
+    /// Every write is skipped while set.
+    read_only: AtomicBool,
+}
+
 impl Process {
     pub fn attach(pid: u32, config: &YClassConfig) -> eyre::Result<Self> {
         let (path, modified) = (
@@ -38,7 +57,7 @@ impl Process {
         );
 
         let metadata = fs::metadata(&path);
-        Ok(if metadata.is_ok() {
+        let backend = if metadata.is_ok() {
             let lib = unsafe { Library::new(&path)? };
             let attach = unsafe { *lib.get::<fn(u32) -> u32>(b"yc_attach")? };
             let read = unsafe { *lib.get::<fn(usize, *mut u8, usize) -> u32>(b"yc_read")? };
@@ -58,7 +77,7 @@ impl Process {
 
             (ext.attach)(pid);
 
-            Self::Managed(ext)
+            ProcessBackend::Managed(ext)
         } else if modified {
             #[allow(clippy::unnecessary_unwrap)]
             return Err(metadata.unwrap_err().into());
@@ -79,46 +98,234 @@ impl Process {
             };
 
             let maps = proc.maps()?;
-            Self::Internal((proc, maps))
+            let modules = collect_modules(&proc);
+            ProcessBackend::Internal((proc, maps, modules))
+        };
+
+        Ok(Self {
+            backend,
+            journal: Mutex::default(),
+            frozen: Mutex::default(),
+            read_only: AtomicBool::new(config.read_only.unwrap_or_default()),
         })
     }
 
     pub fn read(&self, address: usize, buf: &mut [u8]) {
-        match self {
+        match &self.backend {
             // TODO(ItsEthra): Proper error handling maybe?.
-            Self::Internal((op, _)) => _ = op.read_buf(address, buf),
-            Self::Managed(ext) => _ = (ext.read)(address, buf.as_mut_ptr(), buf.len()),
+            ProcessBackend::Internal((op, ..)) => _ = op.read_buf(address, buf),
+            ProcessBackend::Managed(ext) => _ = (ext.read)(address, buf.as_mut_ptr(), buf.len()),
         };
     }
 
-    pub fn write(&self, address: usize, buf: &[u8]) {
-        match self {
+    /// Writes `buf` and records the write in the journal so it can be reverted.
+    /// Returns `false` if the write was refused because the process is read-only.
+    pub fn write(&self, address: usize, buf: &[u8]) -> bool {
+        self.write_recorded(address, buf, false)
+    }
+
+    /// Writes `buf` of a widget being dragged, i.e. a colour picker, consecutive
+    /// writes of the drag are recorded as one, see [`Process::write`].
+    pub fn write_dragged(&self, address: usize, buf: &[u8]) -> bool {
+        self.write_recorded(address, buf, true)
+    }
+
+    fn write_recorded(&self, address: usize, buf: &[u8], dragged: bool) -> bool {
+        if self.is_read_only() {
+            return false;
+        }
+
+        let mut old = vec![0; buf.len()];
+        self.read(address, &mut old);
+        self.write_unrecorded(address, buf);
+
+        // Editing a frozen field changes the value it's frozen at.
+        self.frozen
+            .lock()
+            .iter_mut()
+            .filter(|v| v.address == address && v.bytes.len() == buf.len())
+            .for_each(|v| v.bytes = buf.to_vec());
+
+        let entry = JournalEntry {
+            address,
+            old,
+            new: buf.to_vec(),
+            time: SystemTime::now(),
+        };
+        let mut journal = self.journal.lock();
+        if dragged {
+            journal.push_dragged(entry);
+        } else {
+            journal.push(entry);
+        }
+        true
+    }
+
+    /// Writes `buf` without recording it in the journal, see [`Process::write`].
+    pub fn write_unrecorded(&self, address: usize, buf: &[u8]) -> bool {
+        if self.is_read_only() {
+            return false;
+        }
+
+        match &self.backend {
             // TODO(ItsEthra): Proper error handling maybe?.
-            Self::Internal((op, _)) => _ = op.write_buf(address, buf),
-            Self::Managed(ext) => _ = (ext.write)(address, buf.as_ptr(), buf.len()),
+            ProcessBackend::Internal((op, ..)) => _ = op.write_buf(address, buf),
+            ProcessBackend::Managed(ext) => _ = (ext.write)(address, buf.as_ptr(), buf.len()),
         };
+        true
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.read_only.load(Ordering::Relaxed)
+    }
+
+    pub fn set_read_only(&self, read_only: bool) {
+        self.read_only.store(read_only, Ordering::Relaxed);
+    }
+
+    pub fn journal(&self) -> MutexGuard<'_, Journal> {
+        self.journal.lock()
+    }
+
+    pub fn frozen(&self) -> MutexGuard<'_, Vec<FrozenValue>> {
+        self.frozen.lock()
+    }
+
+    pub fn is_frozen(&self, address: usize) -> bool {
+        self.frozen.lock().iter().any(|v| v.address == address)
+    }
+
+    /// Freezes the value at `address`, or unfreezes it if it was already frozen.
+    /// Returns `true` if the value is frozen now.
+    pub fn toggle_freeze(&self, address: usize, size: usize, name: String) -> bool {
+        let mut frozen = self.frozen.lock();
+        if let Some(i) = frozen.iter().position(|v| v.address == address) {
+            frozen.remove(i);
+            return false;
+        }
+
+        let mut bytes = vec![0; size];
+        self.read(address, &mut bytes);
+        frozen.push(FrozenValue {
+            address,
+            bytes,
+            name,
+        });
+        true
+    }
+
+    /// Writes every frozen value back.
+    pub fn rewrite_frozen(&self) {
+        for value in self.frozen.lock().iter() {
+            self.write_unrecorded(value.address, &value.bytes);
+        }
+    }
+
+    /// Restores bytes overwritten by the journal entry and forgets it, newer entries
+    /// overlapping it are reverted first so their old bytes don't stay behind.
+    /// Returns `false` and keeps the entries if the process is read-only.
+    pub fn revert(&self, index: usize) -> bool {
+        if self.is_read_only() {
+            return false;
+        }
+
+        let entries = self.journal.lock().remove(index);
+        for entry in entries {
+            self.write_unrecorded(entry.address, &entry.old);
+        }
+        true
+    }
+
+    /// Reverts every write in the journal, the newest first, see [`Process::revert`].
+    pub fn revert_all(&self) -> bool {
+        if self.is_read_only() {
+            return false;
+        }
+
+        let entries = self.journal.lock().take();
+        for entry in entries.iter().rev() {
+            self.write_unrecorded(entry.address, &entry.old);
+        }
+        true
+    }
+
+    /// Applies writes of the patch whose memory still contains the old bytes,
+    /// returns how many were applied. Applied writes are recorded in the journal.
+    pub fn apply_patch(&self, patch: &[PatchEntry]) -> usize {
+        patch
+            .iter()
+            .filter(|entry| {
+                let Some(address) = entry.address.resolve(self.modules()) else {
+                    return false;
+                };
+
+                let mut current = vec![0; entry.old.len()];
+                self.read(address, &mut current);
+                if current != entry.old || self.is_read_only() {
+                    return false;
+                }
+
+                self.write(address, &entry.new);
+                true
+            })
+            .count()
+    }
+
+    /// Modules of the process, empty for plugins.
+    pub fn modules(&self) -> &[Module] {
+        match &self.backend {
+            ProcessBackend::Internal((_, _, modules)) => modules,
+            ProcessBackend::Managed(_) => &[],
+        }
+    }
+
+    /// Module of the executable the process was started from.
+    pub fn executable(&self) -> Option<&Module> {
+        #[cfg(unix)]
+        if let Ok(exe) = fs::read_link(format!("/proc/{}/exe", self.id())) {
+            if let Some(module) = self.modules().iter().find(|m| m.path() == exe) {
+                return Some(module);
+            }
+        }
+
+        self.modules().first()
     }
 
     pub fn id(&self) -> u32 {
-        match self {
-            Self::Internal((op, _)) => op.id(),
-            Self::Managed(ext) => ext.pid,
+        match &self.backend {
+            ProcessBackend::Internal((op, ..)) => op.id(),
+            ProcessBackend::Managed(ext) => ext.pid,
         }
     }
 
     pub fn can_read(&self, address: usize) -> bool {
-        match self {
-            Self::Internal((_, maps)) => maps
+        match &self.backend {
+            ProcessBackend::Internal((_, maps, _)) => maps
                 .iter()
                 .any(|map| map.from <= address && map.to >= address && map.prot.read()),
-            Self::Managed(ext) => (ext.can_read)(address),
+            ProcessBackend::Managed(ext) => (ext.can_read)(address),
         }
     }
 
     pub fn name(&self) -> eyre::Result<String> {
-        match self {
-            Self::Internal((op, _)) => op.name().map_err(Into::into),
-            Self::Managed(_) => Ok("[MANAGED]".into()),
+        match &self.backend {
+            ProcessBackend::Internal((op, ..)) => op.name().map_err(Into::into),
+            ProcessBackend::Managed(_) => Ok("[MANAGED]".into()),
+        }
+    }
+
+    /// Describes `address` as `module!symbol+offset` or `module+offset`.
+    pub fn describe_address(&self, address: usize) -> Option<String> {
+        match &self.backend {
+            ProcessBackend::Internal((op, _, modules)) => {
+                let module = modules.iter().find(|m| m.contains(address))?;
+                Some(match module.symbol_at(op, address) {
+                    Some((symbol, 0)) => format!("{}!{symbol}", module.name),
+                    Some((symbol, offset)) => format!("{}!{symbol}+{offset:#X}", module.name),
+                    None => format!("{}+{:#X}", module.name, address - module.base),
+                })
+            }
+            ProcessBackend::Managed(_) => None,
         }
     }
 }
@@ -1,29 +1,243 @@
 /// This module contains structures that serialize/deserialize project data(i.e. classes).
 use crate::{
-    class::{Class, ClassList},
-    field::{allocate_padding, CodegenData, Field, FieldKind, PointerField},
-    generator::Generator,
+    class::{Class, ClassId, ClassList},
+    field::{
+        allocate_padding, ChainField, CodegenData, ContainerElement, ContainerField, Field,
+        FieldKind, NumberFormat, PointerField, UnionField, VTableField, VTableSlot, ELEMENT_KINDS,
+    },
+    generator::{BaseClass, ElementType, Generator},
+    session::Session,
 };
+use ron::{error::SpannedError, ser::PrettyConfig};
 use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+pub mod cpp;
+pub mod dwarf;
+mod layout;
+pub mod merge;
+pub mod reclass;
+pub mod rust;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 struct DataField {
     name: String,
     offset: usize,
     kind: FieldKind,
     metadata: Option<String>,
+    /// Element kind of containers, class elements are stored in `metadata` instead.
+    #[serde(default)]
+    element: Option<FieldKind>,
+    /// Functions of the virtual table.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    slots: Vec<VTableSlot>,
+    /// Offsets of the pointer chain, value kind is stored in `element`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    offsets: Vec<usize>,
+    /// Alternatives of the union, their offsets are relative to the union.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    alternatives: Vec<DataField>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    comment: Option<String>,
+    /// Id of the class pointers and containers refer to, `metadata` keeps its name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    class: Option<ClassId>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl DataField {
+    fn new(name: &str, kind: FieldKind) -> Self {
+        Self {
+            name: name.to_owned(),
+            offset: 0,
+            kind,
+            metadata: None,
+            element: None,
+            slots: vec![],
+            offsets: vec![],
+            alternatives: vec![],
+            comment: None,
+            class: None,
+        }
+    }
+
+    /// Whether `metadata` is a name of the class the field refers to.
+    fn refers_to_class(&self) -> bool {
+        match self.kind {
+            FieldKind::Ptr | FieldKind::RelPtr32(_) | FieldKind::RelPtr64(_) => true,
+            FieldKind::StdVector
+            | FieldKind::RustVec
+            | FieldKind::StdList
+            | FieldKind::RustList
+            | FieldKind::IntrusiveList => self.element.is_none(),
+            _ => false,
+        }
+    }
+
+    /// Stores ids of referenced classes so renaming them doesn't break the reference.
+    fn link_classes(&mut self, ids: &HashMap<&str, ClassId>) {
+        if self.refers_to_class() {
+            self.class = self
+                .metadata
+                .as_deref()
+                .and_then(|name| ids.get(name))
+                .copied();
+        }
+
+        self.alternatives
+            .iter_mut()
+            .for_each(|alt| alt.link_classes(ids));
+    }
+
+    /// Creates the field, invalid element kinds are replaced and a warning is added.
+    fn into_field(
+        self,
+        class: &str,
+        list: &mut ClassList,
+        warnings: &mut Vec<String>,
+    ) -> Box<dyn Field> {
+        // Finds referenced class or creates a new one if it doesn't exist.
+        let resolve_class = |list: &mut ClassList| {
+            let classname = self.metadata.as_deref();
+            if let Some(refclass) = self
+                .class
+                .and_then(|id| list.by_id(id))
+                .or_else(|| classname.and_then(|name| list.by_name(name)))
+            {
+                refclass.id()
+            } else {
+                list.add_class(
+                    classname
+                        .map(str::to_owned)
+                        .unwrap_or_else(|| format!("C{:X}", self.offset)),
+                )
+            }
+        };
+
+        match self.kind {
+            FieldKind::Ptr | FieldKind::RelPtr32(_) | FieldKind::RelPtr64(_) => {
+                let refid = resolve_class(list);
+                Box::new(PointerField::new_with_class_id(self.name, self.kind, refid))
+            }
+            FieldKind::StdVector
+            | FieldKind::RustVec
+            | FieldKind::StdList
+            | FieldKind::RustList
+            | FieldKind::IntrusiveList => {
+                let element = if let Some(mut element) = self.element {
+                    if !ELEMENT_KINDS.contains(&element) {
+                        warnings.push(format!(
+                            "Elements of `{}` in `{class}` can't be {element:?}, they're read as Unk64",
+                            self.name
+                        ));
+                        element = FieldKind::Unk64;
+                    }
+                    ContainerElement::Kind(element)
+                } else {
+                    ContainerElement::Class(resolve_class(list))
+                };
+
+                Box::new(ContainerField::new(self.name, self.kind, element))
+            }
+            FieldKind::VTable => Box::new(VTableField::with_slots(self.name, self.slots)),
+            FieldKind::Chain => {
+                let mut element = self.element.unwrap_or(FieldKind::I32);
+                let named = FieldKind::NAMED_VARIANTS.iter().any(|(k, _)| *k == element);
+                if !named || element.size() > 16 {
+                    warnings.push(format!(
+                        "Value of `{}` in `{class}` can't be {element:?}, it's read as I32",
+                        self.name
+                    ));
+                    element = FieldKind::I32;
+                }
+
+                Box::new(ChainField::with_chain(self.name, self.offsets, element))
+            }
+            FieldKind::Union(size) => {
+                let alternatives = self
+                    .alternatives
+                    .into_iter()
+                    .map(|alt| alt.into_field(class, list, warnings))
+                    .collect();
+                Box::new(UnionField::with_alternatives(self.name, size, alternatives))
+            }
+            other => other.into_formatted_field(
+                Some(self.name),
+                NumberFormat::from_metadata(self.metadata.as_deref()),
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 struct DataClass {
+    /// Stays the same across renames, missing in older projects.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    id: Option<ClassId>,
     name: String,
+    /// Name of the base class, offsets of fields are relative to the end of it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    base: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    base_id: Option<ClassId>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    comment: Option<String>,
     fields: Vec<DataField>,
 }
 
+/// Version of the format projects are saved in, bumped on every change
+/// older versions can't be read with. Projects without a version are version 1.
+pub const PROJECT_VERSION: u32 = 2;
+
+/// Upgrades a project to the next version, `MIGRATIONS[0]` upgrades version 1.
+const MIGRATIONS: [fn(&mut ProjectData); PROJECT_VERSION as usize - 1] = [link_class_ids];
+
+/// Reads only the version so newer projects are reported as such
+/// instead of failing on something unknown.
+#[derive(Deserialize)]
+struct ProjectHeader {
+    #[serde(default = "legacy_version")]
+    version: u32,
+}
+
+fn legacy_version() -> u32 {
+    1
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[non_exhaustive]
 pub struct ProjectData {
+    #[serde(default = "legacy_version")]
+    version: u32,
+    /// Paths of projects whose classes are imported, relative to this project.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    includes: Vec<String>,
     classes: Vec<DataClass>,
+    /// Forbids writing to memory while the project is open.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    read_only: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    session: Option<Session>,
+}
+
+/// Classes read from another format, see [`ProjectData::import`].
+pub struct Imported {
+    classes: Vec<DataClass>,
+    /// What couldn't be imported exactly.
+    pub warnings: Vec<String>,
+}
+
+impl Imported {
+    pub fn len(&self) -> usize {
+        self.classes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.classes.is_empty()
+    }
 }
 
 #[derive(Default, Clone)]
@@ -31,32 +245,88 @@ struct ProjectDataGenerator {
     classes: Vec<DataClass>,
     offset: usize,
     last_offset: usize,
+    in_union: bool,
+    comment: Option<String>,
+}
+
+impl ProjectDataGenerator {
+    fn push(&mut self, mut field: DataField) {
+        let fields = &mut self.classes.last_mut().unwrap().fields;
+
+        if self.in_union {
+            fields.last_mut().unwrap().alternatives.push(field);
+        } else {
+            let size = field.kind.size();
+            field.offset = self.offset;
+            field.comment = self.comment.take();
+            fields.push(field);
+
+            self.offset += size;
+            self.last_offset = self.offset;
+        }
+    }
 }
 
 impl Generator for &mut ProjectDataGenerator {
-    fn begin_class(&mut self, name: &str) {
+    fn begin_class(&mut self, name: &str, base: Option<BaseClass>) {
         self.classes.push(DataClass {
+            id: None,
             name: name.into(),
+            base: base.map(|b| b.name.to_owned()),
+            base_id: None,
+            comment: self.comment.take(),
             fields: vec![],
         });
     }
 
     fn add_field(&mut self, name: &str, kind: FieldKind, metadata: Option<&str>) {
-        let size = kind.size();
-
-        self.classes.last_mut().unwrap().fields.push(DataField {
+        self.push(DataField {
             metadata: metadata.map(|s| s.to_owned()),
-            name: name.to_owned(),
-            offset: self.offset,
-            kind,
+            ..DataField::new(name, kind)
         });
+    }
+
+    fn add_container(&mut self, name: &str, kind: FieldKind, element: ElementType) {
+        let (element, metadata) = match element {
+            ElementType::Kind(kind) => (Some(kind), None),
+            ElementType::Class(name) => (None, Some(name.to_owned())),
+        };
 
-        self.offset += size;
-        self.last_offset = self.offset;
+        self.push(DataField {
+            metadata,
+            element,
+            ..DataField::new(name, kind)
+        });
+    }
+
+    fn add_vtable(&mut self, name: &str, slots: &[VTableSlot]) {
+        self.push(DataField {
+            slots: slots.to_vec(),
+            ..DataField::new(name, FieldKind::VTable)
+        });
+    }
+
+    fn add_chain(&mut self, name: &str, offsets: &[usize], value: FieldKind) {
+        self.push(DataField {
+            element: Some(value),
+            offsets: offsets.to_vec(),
+            ..DataField::new(name, FieldKind::Chain)
+        });
+    }
+
+    fn begin_union(&mut self, name: &str, size: usize) {
+        self.push(DataField::new(name, FieldKind::Union(size)));
+        self.in_union = true;
+    }
+
+    fn end_union(&mut self) {
+        self.in_union = false;
     }
 
     fn add_offset(&mut self, offset: usize) {
-        self.offset += offset;
+        if !self.in_union {
+            self.offset += offset;
+        }
     }
 
     fn end_class(&mut self) {
@@ -64,50 +334,157 @@ impl Generator for &mut ProjectDataGenerator {
         self.last_offset = 0;
     }
 
+    fn add_comment(&mut self, comment: &str) {
+        self.comment = Some(comment.to_owned());
+    }
+
     fn finilize(&mut self) -> String {
         unimplemented!()
     }
 }
 
 impl ProjectData {
-    pub fn store(classes: &[Class]) -> Self {
+    /// Stores classes of the project, imported classes are left to their projects.
+    pub fn store(classes: &[Class], read_only: bool) -> Self {
         let mut datagen = ProjectDataGenerator::default();
         let dynam = &mut &mut datagen as &mut dyn Generator;
         let data = CodegenData { classes };
 
-        for class in classes {
-            dynam.begin_class(&class.name);
-            for f in class.fields.iter() {
-                f.codegen(dynam, &data);
-            }
-            dynam.end_class();
+        let own = classes.iter().filter(|c| c.library.is_none());
+        for class in own.clone() {
+            data.codegen_class(class, dynam);
+        }
+
+        let ids = classes
+            .iter()
+            .map(|c| (c.name.as_str(), c.id()))
+            .collect::<HashMap<_, _>>();
+        for (dataclass, class) in datagen.classes.iter_mut().zip(own) {
+            dataclass.id = Some(class.id());
+            dataclass.base_id = class.base();
+            dataclass
+                .fields
+                .iter_mut()
+                .for_each(|f| f.link_classes(&ids));
         }
 
         Self {
+            version: PROJECT_VERSION,
+            includes: vec![],
             classes: datagen.classes,
+            read_only,
+            session: None,
         }
     }
 
-    pub fn load(self) -> ClassList {
+    pub fn read_only(&self) -> bool {
+        self.read_only
+    }
+
+    pub fn with_session(mut self, session: Session) -> Self {
+        self.session = Some(session);
+        self
+    }
+
+    pub fn take_session(&mut self) -> Option<Session> {
+        self.session.take()
+    }
+
+    pub fn includes(&self) -> &[String] {
+        &self.includes
+    }
+
+    pub fn with_includes(mut self, includes: Vec<String>) -> Self {
+        self.includes = includes;
+        self
+    }
+
+    /// Adds imported classes, the ones named like an existing class are skipped.
+    /// Returns warnings of the import.
+    pub fn import(&mut self, imported: Imported) -> Vec<String> {
+        let mut warnings = imported.warnings;
+        let mut names = self
+            .classes
+            .iter()
+            .map(|c| c.name.clone())
+            .collect::<HashSet<_>>();
+
+        for class in imported.classes {
+            if names.insert(class.name.clone()) {
+                self.classes.push(class);
+            } else {
+                warnings.push(format!(
+                    "Class `{}` already exists, it's kept as is",
+                    class.name
+                ));
+            }
+        }
+
+        warnings
+    }
+
+    /// Loads classes of the project and of projects it includes, `dir` is where
+    /// the project is, include paths are relative to it. Returns warnings about fields
+    /// that couldn't be loaded as they are.
+    pub fn load(self, dir: Option<&Path>) -> eyre::Result<(ClassList, Vec<String>)> {
+        let mut classes = vec![];
+        collect_includes(&self.includes, dir, &mut classes, &mut HashSet::new())?;
+        classes.extend(self.classes.into_iter().map(|c| (c, None)));
+
+        let mut names = HashMap::new();
+        let mut ids = HashMap::new();
+        for (class, library) in &classes {
+            if let Some(other) = names.insert(&class.name, library) {
+                match library.as_ref().or(other.as_ref()) {
+                    Some(library) => eyre::bail!(
+                        "Class `{}` is defined more than once, also in {library}",
+                        class.name
+                    ),
+                    None => eyre::bail!("Class `{}` is defined more than once", class.name),
+                }
+            }
+
+            // References are resolved by id, they'd all go to one of the classes.
+            let Some(id) = class.id else {
+                continue;
+            };
+            if let Some((other, other_library)) = ids.insert(id, (&class.name, library)) {
+                match library.as_ref().or(other_library.as_ref()) {
+                    Some(library) => eyre::bail!(
+                        "Classes `{other}` and `{}` have the same id, one of them is in {library}",
+                        class.name
+                    ),
+                    None => eyre::bail!("Classes `{other}` and `{}` have the same id", class.name),
+                }
+            }
+        }
+
         let mut list = ClassList::EMPTY;
+        let mut warnings = vec![];
 
-        self.classes
+        classes
             .iter()
-            .for_each(|cl| _ = list.add_empty_class(cl.name.to_string()));
+            .for_each(|(cl, _)| _ = list.add_empty_class(cl.name.to_string(), cl.id));
 
-        self.classes.into_iter().for_each(|mut dataclass| {
+        classes.into_iter().for_each(|(mut dataclass, library)| {
             dataclass.fields.sort_by_key(|f| f.offset);
 
             let cid = list.by_name(&dataclass.name).unwrap().id();
+            let class = list.by_id_mut(cid).unwrap();
+            class.comment = dataclass.comment.unwrap_or_default();
+            class.library = library;
+            if let Some(base) = dataclass
+                .base_id
+                .and_then(|id| list.by_id(id))
+                .or_else(|| dataclass.base.and_then(|name| list.by_name(&name)))
+            {
+                list.set_base(cid, Some(base.id()));
+            }
             let mut current_offset = 0;
 
-            for DataField {
-                offset: field_offset,
-                name,
-                kind,
-                metadata,
-            } in dataclass.fields
-            {
+            for field in dataclass.fields {
+                let (field_offset, size) = (field.offset, field.kind.size());
+
                 let class = list.by_id_mut(cid).unwrap();
                 if field_offset > current_offset {
                     class
@@ -115,33 +492,15 @@ impl ProjectData {
                         .extend(allocate_padding(field_offset - current_offset));
                 }
 
-                match kind {
-                    FieldKind::Ptr => {
-                        let classname = metadata.as_deref();
-                        if let Some(refclass) = classname.and_then(|name| list.by_name(name)) {
-                            let refid = refclass.id();
-                            let class = list.by_id_mut(cid).unwrap();
-                            class
-                                .fields
-                                .push(Box::new(PointerField::new_with_class_id(name, refid))
-                                    as Box<dyn Field>);
-                        } else {
-                            let new_cid = list.add_class(
-                                classname
-                                    .map(str::to_owned)
-                                    .unwrap_or_else(|| format!("C{:X}", field_offset)),
-                            );
-                            let class = list.by_id_mut(cid).unwrap();
-                            class
-                                .fields
-                                .push(Box::new(PointerField::new_with_class_id(name, new_cid))
-                                    as Box<dyn Field>);
-                        }
-                    }
-                    other => class.fields.push(other.into_field(Some(name))),
+                let comment = field.comment.clone();
+                let field = field.into_field(&dataclass.name, &mut list, &mut warnings);
+                let class = list.by_id_mut(cid).unwrap();
+                if let Some(comment) = comment {
+                    class.field_comments.insert(field.id(), comment);
                 }
+                class.fields.push(field);
 
-                current_offset = field_offset + kind.size();
+                current_offset = field_offset + size;
             }
 
             if current_offset % 8 != 0 {
@@ -152,15 +511,199 @@ impl ProjectData {
             }
         });
 
-        list
+        Ok((list, warnings))
     }
 
-    pub fn from_str(text: &str) -> Option<Self> {
-        ron::from_str(text).ok()
+    /// Parses the project and migrates it to [`PROJECT_VERSION`].
+    pub fn from_str(text: &str) -> eyre::Result<Self> {
+        let header = ron::from_str::<ProjectHeader>(text).map_err(parse_error)?;
+        if header.version > PROJECT_VERSION {
+            eyre::bail!(
+                "Project is in format version {}, newer than supported {PROJECT_VERSION}",
+                header.version
+            );
+        } else if header.version == 0 {
+            eyre::bail!("Project format version 0 doesn't exist");
+        }
+
+        let mut pd = ron::from_str::<Self>(text).map_err(parse_error)?;
+        for migration in &MIGRATIONS[pd.version as usize - 1..] {
+            migration(&mut pd);
+        }
+        pd.version = PROJECT_VERSION;
+
+        Ok(pd)
     }
 
+    /// Pretty format with one field per line, so changes make small diffs.
     #[allow(clippy::inherent_to_string)]
     pub fn to_string(&self) -> String {
-        ron::to_string(self).unwrap()
+        ron::ser::to_string_pretty(self, PrettyConfig::new().new_line("\n".into())).unwrap()
+    }
+}
+
+/// Reads classes of included projects and projects they include, every project is read once.
+/// Classes are paired with the file name of their project.
+fn collect_includes(
+    includes: &[String],
+    dir: Option<&Path>,
+    classes: &mut Vec<(DataClass, Option<String>)>,
+    visited: &mut HashSet<PathBuf>,
+) -> eyre::Result<()> {
+    for include in includes {
+        let path = dir
+            .map(|d| d.join(include))
+            .unwrap_or_else(|| include.into());
+        let (path, pd) = path
+            .canonicalize()
+            .and_then(|path| {
+                let text = fs::read_to_string(&path)?;
+                Ok((path, text))
+            })
+            .map_err(Into::into)
+            .and_then(|(path, text)| ProjectData::from_str(&text).map(|pd| (path, pd)))
+            .map_err(|e| eyre::eyre!("Included project {include} can't be read. {e}"))?;
+
+        if !visited.insert(path.clone()) {
+            continue;
+        }
+
+        collect_includes(&pd.includes, path.parent(), classes, visited)?;
+        let library = path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| include.clone());
+        classes.extend(pd.classes.into_iter().map(|c| (c, Some(library.clone()))));
+    }
+
+    Ok(())
+}
+
+fn parse_error(e: SpannedError) -> eyre::Report {
+    eyre::eyre!(
+        "Line {}, column {}: {}",
+        e.position.line,
+        e.position.col,
+        e.code
+    )
+}
+
+/// Version 1 referred to classes only by their names.
+fn link_class_ids(pd: &mut ProjectData) {
+    let names = pd
+        .classes
+        .iter_mut()
+        .map(|c| {
+            (
+                c.name.clone(),
+                *c.id.get_or_insert_with(|| fastrand::usize(..)),
+            )
+        })
+        .collect::<Vec<_>>();
+    let ids = names
+        .iter()
+        .map(|(name, id)| (name.as_str(), *id))
+        .collect::<HashMap<_, _>>();
+
+    for class in &mut pd.classes {
+        class.base_id = class
+            .base
+            .as_deref()
+            .and_then(|name| ids.get(name))
+            .copied();
+        class.fields.iter_mut().for_each(|f| f.link_classes(&ids));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn version_1_classes_are_linked_by_ids() {
+        let pd = ProjectData::from_str(
+            r#"(classes: [
+                (name: "Entity", fields: [
+                    (name: "owner", offset: 0, kind: Ptr, metadata: Some("Player")),
+                ]),
+                (name: "Player", base: Some("Entity"), fields: [
+                    (name: "value", offset: 8, kind: Union(8), metadata: None, alternatives: [
+                        (name: "target", offset: 0, kind: Ptr, metadata: Some("Entity")),
+                    ]),
+                ]),
+            ])"#,
+        )
+        .unwrap();
+        assert_eq!(pd.version, PROJECT_VERSION);
+
+        let [entity, player] = &pd.classes[..] else {
+            panic!("{:?}", pd.classes);
+        };
+        assert!(entity.id.is_some() && player.id.is_some());
+        assert_ne!(entity.id, player.id);
+        assert_eq!(player.base_id, entity.id);
+        assert_eq!(entity.fields[0].class, player.id);
+        assert_eq!(player.fields[0].alternatives[0].class, entity.id);
+
+        let (classes, _) = pd.load(None).unwrap();
+        let player = classes.by_name("Player").unwrap();
+        assert_eq!(player.base(), classes.by_name("Entity").map(|c| c.id()));
+    }
+
+    #[test]
+    fn duplicate_class_ids_are_rejected() {
+        let pd = ProjectData::from_str(
+            r#"(version: 2, classes: [
+                (id: Some(1), name: "Entity", fields: []),
+                (id: Some(1), name: "Player", fields: []),
+            ])"#,
+        )
+        .unwrap();
+
+        let Err(error) = pd.load(None) else {
+            panic!("classes with the same id are loaded");
+        };
+        assert_eq!(
+            error.to_string(),
+            "Classes `Entity` and `Player` have the same id"
+        );
+    }
+
+    #[test]
+    fn invalid_container_elements_are_read_as_unk64() {
+        let pd = ProjectData::from_str(
+            r#"(version: 2, classes: [
+                (name: "Player", fields: [
+                    (name: "items", offset: 0, kind: StdVector, element: Some(StdVector)),
+                    (name: "scores", offset: 24, kind: StdVector, element: Some(I32)),
+                ]),
+            ])"#,
+        )
+        .unwrap();
+
+        let (_, warnings) = pd.load(None).unwrap();
+        assert_eq!(
+            warnings,
+            ["Elements of `items` in `Player` can't be StdVector, they're read as Unk64"]
+        );
+    }
+
+    #[test]
+    fn invalid_chain_values_are_read_as_i32() {
+        let pd = ProjectData::from_str(
+            r#"(version: 2, classes: [
+                (name: "Player", fields: [
+                    (name: "name", offset: 0, kind: Chain, element: Some(Text(Utf8, 32)), offsets: [8]),
+                    (name: "speed", offset: 8, kind: Chain, element: Some(F32), offsets: [8]),
+                ]),
+            ])"#,
+        )
+        .unwrap();
+
+        let (_, warnings) = pd.load(None).unwrap();
+        assert_eq!(
+            warnings,
+            ["Value of `name` in `Player` can't be Text(Utf8, 32), it's read as I32"]
+        );
     }
 }
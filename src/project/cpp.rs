@@ -0,0 +1,1274 @@
+/// This module contains import of classes from C/C++ headers. Only declarations are
+/// understood, layouts are computed for x86-64 with natural alignment and `#pragma pack`.
+use super::{
+    layout::{char_kind, Importer, Member, Record, Type},
+    Imported,
+};
+use crate::field::{FieldKind, TextEncoding, VTableSlot};
+use std::collections::HashMap;
+
+/// Reads every struct, class and union defined in the header.
+pub fn import(text: &str) -> Imported {
+    let mut parser = Parser::default();
+    parser.tokens = lex(text, &mut parser.constants);
+    parser.parse_scope();
+
+    Importer::new(
+        parser.records,
+        parser.types,
+        builtin_typedef,
+        parser.warnings,
+    )
+    .import()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Number(i64),
+    Punct(char),
+    /// `::`
+    Scope,
+    Pack(Pack),
+}
+
+/// `#pragma pack` directive.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Pack {
+    Push(Option<usize>),
+    Pop,
+    Set(Option<usize>),
+}
+
+/// Splits the header into tokens, `#define`s of numbers are stored in `constants`
+/// and other preprocessor directives are dropped.
+fn lex(text: &str, constants: &mut HashMap<String, i64>) -> Vec<Token> {
+    let chars = text.chars().collect::<Vec<_>>();
+    let mut tokens = vec![];
+    let mut line_start = true;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '\n' => {
+                line_start = true;
+                i += 1;
+                continue;
+            }
+            _ if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            '#' if line_start => {
+                let mut line = String::new();
+                i += 1;
+                while i < chars.len() && chars[i] != '\n' {
+                    if chars[i] == '\\' && chars.get(i + 1) == Some(&'\n') {
+                        i += 2;
+                        continue;
+                    }
+                    line.push(chars[i]);
+                    i += 1;
+                }
+                directive(&line, &mut tokens, constants);
+                continue;
+            }
+            '/' if chars.get(i + 1) == Some(&'/') => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+                continue;
+            }
+            '/' if chars.get(i + 1) == Some(&'*') => {
+                i += 2;
+                while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                    i += 1;
+                }
+                i += 2;
+                continue;
+            }
+            '"' | '\'' => {
+                let start = i;
+                i += 1;
+                while i < chars.len() && chars[i] != c {
+                    i += if chars[i] == '\\' { 2 } else { 1 };
+                }
+                i += 1;
+                if c == '\'' {
+                    let value = chars.get(start + 1).map_or(0, |&c| c as i64);
+                    tokens.push(Token::Number(value));
+                }
+            }
+            ':' if chars.get(i + 1) == Some(&':') => {
+                tokens.push(Token::Scope);
+                i += 2;
+            }
+            _ if c.is_ascii_digit() => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_alphanumeric() || "_.'".contains(chars[i])) {
+                    i += 1;
+                }
+                let literal = chars[start..i].iter().collect::<String>();
+                tokens.push(Token::Number(parse_number(&literal).unwrap_or_default()));
+            }
+            _ if c.is_alphabetic() || c == '_' || c == '$' => {
+                let start = i;
+                while i < chars.len()
+                    && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '$')
+                {
+                    i += 1;
+                }
+                tokens.push(Token::Ident(chars[start..i].iter().collect()));
+            }
+            _ => {
+                tokens.push(Token::Punct(c));
+                i += 1;
+            }
+        }
+        line_start = false;
+    }
+
+    tokens
+}
+
+fn directive(line: &str, tokens: &mut Vec<Token>, constants: &mut HashMap<String, i64>) {
+    let line = line.trim();
+    if let Some(rest) = line.strip_prefix("pragma") {
+        let Some(args) = rest
+            .trim()
+            .strip_prefix("pack")
+            .and_then(|r| r.trim().strip_prefix('('))
+            .and_then(|r| r.trim_end().strip_suffix(')'))
+        else {
+            return;
+        };
+
+        let args = args.split(',').map(str::trim).collect::<Vec<_>>();
+        let value = |s: &&str| parse_number(s).and_then(|n| usize::try_from(n).ok());
+        let pack = match args[..] {
+            ["push", .., n] if n != "push" => Pack::Push(value(&n)),
+            ["push"] => Pack::Push(None),
+            ["pop", ..] => Pack::Pop,
+            [n] => Pack::Set(value(&n)),
+            _ => return,
+        };
+        tokens.push(Token::Pack(pack));
+    } else if let Some(rest) = line.strip_prefix("define") {
+        let mut parts = rest.split_whitespace();
+        if let (Some(name), Some(value)) = (parts.next(), parts.next()) {
+            let value = value.trim_start_matches('(').trim_end_matches(')');
+            if let (false, Some(value)) = (name.contains('('), parse_number(value)) {
+                constants.insert(name.to_owned(), value);
+            }
+        }
+    }
+}
+
+/// Parses an integer literal, floating point literals are `0`.
+fn parse_number(literal: &str) -> Option<i64> {
+    let literal = literal.replace('\'', "");
+    if literal.contains('.') {
+        return Some(0);
+    }
+
+    let literal = literal.trim_end_matches(['u', 'U', 'l', 'L']);
+    if let Some(hex) = literal
+        .strip_prefix("0x")
+        .or_else(|| literal.strip_prefix("0X"))
+    {
+        u64::from_str_radix(hex, 16).ok().map(|n| n as i64)
+    } else if let Some(bin) = literal
+        .strip_prefix("0b")
+        .or_else(|| literal.strip_prefix("0B"))
+    {
+        i64::from_str_radix(bin, 2).ok()
+    } else if literal.len() > 1 && literal.starts_with('0') {
+        i64::from_str_radix(&literal[1..], 8).ok()
+    } else {
+        literal.parse().ok()
+    }
+}
+
+/// `alignas`, `__declspec(align)` and `__attribute__((aligned, packed))`.
+#[derive(Default)]
+struct Attributes {
+    align: Option<usize>,
+    packed: bool,
+}
+
+#[derive(Default)]
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    pack: Option<usize>,
+    pack_stack: Vec<Option<usize>>,
+    constants: HashMap<String, i64>,
+    types: HashMap<String, Type>,
+    records: Vec<Record>,
+    index: HashMap<String, usize>,
+    anonymous: usize,
+    warnings: Vec<String>,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn peek_at(&self, n: usize) -> Option<&Token> {
+        self.tokens.get(self.pos + n)
+    }
+
+    fn is_punct(&self, c: char) -> bool {
+        self.peek() == Some(&Token::Punct(c))
+    }
+
+    fn is_ident(&self, ident: &str) -> bool {
+        matches!(self.peek(), Some(Token::Ident(i)) if i == ident)
+    }
+
+    fn eat_punct(&mut self, c: char) -> bool {
+        let eaten = self.is_punct(c);
+        self.pos += eaten as usize;
+        eaten
+    }
+
+    fn eat_ident(&mut self, ident: &str) -> bool {
+        let eaten = self.is_ident(ident);
+        self.pos += eaten as usize;
+        eaten
+    }
+
+    fn ident(&mut self) -> Option<String> {
+        match self.peek() {
+            Some(Token::Ident(ident)) => {
+                let ident = ident.clone();
+                self.pos += 1;
+                Some(ident)
+            }
+            _ => None,
+        }
+    }
+
+    /// Skips from the opening bracket to after the matching closing one.
+    fn skip_balanced(&mut self, open: char, close: char) {
+        let mut depth = 0;
+        while let Some(token) = self.peek().cloned() {
+            self.pos += 1;
+            if token == Token::Punct(open) {
+                depth += 1;
+            } else if token == Token::Punct(close) {
+                depth -= 1;
+                if depth == 0 {
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Skips to the end of the declaration or definition, stops before the `}` of the scope.
+    fn skip_statement(&mut self) {
+        while let Some(token) = self.peek() {
+            match token {
+                Token::Punct(';') => {
+                    self.pos += 1;
+                    return;
+                }
+                Token::Punct('{') => {
+                    self.skip_balanced('{', '}');
+                    self.eat_punct(';');
+                    return;
+                }
+                Token::Punct('}') => return,
+                Token::Punct('(') => self.skip_balanced('(', ')'),
+                Token::Punct('[') => self.skip_balanced('[', ']'),
+                _ => self.pos += 1,
+            }
+        }
+    }
+
+    /// Skips an initializer up to the next declarator or the end of the declaration.
+    fn skip_initializer(&mut self) {
+        while let Some(token) = self.peek() {
+            match token {
+                Token::Punct(',' | ';' | '}') => return,
+                Token::Punct('{') => self.skip_balanced('{', '}'),
+                Token::Punct('(') => self.skip_balanced('(', ')'),
+                Token::Punct('[') => self.skip_balanced('[', ']'),
+                _ => self.pos += 1,
+            }
+        }
+    }
+
+    fn apply_pack(&mut self, pack: Pack) {
+        match pack {
+            Pack::Push(value) => {
+                self.pack_stack.push(self.pack);
+                if value.is_some() {
+                    self.pack = value;
+                }
+            }
+            Pack::Pop => self.pack = self.pack_stack.pop().flatten(),
+            Pack::Set(value) => self.pack = value,
+        }
+    }
+
+    /// Top level or a namespace, returns after the closing `}`.
+    fn parse_scope(&mut self) {
+        while let Some(token) = self.peek().cloned() {
+            let Token::Ident(ident) = token else {
+                match token {
+                    Token::Pack(pack) => {
+                        self.pos += 1;
+                        self.apply_pack(pack);
+                    }
+                    Token::Punct('}') => {
+                        self.pos += 1;
+                        return;
+                    }
+                    _ => self.skip_statement(),
+                }
+                continue;
+            };
+
+            match ident.as_str() {
+                "namespace" => {
+                    self.pos += 1;
+                    while self.ident().is_some() || self.peek() == Some(&Token::Scope) {
+                        self.eat_scope();
+                    }
+                    if self.eat_punct('{') {
+                        self.parse_scope();
+                    } else {
+                        self.skip_statement();
+                    }
+                }
+                "extern" if self.peek_at(1) == Some(&Token::Punct('{')) => {
+                    self.pos += 2;
+                    self.parse_scope();
+                }
+                "template" => self.skip_template(),
+                "typedef" => self.parse_typedef(),
+                "using" => self.parse_using(),
+                "struct" | "class" | "union" | "enum" => {
+                    self.parse_type();
+                    self.skip_statement();
+                }
+                _ => self.skip_statement(),
+            }
+        }
+    }
+
+    fn eat_scope(&mut self) -> bool {
+        let eaten = self.peek() == Some(&Token::Scope);
+        self.pos += eaten as usize;
+        eaten
+    }
+
+    fn skip_template(&mut self) {
+        self.pos += 1;
+        if self.is_punct('<') {
+            self.skip_balanced('<', '>');
+        }
+        self.skip_statement();
+    }
+
+    fn parse_typedef(&mut self) {
+        self.pos += 1;
+        let Some(base) = self.parse_type() else {
+            self.skip_statement();
+            return;
+        };
+
+        let mut first = true;
+        while let Some(Declarator::Member(name, ty)) = self.parse_declarator(base.clone()) {
+            // `typedef struct { ... } Name;` names the anonymous struct.
+            if let (true, Type::Named(anonymous), Type::Named(_)) = (first, &base, &ty) {
+                if let Some(&i) = self.index.get(anonymous) {
+                    if self.records[i].anonymous && !self.index.contains_key(&name) {
+                        self.index.remove(anonymous);
+                        self.index.insert(name.clone(), i);
+                        self.records[i].name = name.clone();
+                        self.records[i].anonymous = false;
+                        first = false;
+                        if !self.eat_punct(',') {
+                            break;
+                        }
+                        continue;
+                    }
+                }
+            }
+            first = false;
+
+            if !name.is_empty() && !matches!(&ty, Type::Named(n) if *n == name) {
+                self.types.insert(name, ty);
+            }
+            if !self.eat_punct(',') {
+                break;
+            }
+        }
+        self.skip_statement();
+    }
+
+    fn parse_using(&mut self) {
+        self.pos += 1;
+        match (self.ident(), self.is_punct('=')) {
+            (Some(name), true) if name != "namespace" => {
+                self.pos += 1;
+                if let Some(base) = self.parse_type() {
+                    if let Some(Declarator::Member(_, ty)) = self.parse_declarator(base) {
+                        self.types.insert(name, ty);
+                    }
+                }
+                self.skip_statement();
+            }
+            _ => self.skip_statement(),
+        }
+    }
+
+    fn parse_attributes(&mut self) -> Attributes {
+        let mut attributes = Attributes::default();
+        loop {
+            match self.peek() {
+                Some(Token::Ident(i)) if i == "alignas" => {
+                    self.pos += 2;
+                    attributes.align = self.expression().and_then(|n| usize::try_from(n).ok());
+                    self.skip_to_close(')');
+                }
+                Some(Token::Ident(i)) if i == "__declspec" || i == "__attribute__" => {
+                    self.pos += 1;
+                    let start = self.pos;
+                    self.skip_balanced('(', ')');
+                    let inner = &self.tokens[start..self.pos];
+                    for (i, token) in inner.iter().enumerate() {
+                        match token {
+                            Token::Ident(name) if name == "packed" => attributes.packed = true,
+                            Token::Ident(name) if name == "align" || name == "aligned" => {
+                                if let Some(Token::Number(n)) = inner.get(i + 2) {
+                                    attributes.align = usize::try_from(*n).ok();
+                                }
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                Some(Token::Punct('[')) if self.peek_at(1) == Some(&Token::Punct('[')) => {
+                    self.skip_balanced('[', ']');
+                }
+                _ => return attributes,
+            }
+        }
+    }
+
+    /// Skips past the `close` of the current bracket.
+    fn skip_to_close(&mut self, close: char) {
+        while let Some(token) = self.peek().cloned() {
+            self.pos += 1;
+            if token == Token::Punct(close) {
+                return;
+            }
+        }
+    }
+
+    fn parse_type(&mut self) -> Option<Type> {
+        let mut words: Vec<String> = vec![];
+        let mut ty = None;
+
+        while let Some(Token::Ident(ident)) = self.peek().cloned() {
+            match ident.as_str() {
+                "const" | "volatile" | "mutable" | "inline" | "constexpr" | "register"
+                | "typename" | "__unaligned" => self.pos += 1,
+                "struct" | "class" | "union" if ty.is_none() && words.is_empty() => {
+                    self.pos += 1;
+                    ty = Some(self.parse_record(ident == "union"));
+                }
+                "enum" if ty.is_none() && words.is_empty() => {
+                    self.pos += 1;
+                    ty = Some(self.parse_enum());
+                }
+                "unsigned" | "signed" | "short" | "long" | "int" | "char" | "bool" | "_Bool"
+                | "float" | "double" | "void" | "wchar_t" | "char8_t" | "char16_t" | "char32_t"
+                | "__int8" | "__int16" | "__int32" | "__int64" | "__int128"
+                    if ty.is_none() =>
+                {
+                    words.push(ident);
+                    self.pos += 1;
+                }
+                _ if ty.is_none() && words.is_empty() => ty = Some(self.parse_named()),
+                _ => break,
+            }
+        }
+
+        if self.peek() == Some(&Token::Scope) && ty.is_none() && words.is_empty() {
+            ty = Some(self.parse_named());
+        }
+
+        if words.is_empty() {
+            ty
+        } else {
+            Some(builtin(&words))
+        }
+    }
+
+    /// Possibly qualified and templated name of a type.
+    fn parse_named(&mut self) -> Type {
+        let mut path = vec![];
+        loop {
+            self.eat_scope();
+            let Some(segment) = self.ident() else {
+                break;
+            };
+            path.push(segment);
+
+            if self.is_punct('<') {
+                let kind = match path.join("::").as_str() {
+                    "std::vector" => Some(FieldKind::StdVector),
+                    "std::list" => Some(FieldKind::StdList),
+                    _ => None,
+                };
+
+                if let Some(kind) = kind {
+                    self.pos += 1;
+                    let element = self.parse_type().map(|ty| self.parse_pointers(ty));
+                    self.skip_template_rest();
+                    return match element {
+                        Some(element) => Type::Container(kind, Box::new(element)),
+                        None => Type::Named(path.join("::")),
+                    };
+                }
+                self.skip_balanced('<', '>');
+            }
+
+            if self.peek() != Some(&Token::Scope) {
+                break;
+            }
+        }
+
+        match path.join("::").as_str() {
+            "std::string" | "std::basic_string" => Type::Prim(FieldKind::StdString),
+            _ => Type::Named(path.pop().unwrap_or_default()),
+        }
+    }
+
+    /// Skips remaining template arguments and the closing `>`.
+    fn skip_template_rest(&mut self) {
+        let mut depth = 1;
+        while let Some(token) = self.peek().cloned() {
+            self.pos += 1;
+            match token {
+                Token::Punct('<') => depth += 1,
+                Token::Punct('>') => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn parse_pointers(&mut self, mut ty: Type) -> Type {
+        loop {
+            if self.eat_punct('*') || self.eat_punct('&') {
+                ty = Type::Pointer(Box::new(ty));
+            } else if !(self.eat_ident("const")
+                || self.eat_ident("volatile")
+                || self.eat_ident("__ptr64")
+                || self.eat_ident("__restrict")
+                || self.eat_ident("__unaligned"))
+            {
+                return ty;
+            }
+        }
+    }
+
+    fn skip_calling_convention(&mut self) {
+        while let Some(Token::Ident(i)) = self.peek() {
+            if !matches!(
+                i.as_str(),
+                "__cdecl" | "__stdcall" | "__fastcall" | "__thiscall" | "__vectorcall" | "WINAPI"
+            ) {
+                return;
+            }
+            self.pos += 1;
+        }
+    }
+
+    fn parse_declarator(&mut self, base: Type) -> Option<Declarator> {
+        let mut ty = self.parse_pointers(base);
+        self.skip_calling_convention();
+
+        if self.is_punct('(') {
+            // Function pointer `ret (*name)(args)` or a function.
+            let mut n = 1;
+            while let Some(Token::Ident(_)) = self.peek_at(n) {
+                n += 1;
+            }
+            if self.peek_at(n) != Some(&Token::Punct('*')) {
+                return Some(Declarator::Function);
+            }
+
+            self.pos += 1;
+            self.skip_calling_convention();
+            while self.eat_punct('*') {}
+            let name = self.ident().unwrap_or_default();
+            let dims = self.parse_dimensions();
+            self.skip_to_close(')');
+            if self.is_punct('(') {
+                self.skip_balanced('(', ')');
+            }
+
+            ty = Type::Pointer(Box::new(Type::Function));
+            for dim in dims.into_iter().rev() {
+                ty = Type::Array(Box::new(ty), dim);
+            }
+            return Some(Declarator::Member(name, ty));
+        }
+
+        let name = self.ident().unwrap_or_default();
+        if name == "operator" || self.is_punct('(') {
+            return Some(Declarator::Function);
+        }
+
+        for dim in self.parse_dimensions().into_iter().rev() {
+            ty = Type::Array(Box::new(ty), dim);
+        }
+        Some(Declarator::Member(name, ty))
+    }
+
+    fn parse_dimensions(&mut self) -> Vec<Option<usize>> {
+        let mut dims = vec![];
+        while self.eat_punct('[') {
+            // Negative sizes are left unknown, the member is left out with a warning.
+            let dim = if self.is_punct(']') {
+                Some(0)
+            } else {
+                self.expression().and_then(|n| usize::try_from(n).ok())
+            };
+            self.skip_to_close(']');
+            dims.push(dim);
+        }
+        dims
+    }
+
+    fn parse_record(&mut self, union: bool) -> Type {
+        let mut attributes = self.parse_attributes();
+        let mut name = None;
+        while let Some(Token::Ident(ident)) = self.peek().cloned() {
+            self.pos += 1;
+            if ident != "final" {
+                name = Some(ident);
+            }
+            if !self.eat_scope() && !self.is_punct('<') {
+                break;
+            }
+            if self.is_punct('<') {
+                self.skip_balanced('<', '>');
+            }
+        }
+        let after = self.parse_attributes();
+        attributes.align = attributes.align.or(after.align);
+
+        let mut base = None;
+        if self.eat_punct(':') {
+            let mut first = true;
+            while !self.is_punct('{') && self.peek().is_some() {
+                if self.eat_punct(',') {
+                    first = false;
+                    continue;
+                }
+                if ["public", "private", "protected", "virtual"]
+                    .iter()
+                    .any(|kw| self.eat_ident(kw))
+                {
+                    continue;
+                }
+
+                match self.parse_named() {
+                    Type::Named(b) if first => base = Some(b),
+                    Type::Named(b) => self.warnings.push(format!(
+                        "Base `{b}` of `{}` is left out, only the first base is supported",
+                        name.as_deref().unwrap_or("?")
+                    )),
+                    _ => self.pos += 1,
+                }
+            }
+        }
+
+        if !self.eat_punct('{') {
+            return Type::Named(name.unwrap_or_default());
+        }
+
+        let anonymous = name.is_none();
+        let name = name.unwrap_or_else(|| {
+            self.anonymous += 1;
+            format!("__anonymous{}", self.anonymous)
+        });
+
+        let mut record = Record {
+            name: name.clone(),
+            union,
+            anonymous,
+            defined: true,
+            base,
+            pack: self.pack,
+            align: attributes.align,
+            ..Default::default()
+        };
+        self.parse_members(&mut record);
+
+        let trailing = self.parse_attributes();
+        record.align = record.align.or(trailing.align);
+        if attributes.packed || trailing.packed {
+            record.pack = Some(1);
+        }
+
+        match self.index.get(&name) {
+            Some(&i) if self.records[i].defined => self.warnings.push(format!(
+                "`{name}` is defined more than once, the first definition is used"
+            )),
+            _ => {
+                self.index.insert(name.clone(), self.records.len());
+                self.records.push(record);
+            }
+        }
+
+        Type::Named(name)
+    }
+
+    /// Reads members up to and including the closing `}`.
+    fn parse_members(&mut self, record: &mut Record) {
+        while let Some(token) = self.peek().cloned() {
+            match token {
+                Token::Punct('}') => {
+                    self.pos += 1;
+                    return;
+                }
+                Token::Pack(pack) => {
+                    self.pos += 1;
+                    self.apply_pack(pack);
+                    continue;
+                }
+                Token::Ident(ref i)
+                    if matches!(i.as_str(), "public" | "private" | "protected")
+                        && self.peek_at(1) == Some(&Token::Punct(':')) =>
+                {
+                    self.pos += 2;
+                    continue;
+                }
+                Token::Ident(ref i) => match i.as_str() {
+                    "virtual" => {
+                        self.parse_virtual(record);
+                        continue;
+                    }
+                    "typedef" => {
+                        self.parse_typedef();
+                        continue;
+                    }
+                    "using" => {
+                        self.parse_using();
+                        continue;
+                    }
+                    "template" => {
+                        self.skip_template();
+                        continue;
+                    }
+                    "static" | "friend" | "static_assert" => {
+                        self.skip_statement();
+                        continue;
+                    }
+                    _ => {}
+                },
+                Token::Punct(';') => {
+                    self.pos += 1;
+                    continue;
+                }
+                _ => {}
+            }
+
+            let attributes = self.parse_attributes();
+            let Some(base) = self.parse_type() else {
+                self.skip_statement();
+                continue;
+            };
+
+            if self.eat_punct(';') {
+                // Anonymous struct or union is a member, named ones only declare a type.
+                if let Type::Named(name) = &base {
+                    if self
+                        .index
+                        .get(name)
+                        .is_some_and(|&i| self.records[i].anonymous)
+                    {
+                        record.members.push(Member {
+                            name: String::new(),
+                            ty: base,
+                            bits: None,
+                            align: attributes.align,
+                            offset: None,
+                            comment: None,
+                        });
+                    }
+                }
+                continue;
+            }
+
+            loop {
+                match self.parse_declarator(base.clone()) {
+                    Some(Declarator::Member(name, ty)) => {
+                        let bits = if self.eat_punct(':') {
+                            self.expression().and_then(|n| u32::try_from(n).ok())
+                        } else {
+                            None
+                        };
+                        let member_attributes = self.parse_attributes();
+                        self.skip_initializer();
+
+                        record.members.push(Member {
+                            name,
+                            ty,
+                            bits,
+                            align: attributes.align.or(member_attributes.align),
+                            offset: None,
+                            comment: None,
+                        });
+                    }
+                    _ => {
+                        self.skip_statement();
+                        break;
+                    }
+                }
+
+                if self.eat_punct(';') {
+                    break;
+                } else if !self.eat_punct(',') {
+                    self.skip_statement();
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Virtual method, becomes a slot of the virtual table.
+    fn parse_virtual(&mut self, record: &mut Record) {
+        self.pos += 1;
+        let start = self.pos;
+        while !matches!(
+            self.peek(),
+            None | Some(Token::Punct('(' | ';' | '{' | '}'))
+        ) {
+            self.pos += 1;
+        }
+
+        if self.is_punct('(') && self.pos > start {
+            let (name, ret) = match &self.tokens[start..self.pos] {
+                [.., Token::Punct('~'), Token::Ident(_)] => {
+                    ("destructor".to_owned(), "void".to_owned())
+                }
+                [ret @ .., Token::Ident(name)] if !ret.is_empty() => (name.clone(), join(ret)),
+                _ => (format!("vfunc{}", record.virtuals.len()), "void".to_owned()),
+            };
+
+            let args_start = self.pos + 1;
+            self.skip_balanced('(', ')');
+            let args = join(&self.tokens[args_start..self.pos - 1]);
+
+            record.virtuals.push(VTableSlot {
+                name,
+                signature: format!("{ret}({args})"),
+            });
+        }
+        self.skip_statement();
+    }
+
+    fn parse_enum(&mut self) -> Type {
+        if !self.eat_ident("class") {
+            self.eat_ident("struct");
+        }
+        self.parse_attributes();
+
+        let mut name = None;
+        while let Some(ident) = self.ident() {
+            name = Some(ident);
+            if !self.eat_scope() {
+                break;
+            }
+        }
+
+        let mut kind = FieldKind::I32;
+        if self.eat_punct(':') {
+            if let Some(Type::Prim(underlying)) = self.parse_type().map(|ty| self.resolve_early(ty))
+            {
+                kind = underlying;
+            }
+        }
+
+        if self.eat_punct('{') {
+            let mut value = 0;
+            while let Some(enumerator) = self.ident() {
+                self.parse_attributes();
+                if self.eat_punct('=') {
+                    value = self.expression().unwrap_or(value);
+                }
+                self.constants.insert(enumerator, value);
+                value += 1;
+
+                self.skip_initializer();
+                if !self.eat_punct(',') {
+                    break;
+                }
+            }
+            self.skip_to_close('}');
+        }
+
+        match name {
+            Some(name) => {
+                let ty = Type::Enum(name.clone(), kind);
+                self.types.insert(name, ty.clone());
+                ty
+            }
+            None => Type::Prim(kind),
+        }
+    }
+
+    /// Resolves typedefs known so far, used for underlying types of enums.
+    fn resolve_early(&self, mut ty: Type) -> Type {
+        for _ in 0..32 {
+            match ty {
+                Type::Named(ref name) => {
+                    match self.types.get(name).or(builtin_typedef(name).as_ref()) {
+                        Some(next) => ty = next.clone(),
+                        None => return ty,
+                    }
+                }
+                Type::Enum(_, kind) => return Type::Prim(kind),
+                Type::Char(encoding) => return Type::Prim(char_kind(encoding)),
+                _ => return ty,
+            }
+        }
+        ty
+    }
+
+    /// Evaluates an integer constant expression, `None` if it has unknown names.
+    fn expression(&mut self) -> Option<i64> {
+        self.binary(0)
+    }
+
+    fn binary(&mut self, min_precedence: u8) -> Option<i64> {
+        let mut lhs = self.unary()?;
+        while let Some((op, precedence, len)) = self.operator() {
+            if precedence < min_precedence {
+                break;
+            }
+            self.pos += len;
+            let rhs = self.binary(precedence + 1)?;
+            lhs = match op {
+                '|' => lhs | rhs,
+                '^' => lhs ^ rhs,
+                '&' => lhs & rhs,
+                '<' => lhs.checked_shl(rhs as u32)?,
+                '>' => lhs.checked_shr(rhs as u32)?,
+                '+' => lhs.wrapping_add(rhs),
+                '-' => lhs.wrapping_sub(rhs),
+                '*' => lhs.wrapping_mul(rhs),
+                '/' => lhs.checked_div(rhs)?,
+                _ => lhs.checked_rem(rhs)?,
+            };
+        }
+        Some(lhs)
+    }
+
+    /// Binary operator at the current token, its precedence and length in tokens.
+    fn operator(&self) -> Option<(char, u8, usize)> {
+        let Some(Token::Punct(c)) = self.peek() else {
+            return None;
+        };
+        let doubled = self.peek_at(1) == Some(&Token::Punct(*c));
+        match c {
+            '<' | '>' if doubled => Some((*c, 4, 2)),
+            '|' | '&' if doubled => None,
+            '|' => Some(('|', 1, 1)),
+            '^' => Some(('^', 2, 1)),
+            '&' => Some(('&', 3, 1)),
+            '+' | '-' => Some((*c, 5, 1)),
+            '*' | '/' | '%' => Some((*c, 6, 1)),
+            _ => None,
+        }
+    }
+
+    fn unary(&mut self) -> Option<i64> {
+        match self.peek().cloned()? {
+            Token::Number(n) => {
+                self.pos += 1;
+                Some(n)
+            }
+            Token::Punct('-') => {
+                self.pos += 1;
+                self.unary().map(|n| n.wrapping_neg())
+            }
+            Token::Punct('~') => {
+                self.pos += 1;
+                self.unary().map(|n| !n)
+            }
+            Token::Punct('+') => {
+                self.pos += 1;
+                self.unary()
+            }
+            Token::Punct('(') => {
+                self.pos += 1;
+                let value = self.expression();
+                self.eat_punct(')');
+                value
+            }
+            Token::Ident(name) => {
+                self.pos += 1;
+                // Qualified enumerators are looked up by their last segment.
+                let mut name = name;
+                while self.eat_scope() {
+                    name = self.ident()?;
+                }
+                self.constants.get(&name).copied()
+            }
+            _ => None,
+        }
+    }
+}
+
+enum Declarator {
+    Member(String, Type),
+    Function,
+}
+
+/// Joins tokens back into code, i.e. a signature of a virtual method.
+fn join(tokens: &[Token]) -> String {
+    let mut out = String::new();
+    let mut word = false;
+    for token in tokens {
+        let (text, is_word) = match token {
+            Token::Ident(i) => (i.clone(), true),
+            Token::Number(n) => (n.to_string(), true),
+            Token::Punct(',') => (", ".to_owned(), false),
+            Token::Punct(c) => (c.to_string(), false),
+            Token::Scope => ("::".to_owned(), false),
+            Token::Pack(_) => continue,
+        };
+        if word && is_word {
+            out.push(' ');
+        }
+        out += &text;
+        word = is_word;
+    }
+    out
+}
+
+/// Type spelled with keywords, i.e. `unsigned long long`.
+fn builtin(words: &[String]) -> Type {
+    let has = |w: &str| words.iter().any(|word| word == w);
+    let unsigned = has("unsigned");
+    let int = |signed, unsigned_kind| Type::Prim(if unsigned { unsigned_kind } else { signed });
+    let longs = words.iter().filter(|w| *w == "long").count();
+
+    if has("void") {
+        Type::Void
+    } else if has("bool") || has("_Bool") {
+        Type::Prim(FieldKind::Bool)
+    } else if has("float") {
+        Type::Prim(FieldKind::F32)
+    } else if has("double") {
+        Type::Prim(FieldKind::F64)
+    } else if has("char") || has("char8_t") {
+        if unsigned {
+            Type::Prim(FieldKind::U8)
+        } else if has("signed") {
+            Type::Prim(FieldKind::I8)
+        } else {
+            Type::Char(TextEncoding::Utf8)
+        }
+    } else if has("wchar_t") {
+        Type::Char(wchar_encoding())
+    } else if has("char16_t") {
+        Type::Char(TextEncoding::Utf16)
+    } else if has("char32_t") {
+        Type::Char(TextEncoding::Utf32)
+    } else if has("__int8") {
+        int(FieldKind::I8, FieldKind::U8)
+    } else if has("short") || has("__int16") {
+        int(FieldKind::I16, FieldKind::U16)
+    } else if has("__int64") || longs >= 2 || (longs == 1 && !cfg!(windows)) {
+        int(FieldKind::I64, FieldKind::U64)
+    } else if has("__int128") {
+        int(FieldKind::I128, FieldKind::U128)
+    } else {
+        int(FieldKind::I32, FieldKind::U32)
+    }
+}
+
+/// `wchar_t` is 2 bytes on Windows and 4 everywhere else.
+fn wchar_encoding() -> TextEncoding {
+    if cfg!(windows) {
+        TextEncoding::Utf16
+    } else {
+        TextEncoding::Utf32
+    }
+}
+
+/// Common typedefs of `<cstdint>`, Windows headers and decompilers.
+fn builtin_typedef(name: &str) -> Option<Type> {
+    use FieldKind::*;
+
+    let kind = match name {
+        "int8_t" | "int8" | "i8" | "INT8" | "__int8" | "_BYTE" | "SBYTE" => I8,
+        "uint8_t" | "uint8" | "u8" | "UINT8" | "BYTE" | "UCHAR" | "byte" | "undefined"
+        | "undefined1" | "uchar" | "BOOLEAN" => U8,
+        "int16_t" | "int16" | "i16" | "INT16" | "SHORT" => I16,
+        "uint16_t" | "uint16" | "u16" | "UINT16" | "WORD" | "USHORT" | "_WORD" | "word"
+        | "ushort" | "undefined2" => U16,
+        "int32_t" | "int32" | "i32" | "INT32" | "INT" | "LONG" | "BOOL" | "_BOOL4" | "HRESULT" => {
+            I32
+        }
+        "uint32_t" | "uint32" | "u32" | "UINT32" | "UINT" | "ULONG" | "DWORD" | "_DWORD"
+        | "dword" | "uint" | "undefined4" => U32,
+        "int64_t" | "int64" | "i64" | "INT64" | "LONGLONG" | "LONG64" | "intptr_t"
+        | "ptrdiff_t" | "ssize_t" | "INT_PTR" | "LONG_PTR" | "longlong" => I64,
+        "uint64_t" | "uint64" | "u64" | "UINT64" | "ULONGLONG" | "ULONG64" | "DWORD64"
+        | "QWORD" | "_QWORD" | "qword" | "uintptr_t" | "size_t" | "SIZE_T" | "UINT_PTR"
+        | "ULONG_PTR" | "DWORD_PTR" | "ulonglong" | "undefined8" => U64,
+        "__int128_t" | "i128" => I128,
+        "__uint128_t" | "u128" | "_OWORD" => U128,
+        "FLOAT" | "float32" | "f32" => F32,
+        "DOUBLE" | "float64" | "f64" => F64,
+        "_BOOL1" | "bool8" => Bool,
+        "CHAR" => return Some(Type::Char(TextEncoding::Utf8)),
+        "WCHAR" => return Some(Type::Char(TextEncoding::Utf16)),
+        "HANDLE" | "PVOID" | "LPVOID" | "HMODULE" | "HINSTANCE" | "HWND" | "FARPROC" => {
+            return Some(Type::Pointer(Box::new(Type::Void)))
+        }
+        "LPSTR" | "LPCSTR" | "PSTR" | "PCSTR" => {
+            return Some(Type::Pointer(Box::new(Type::Char(TextEncoding::Utf8))))
+        }
+        "LPWSTR" | "LPCWSTR" | "PWSTR" | "PCWSTR" => {
+            return Some(Type::Pointer(Box::new(Type::Char(TextEncoding::Utf16))))
+        }
+        _ => return None,
+    };
+    Some(Type::Prim(kind))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project::{layout::fields, DataClass};
+
+    fn size(class: &DataClass) -> usize {
+        class
+            .fields
+            .iter()
+            .map(|f| f.offset + f.kind.size())
+            .max()
+            .unwrap_or(0)
+    }
+
+    #[test]
+    fn pragma_pack() {
+        let imported = import(
+            "#pragma pack(push, 1)
+            struct Packed { char a; int b; short c; };
+            #pragma pack(pop)
+            struct Natural { char a; int b; short c; };",
+        );
+
+        use FieldKind::*;
+        assert_eq!(
+            fields(&imported.classes[0]),
+            [("a", 0, I8), ("b", 1, I32), ("c", 5, I16)]
+        );
+        assert_eq!(
+            fields(&imported.classes[1]),
+            [("a", 0, I8), ("b", 4, I32), ("c", 8, I16), ("", 10, Unk16)]
+        );
+        assert_eq!(size(&imported.classes[1]), 12);
+    }
+
+    #[test]
+    fn bit_fields_share_storage() {
+        let imported =
+            import("struct Flags { unsigned a : 3; unsigned b : 5; unsigned c : 30; char d; };");
+
+        let class = &imported.classes[0];
+        assert_eq!(
+            fields(class),
+            [
+                ("a", 0, FieldKind::U32),
+                ("c", 4, FieldKind::U32),
+                ("d", 8, FieldKind::I8),
+                ("", 9, FieldKind::Unk16),
+                ("", 11, FieldKind::Unk8),
+            ]
+        );
+        assert_eq!(
+            class.fields[0].comment.as_deref(),
+            Some("Bit fields a: 3, b: 5")
+        );
+        assert_eq!(size(class), 12);
+    }
+
+    #[test]
+    fn bases_and_virtual_tables() {
+        let imported = import(
+            "struct Base { int x; int y; };
+            struct Derived : Base { float z; };
+            struct Actor { virtual ~Actor(); virtual void tick(int dt); int hp; };",
+        );
+
+        let derived = &imported.classes[1];
+        assert_eq!(derived.base.as_deref(), Some("Base"));
+        assert_eq!(fields(derived), [("z", 0, FieldKind::F32)]);
+
+        let actor = &imported.classes[2];
+        assert_eq!(
+            fields(actor),
+            [
+                ("vtable", 0, FieldKind::VTable),
+                ("hp", 8, FieldKind::I32),
+                ("", 12, FieldKind::Unk32),
+            ]
+        );
+        let slots = actor.fields[0]
+            .slots
+            .iter()
+            .map(|s| s.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(slots, ["destructor", "tick"]);
+        assert!(imported.warnings.is_empty());
+    }
+
+    #[test]
+    fn long_arrays_keep_the_size() {
+        let imported = import(
+            "struct S { int a; float m[64]; };
+            struct T { char c; double d[20]; int x; };",
+        );
+
+        assert_eq!(size(&imported.classes[0]), 260);
+        let t = &imported.classes[1];
+        assert_eq!(size(t), 176);
+        assert!(t.fields.iter().any(|f| f.name == "x" && f.offset == 168));
+    }
+
+    #[test]
+    fn invalid_array_sizes_are_left_out() {
+        let imported = import(
+            "struct Negative { int x; int a[-1]; int y; };
+            struct Huge { int x; char b[0x7FFFFFFFFFFFFFFF]; };
+            struct Overflow { int x; double c[0x4000000000000000]; };",
+        );
+
+        for class in &imported.classes {
+            assert_eq!(fields(class), [("x", 0, FieldKind::I32)]);
+            assert_eq!(size(class), 4);
+        }
+        assert_eq!(imported.warnings.len(), 3, "{:?}", imported.warnings);
+    }
+}
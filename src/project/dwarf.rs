@@ -0,0 +1,1255 @@
+/// This module contains import of types from DWARF debug info of 64-bit little endian ELF files,
+/// i.e. executables built with `-g` or their separate debug files. Structs, classes and unions
+/// keep the offsets and sizes the compiler reported.
+use super::{
+    layout::{ident, Importer, Member, Record, Type},
+    DataField, Imported,
+};
+use crate::field::{FieldKind, TextEncoding, VTableSlot};
+use std::collections::{hash_map::Entry, HashMap, HashSet};
+
+const DW_TAG_ARRAY_TYPE: u16 = 0x01;
+const DW_TAG_CLASS_TYPE: u16 = 0x02;
+const DW_TAG_ENUMERATION_TYPE: u16 = 0x04;
+const DW_TAG_MEMBER: u16 = 0x0D;
+const DW_TAG_POINTER_TYPE: u16 = 0x0F;
+const DW_TAG_REFERENCE_TYPE: u16 = 0x10;
+const DW_TAG_COMPILE_UNIT: u16 = 0x11;
+const DW_TAG_STRUCTURE_TYPE: u16 = 0x13;
+const DW_TAG_SUBROUTINE_TYPE: u16 = 0x15;
+const DW_TAG_TYPEDEF: u16 = 0x16;
+const DW_TAG_UNION_TYPE: u16 = 0x17;
+const DW_TAG_INHERITANCE: u16 = 0x1C;
+const DW_TAG_PTR_TO_MEMBER_TYPE: u16 = 0x1F;
+const DW_TAG_SUBRANGE_TYPE: u16 = 0x21;
+const DW_TAG_BASE_TYPE: u16 = 0x24;
+const DW_TAG_CONST_TYPE: u16 = 0x26;
+const DW_TAG_FORMAL_PARAMETER: u16 = 0x05;
+const DW_TAG_SUBPROGRAM: u16 = 0x2E;
+const DW_TAG_TEMPLATE_TYPE_PARAM: u16 = 0x2F;
+const DW_TAG_VARIABLE: u16 = 0x34;
+const DW_TAG_VOLATILE_TYPE: u16 = 0x35;
+const DW_TAG_RESTRICT_TYPE: u16 = 0x37;
+const DW_TAG_NAMESPACE: u16 = 0x39;
+const DW_TAG_PARTIAL_UNIT: u16 = 0x3C;
+const DW_TAG_RVALUE_REFERENCE_TYPE: u16 = 0x42;
+const DW_TAG_ATOMIC_TYPE: u16 = 0x47;
+
+const DW_AT_LOCATION: u16 = 0x02;
+const DW_AT_NAME: u16 = 0x03;
+const DW_AT_BYTE_SIZE: u16 = 0x0B;
+const DW_AT_BIT_OFFSET: u16 = 0x0C;
+const DW_AT_BIT_SIZE: u16 = 0x0D;
+const DW_AT_UPPER_BOUND: u16 = 0x2F;
+const DW_AT_ARTIFICIAL: u16 = 0x34;
+const DW_AT_COUNT: u16 = 0x37;
+const DW_AT_DATA_MEMBER_LOCATION: u16 = 0x38;
+const DW_AT_DECLARATION: u16 = 0x3C;
+const DW_AT_ENCODING: u16 = 0x3E;
+const DW_AT_SPECIFICATION: u16 = 0x47;
+const DW_AT_VIRTUALITY: u16 = 0x4C;
+const DW_AT_TYPE: u16 = 0x49;
+const DW_AT_VTABLE_ELEM_LOCATION: u16 = 0x4D;
+const DW_AT_DATA_BIT_OFFSET: u16 = 0x6B;
+const DW_AT_STR_OFFSETS_BASE: u16 = 0x72;
+const DW_AT_ADDR_BASE: u16 = 0x73;
+
+const DW_ATE_BOOLEAN: u64 = 0x02;
+const DW_ATE_FLOAT: u64 = 0x04;
+const DW_ATE_SIGNED: u64 = 0x05;
+const DW_ATE_SIGNED_CHAR: u64 = 0x06;
+const DW_ATE_UNSIGNED: u64 = 0x07;
+const DW_ATE_UNSIGNED_CHAR: u64 = 0x08;
+const DW_ATE_UTF: u64 = 0x10;
+
+const DW_OP_ADDR: u8 = 0x03;
+const DW_OP_CONSTU: u8 = 0x10;
+const DW_OP_PLUS_UCONST: u8 = 0x23;
+const DW_OP_ADDRX: u8 = 0xA1;
+const DW_OP_GNU_ADDR_INDEX: u8 = 0xFB;
+
+/// Struct, class or union that can be picked for import.
+pub struct DebugType {
+    pub name: String,
+    pub kind: &'static str,
+    pub size: usize,
+}
+
+/// Global variable of a struct, class or union type.
+pub struct DebugGlobal {
+    pub name: String,
+    pub class: String,
+    /// Virtual address in the file, see [`DebugInfo::load_bias`].
+    pub address: usize,
+}
+
+pub struct DebugInfo {
+    pub types: Vec<DebugType>,
+    pub globals: Vec<DebugGlobal>,
+    records: Vec<Record>,
+    /// Lowest virtual address of the loaded segments.
+    min_vaddr: usize,
+    warnings: Vec<String>,
+}
+
+impl DebugInfo {
+    /// Reads the types and global variables of an ELF file.
+    pub fn read(data: &[u8]) -> eyre::Result<Self> {
+        let elf = Elf::parse(data)
+            .ok_or_else(|| eyre::eyre!("Only 64-bit little endian ELF files are supported"))?;
+
+        if elf.section(".zdebug_info").is_some() || elf.compressed(".debug_info") {
+            eyre::bail!(
+                "Debug sections are compressed, decompress them with `objcopy --decompress-debug-sections`"
+            );
+        }
+        let Some(info) = elf.section(".debug_info") else {
+            eyre::bail!("There is no debug info, it might be in a separate debug file");
+        };
+
+        let mut dwarf = Dwarf {
+            info,
+            abbrev: elf.section(".debug_abbrev").unwrap_or_default(),
+            str: elf.section(".debug_str").unwrap_or_default(),
+            line_str: elf.section(".debug_line_str").unwrap_or_default(),
+            str_offsets: elf.section(".debug_str_offsets").unwrap_or_default(),
+            addr: elf.section(".debug_addr").unwrap_or_default(),
+            dies: vec![],
+            index: HashMap::new(),
+            signatures: HashMap::new(),
+            units: vec![],
+            names: HashMap::new(),
+            defined: HashMap::new(),
+            warnings: vec![],
+        };
+        dwarf.parse();
+        if dwarf.dies.is_empty() {
+            eyre::bail!("Debug info is empty or malformed");
+        }
+
+        Ok(dwarf.into_info(elf.min_vaddr))
+    }
+
+    /// Difference between addresses in the process and in the file,
+    /// `base` is the address the file is loaded at.
+    pub fn load_bias(&self, base: usize) -> usize {
+        base.wrapping_sub(self.min_vaddr & !0xFFF)
+    }
+
+    /// Builds classes out of the picked types, others are only used for their layout.
+    pub fn import(&self, picked: &HashSet<String>) -> Imported {
+        let records = self
+            .records
+            .iter()
+            .cloned()
+            .map(|mut r| {
+                r.inline = !picked.contains(&r.name);
+                r
+            })
+            .collect();
+
+        Importer::new(records, HashMap::new(), |_| None, self.warnings.clone()).import()
+    }
+}
+
+struct Elf<'a> {
+    data: &'a [u8],
+    /// Name, flags, offset and size of every section.
+    sections: Vec<(String, u64, usize, usize)>,
+    min_vaddr: usize,
+}
+
+impl<'a> Elf<'a> {
+    fn parse(data: &'a [u8]) -> Option<Self> {
+        const PT_LOAD: u32 = 1;
+        const SHT_NOBITS: u32 = 8;
+
+        // Offsets come from the file, malformed ones must not overflow.
+        let bytes = |o: usize, len: usize| data.get(o..o.checked_add(len)?);
+        let u16_at = |o: usize| Some(u16::from_le_bytes(bytes(o, 2)?.try_into().ok()?));
+        let u32_at = |o: usize| Some(u32::from_le_bytes(bytes(o, 4)?.try_into().ok()?));
+        let u64_at = |o: usize| Some(u64::from_le_bytes(bytes(o, 8)?.try_into().ok()?) as usize);
+        let entry = |table: usize, i: usize, size: usize| table.checked_add(i.checked_mul(size)?);
+
+        if data.get(..6)? != b"\x7fELF\x02\x01" {
+            return None;
+        }
+
+        let (phoff, phentsize, phnum) = (u64_at(0x20)?, u16_at(0x36)? as usize, u16_at(0x38)?);
+        let min_vaddr = (0..phnum as usize)
+            .filter_map(|i| entry(phoff, i, phentsize))
+            .filter(|ph| u32_at(*ph) == Some(PT_LOAD))
+            .filter_map(|ph| u64_at(ph.checked_add(0x10)?))
+            .min()
+            .unwrap_or_default();
+
+        let (shoff, shentsize, shnum) = (u64_at(0x28)?, u16_at(0x3A)? as usize, u16_at(0x3C)?);
+        let strtab = entry(shoff, u16_at(0x3E)? as usize, shentsize)?;
+        let strtab = u64_at(strtab.checked_add(0x18)?)?;
+
+        let mut sections = vec![];
+        for i in 0..shnum as usize {
+            let section = entry(shoff, i, shentsize)?;
+            let field = |offset: usize| section.checked_add(offset);
+            // Sections stripped into a separate debug file have no data.
+            if u32_at(field(4)?)? == SHT_NOBITS {
+                continue;
+            }
+
+            let name = data.get(strtab.checked_add(u32_at(section)? as usize)?..)?;
+            let end = name.iter().position(|c| *c == 0)?;
+            sections.push((
+                String::from_utf8_lossy(&name[..end]).into_owned(),
+                u64_at(field(8)?)? as u64,
+                u64_at(field(0x18)?)?,
+                u64_at(field(0x20)?)?,
+            ));
+        }
+
+        Some(Self {
+            data,
+            sections,
+            min_vaddr,
+        })
+    }
+
+    fn section(&self, name: &str) -> Option<&'a [u8]> {
+        let (_, _, offset, size) = self.sections.iter().find(|s| s.0 == name)?;
+        self.data.get(*offset..offset.checked_add(*size)?)
+    }
+
+    fn compressed(&self, name: &str) -> bool {
+        const SHF_COMPRESSED: u64 = 0x800;
+        self.sections
+            .iter()
+            .any(|s| s.0 == name && s.1 & SHF_COMPRESSED != 0)
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8], pos: usize) -> Self {
+        Self { data, pos }
+    }
+
+    fn bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        let bytes = self.data.get(self.pos..self.pos.checked_add(len)?)?;
+        self.pos += len;
+        Some(bytes)
+    }
+
+    fn uint(&mut self, len: usize) -> Option<u64> {
+        if len > 8 {
+            return None;
+        }
+        let mut buf = [0; 8];
+        buf[..len].copy_from_slice(self.bytes(len)?);
+        Some(u64::from_le_bytes(buf))
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        Some(self.bytes(1)?[0])
+    }
+
+    fn uleb(&mut self) -> Option<u64> {
+        let (mut value, mut shift) = (0u64, 0);
+        loop {
+            let byte = self.u8()?;
+            if shift < 64 {
+                value |= ((byte & 0x7F) as u64) << shift;
+            }
+            shift += 7;
+            if byte & 0x80 == 0 {
+                return Some(value);
+            }
+        }
+    }
+
+    fn sleb(&mut self) -> Option<i64> {
+        let (mut value, mut shift) = (0i64, 0);
+        loop {
+            let byte = self.u8()?;
+            if shift < 64 {
+                value |= ((byte & 0x7F) as i64) << shift;
+            }
+            shift += 7;
+            if byte & 0x80 == 0 {
+                if shift < 64 && byte & 0x40 != 0 {
+                    value |= -1 << shift;
+                }
+                return Some(value);
+            }
+        }
+    }
+
+    fn cstr(&mut self) -> Option<&'a [u8]> {
+        let len = self.data.get(self.pos..)?.iter().position(|c| *c == 0)?;
+        let bytes = self.bytes(len)?;
+        self.pos += 1;
+        Some(bytes)
+    }
+}
+
+/// Block with its length read as `len` bytes, ULEB128 if `len` is 0.
+fn block(r: &mut Reader, len: usize) -> Option<Value> {
+    let len = if len == 0 { r.uleb()? } else { r.uint(len)? };
+    Some(Value::Block(r.bytes(len as usize)?.to_vec()))
+}
+
+fn cstr_at(section: &[u8], offset: u64) -> Option<String> {
+    let bytes = Reader::new(section, offset as usize).cstr()?;
+    Some(String::from_utf8_lossy(bytes).into_owned())
+}
+
+#[derive(Debug, Clone)]
+enum Value {
+    Unsigned(u64),
+    Signed(i64),
+    Str(String),
+    /// Offset of the entry in `.debug_info`.
+    Ref(usize),
+    /// Signature of a type unit.
+    Signature(u64),
+    Block(Vec<u8>),
+    Flag(bool),
+    /// Resolved once the bases of the unit are known.
+    StrIndex(u64),
+    AddrIndex(u64),
+}
+
+struct Abbrev {
+    tag: u16,
+    children: bool,
+    /// Attribute, form and the implicit constant.
+    attrs: Vec<(u16, u16, i64)>,
+}
+
+struct Unit {
+    addr_size: usize,
+    addr_base: u64,
+    str_offsets_base: u64,
+}
+
+struct Die {
+    offset: usize,
+    tag: u16,
+    attrs: Vec<(u16, Value)>,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    unit: usize,
+}
+
+struct Dwarf<'a> {
+    info: &'a [u8],
+    abbrev: &'a [u8],
+    str: &'a [u8],
+    line_str: &'a [u8],
+    str_offsets: &'a [u8],
+    addr: &'a [u8],
+    dies: Vec<Die>,
+    /// Offset of an entry to its index.
+    index: HashMap<usize, usize>,
+    signatures: HashMap<u64, usize>,
+    units: Vec<Unit>,
+    /// Names of records, made up for anonymous ones.
+    names: HashMap<usize, String>,
+    /// First definition of every record name.
+    defined: HashMap<String, usize>,
+    warnings: Vec<String>,
+}
+
+impl Dwarf<'_> {
+    fn parse(&mut self) {
+        let mut abbrevs = HashMap::new();
+        let mut pos = 0;
+
+        while pos + 4 <= self.info.len() {
+            let mut r = Reader::new(self.info, pos);
+            let Some(len) = r.uint(4) else {
+                break;
+            };
+            if len >= 0xFFFF_FFF0 {
+                self.warnings
+                    .push("64-bit DWARF units aren't supported and are left out".into());
+                break;
+            }
+            let end = r.pos + len as usize;
+
+            if self.parse_unit(r, end, &mut abbrevs).is_none() {
+                self.warnings.push(format!(
+                    "Unit at {pos:#X} is malformed and partially left out"
+                ));
+            }
+            pos = end;
+        }
+    }
+
+    fn parse_unit(
+        &mut self,
+        mut r: Reader,
+        end: usize,
+        abbrevs: &mut HashMap<u64, HashMap<u64, Abbrev>>,
+    ) -> Option<()> {
+        const DW_UT_TYPE: u8 = 0x02;
+        const DW_UT_SKELETON: u8 = 0x04;
+        const DW_UT_SPLIT_COMPILE: u8 = 0x05;
+        const DW_UT_SPLIT_TYPE: u8 = 0x06;
+
+        let start = r.pos - 4;
+        let version = r.uint(2)?;
+        let (abbrev_offset, addr_size) = if version >= 5 {
+            let unit_type = r.u8()?;
+            let addr_size = r.u8()? as usize;
+            let abbrev_offset = r.uint(4)?;
+            match unit_type {
+                DW_UT_SKELETON | DW_UT_SPLIT_COMPILE => _ = r.uint(8)?,
+                DW_UT_TYPE | DW_UT_SPLIT_TYPE => {
+                    let signature = r.uint(8)?;
+                    let type_offset = r.uint(4)? as usize;
+                    self.signatures.insert(signature, start + type_offset);
+                }
+                _ => {}
+            }
+            (abbrev_offset, addr_size)
+        } else {
+            (r.uint(4)?, r.u8()? as usize)
+        };
+
+        let abbrevs = match abbrevs.entry(abbrev_offset) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(self.parse_abbrevs(abbrev_offset)?),
+        };
+
+        let unit = self.units.len();
+        self.units.push(Unit {
+            addr_size,
+            addr_base: 0,
+            str_offsets_base: 8,
+        });
+
+        // `None` for entries that are skipped with their children, i.e. function bodies.
+        let mut parents: Vec<Option<usize>> = vec![];
+        while r.pos < end {
+            let offset = r.pos;
+            let code = r.uleb()?;
+            if code == 0 {
+                parents.pop();
+                continue;
+            }
+
+            let abbrev = abbrevs.get(&code)?;
+            let mut attrs = Vec::with_capacity(abbrev.attrs.len());
+            for &(attr, form, implicit) in &abbrev.attrs {
+                let value = self.read_value(&mut r, form, implicit, start, version, addr_size)?;
+                attrs.push((attr, value));
+            }
+
+            let parent = match parents.last().copied() {
+                None => None,
+                Some(None) => {
+                    if abbrev.children {
+                        parents.push(None);
+                    }
+                    continue;
+                }
+                Some(parent) => parent,
+            };
+
+            let parent_tag = parent.map(|p| self.dies[p].tag);
+            let kept = match abbrev.tag {
+                // Only methods are kept, their parameters make up the signature.
+                DW_TAG_SUBPROGRAM => parent_tag.is_some_and(is_record),
+                _ if parent_tag == Some(DW_TAG_SUBPROGRAM) => abbrev.tag == DW_TAG_FORMAL_PARAMETER,
+                _ => true,
+            };
+            if !kept {
+                if abbrev.children {
+                    parents.push(None);
+                }
+                continue;
+            }
+
+            if parent.is_none() {
+                self.unit_bases(unit, &attrs);
+            }
+            for (_, value) in attrs.iter_mut() {
+                self.resolve(unit, value);
+            }
+
+            let index = self.dies.len();
+            self.index.insert(offset, index);
+            self.dies.push(Die {
+                offset,
+                tag: abbrev.tag,
+                attrs,
+                parent,
+                children: vec![],
+                unit,
+            });
+            if let Some(parent) = parent {
+                self.dies[parent].children.push(index);
+            }
+            if abbrev.children {
+                parents.push(Some(index));
+            }
+        }
+
+        Some(())
+    }
+
+    fn parse_abbrevs(&self, offset: u64) -> Option<HashMap<u64, Abbrev>> {
+        const DW_FORM_IMPLICIT_CONST: u16 = 0x21;
+
+        let mut r = Reader::new(self.abbrev, offset as usize);
+        let mut abbrevs = HashMap::new();
+        loop {
+            let code = r.uleb()?;
+            if code == 0 {
+                return Some(abbrevs);
+            }
+
+            let tag = r.uleb()? as u16;
+            let children = r.u8()? != 0;
+            let mut attrs = vec![];
+            loop {
+                let (attr, form) = (r.uleb()? as u16, r.uleb()? as u16);
+                if attr == 0 && form == 0 {
+                    break;
+                }
+                let implicit = if form == DW_FORM_IMPLICIT_CONST {
+                    r.sleb()?
+                } else {
+                    0
+                };
+                attrs.push((attr, form, implicit));
+            }
+
+            abbrevs.insert(
+                code,
+                Abbrev {
+                    tag,
+                    children,
+                    attrs,
+                },
+            );
+        }
+    }
+
+    fn read_value(
+        &self,
+        r: &mut Reader,
+        form: u16,
+        implicit: i64,
+        unit: usize,
+        version: u64,
+        addr_size: usize,
+    ) -> Option<Value> {
+        use Value::*;
+
+        Some(match form {
+            0x01 => Unsigned(r.uint(addr_size)?),
+            0x03 => block(r, 2)?,
+            0x04 => block(r, 4)?,
+            0x05 => Unsigned(r.uint(2)?),
+            0x06 => Unsigned(r.uint(4)?),
+            0x07 => Unsigned(r.uint(8)?),
+            0x08 => Str(String::from_utf8_lossy(r.cstr()?).into_owned()),
+            0x09 | 0x18 => block(r, 0)?,
+            0x0A => block(r, 1)?,
+            0x0B => Unsigned(r.uint(1)?),
+            0x0C => Flag(r.u8()? != 0),
+            0x0D => Signed(r.sleb()?),
+            0x0E => Str(cstr_at(self.str, r.uint(4)?).unwrap_or_default()),
+            0x0F => Unsigned(r.uleb()?),
+            0x10 => Ref(r.uint(if version <= 2 { addr_size } else { 4 })? as usize),
+            0x11 => Ref(unit.checked_add(r.uint(1)? as usize)?),
+            0x12 => Ref(unit.checked_add(r.uint(2)? as usize)?),
+            0x13 => Ref(unit.checked_add(r.uint(4)? as usize)?),
+            0x14 => Ref(unit.checked_add(r.uint(8)? as usize)?),
+            0x15 => Ref(unit.checked_add(r.uleb()? as usize)?),
+            0x16 => {
+                let form = r.uleb()? as u16;
+                return self.read_value(r, form, implicit, unit, version, addr_size);
+            }
+            0x17 | 0x1C | 0x1D => Unsigned(r.uint(4)?),
+            0x22 | 0x23 => Unsigned(r.uleb()?),
+            0x19 => Flag(true),
+            0x1A | 0x1F02 => StrIndex(r.uleb()?),
+            0x1B | 0x1F01 => AddrIndex(r.uleb()?),
+            0x1E => Block(r.bytes(16)?.to_vec()),
+            0x1F => Str(cstr_at(self.line_str, r.uint(4)?).unwrap_or_default()),
+            0x20 => Signature(r.uint(8)?),
+            0x21 => Signed(implicit),
+            0x24 => Unsigned(r.uint(8)?),
+            0x25..=0x28 => StrIndex(r.uint(form as usize - 0x24)?),
+            0x29..=0x2C => AddrIndex(r.uint(form as usize - 0x28)?),
+            // References into a supplementary file, i.e. made by dwz.
+            0x1F20 | 0x1F21 => Unsigned(r.uint(4)?),
+            _ => return None,
+        })
+    }
+
+    fn unit_bases(&mut self, unit: usize, attrs: &[(u16, Value)]) {
+        for (attr, value) in attrs {
+            let Value::Unsigned(base) = value else {
+                continue;
+            };
+            match *attr {
+                DW_AT_STR_OFFSETS_BASE => self.units[unit].str_offsets_base = *base,
+                DW_AT_ADDR_BASE => self.units[unit].addr_base = *base,
+                _ => {}
+            }
+        }
+    }
+
+    fn resolve(&self, unit: usize, value: &mut Value) {
+        let unit = &self.units[unit];
+        match *value {
+            Value::StrIndex(index) => {
+                let offset = index
+                    .checked_mul(4)
+                    .and_then(|offset| offset.checked_add(unit.str_offsets_base))
+                    .and_then(|offset| Reader::new(self.str_offsets, offset as usize).uint(4));
+                *value = Value::Str(
+                    offset
+                        .and_then(|offset| cstr_at(self.str, offset))
+                        .unwrap_or_default(),
+                );
+            }
+            Value::AddrIndex(index) => {
+                if let Some(address) = self.address(unit, index) {
+                    *value = Value::Unsigned(address);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn address(&self, unit: &Unit, index: u64) -> Option<u64> {
+        let offset = index
+            .checked_mul(unit.addr_size as u64)?
+            .checked_add(unit.addr_base)?;
+        Reader::new(self.addr, offset as usize).uint(unit.addr_size)
+    }
+
+    fn attr(&self, die: usize, attr: u16) -> Option<&Value> {
+        self.dies[die]
+            .attrs
+            .iter()
+            .find(|(a, _)| *a == attr)
+            .map(|(_, v)| v)
+    }
+
+    fn name(&self, die: usize) -> Option<&str> {
+        match self.attr(die, DW_AT_NAME)? {
+            Value::Str(name) if !name.is_empty() => Some(name),
+            _ => None,
+        }
+    }
+
+    fn udata(&self, die: usize, attr: u16) -> Option<u64> {
+        match self.attr(die, attr)? {
+            Value::Unsigned(n) => Some(*n),
+            Value::Signed(n) => Some(*n as u64),
+            _ => None,
+        }
+    }
+
+    fn flag(&self, die: usize, attr: u16) -> bool {
+        matches!(self.attr(die, attr), Some(Value::Flag(true)))
+    }
+
+    fn target(&self, die: usize, attr: u16) -> Option<usize> {
+        match self.attr(die, attr)? {
+            Value::Ref(offset) => self.index.get(offset).copied(),
+            Value::Signature(signature) => self.index.get(self.signatures.get(signature)?).copied(),
+            _ => None,
+        }
+    }
+
+    /// Follows typedefs and qualifiers.
+    fn strip(&self, mut die: usize) -> Option<usize> {
+        for _ in 0..32 {
+            match self.dies[die].tag {
+                DW_TAG_TYPEDEF | DW_TAG_CONST_TYPE | DW_TAG_VOLATILE_TYPE
+                | DW_TAG_RESTRICT_TYPE | DW_TAG_ATOMIC_TYPE => {
+                    die = self.target(die, DW_AT_TYPE)?
+                }
+                _ => return Some(die),
+            }
+        }
+        None
+    }
+
+    fn in_namespace(&self, die: usize, namespace: impl Fn(&str) -> bool) -> bool {
+        let mut parent = self.dies[die].parent;
+        while let Some(p) = parent {
+            if self.dies[p].tag == DW_TAG_NAMESPACE && self.name(p).is_some_and(&namespace) {
+                return true;
+            }
+            parent = self.dies[p].parent;
+        }
+        false
+    }
+
+    /// Definition of the record, declarations are matched by name.
+    fn definition(&self, die: usize) -> usize {
+        if !self.flag(die, DW_AT_DECLARATION) {
+            return die;
+        }
+        self.names
+            .get(&die)
+            .and_then(|name| self.defined.get(name))
+            .copied()
+            .unwrap_or(die)
+    }
+
+    fn type_of(&self, die: Option<usize>, depth: usize) -> Type {
+        let Some(die) = die.filter(|_| depth < 32) else {
+            return Type::Void;
+        };
+        let next = || self.type_of(self.target(die, DW_AT_TYPE), depth + 1);
+
+        match self.dies[die].tag {
+            DW_TAG_BASE_TYPE => self.base_type(die),
+            DW_TAG_POINTER_TYPE | DW_TAG_REFERENCE_TYPE | DW_TAG_RVALUE_REFERENCE_TYPE => {
+                Type::Pointer(Box::new(next()))
+            }
+            DW_TAG_TYPEDEF | DW_TAG_CONST_TYPE | DW_TAG_VOLATILE_TYPE | DW_TAG_RESTRICT_TYPE
+            | DW_TAG_ATOMIC_TYPE => next(),
+            DW_TAG_STRUCTURE_TYPE | DW_TAG_CLASS_TYPE | DW_TAG_UNION_TYPE => {
+                let die = self.definition(die);
+                self.container(die, depth)
+                    .unwrap_or_else(|| Type::Named(self.names[&die].clone()))
+            }
+            DW_TAG_ENUMERATION_TYPE => {
+                let size = self.udata(die, DW_AT_BYTE_SIZE).unwrap_or(4) as usize;
+                let signed = matches!(
+                    next(),
+                    Type::Prim(FieldKind::I8 | FieldKind::I16 | FieldKind::I32 | FieldKind::I64)
+                );
+                let name = self.name(die).unwrap_or("anonymous").to_owned();
+                Type::Enum(name, int_kind(size, signed).unwrap_or(FieldKind::U32))
+            }
+            DW_TAG_ARRAY_TYPE => {
+                let dimensions = self.dies[die]
+                    .children
+                    .iter()
+                    .filter(|&&c| self.dies[c].tag == DW_TAG_SUBRANGE_TYPE)
+                    .map(|&c| {
+                        // Flexible array members have no bounds.
+                        self.udata(c, DW_AT_COUNT)
+                            .or_else(|| self.udata(c, DW_AT_UPPER_BOUND).map(|n| n.wrapping_add(1)))
+                            .unwrap_or(0) as usize
+                    })
+                    .collect::<Vec<_>>();
+                dimensions.iter().rev().fold(next(), |element, len| {
+                    Type::Array(Box::new(element), Some(*len))
+                })
+            }
+            DW_TAG_SUBROUTINE_TYPE => Type::Function,
+            DW_TAG_PTR_TO_MEMBER_TYPE => {
+                opaque(self.udata(die, DW_AT_BYTE_SIZE).unwrap_or(8) as usize)
+            }
+            _ => Type::Void,
+        }
+    }
+
+    fn base_type(&self, die: usize) -> Type {
+        let size = self.udata(die, DW_AT_BYTE_SIZE).unwrap_or_default() as usize;
+        let encoding = self.udata(die, DW_AT_ENCODING).unwrap_or_default();
+
+        let char_encoding = match size {
+            1 => TextEncoding::Utf8,
+            2 => TextEncoding::Utf16,
+            _ => TextEncoding::Utf32,
+        };
+        if self.name(die) == Some("wchar_t") {
+            return Type::Char(char_encoding);
+        }
+
+        let kind = match (encoding, size) {
+            (DW_ATE_BOOLEAN, 1) => Some(FieldKind::Bool),
+            (DW_ATE_FLOAT, 2) => Some(FieldKind::F16),
+            (DW_ATE_FLOAT, 4) => Some(FieldKind::F32),
+            (DW_ATE_FLOAT, 8) => Some(FieldKind::F64),
+            (DW_ATE_SIGNED, _) => int_kind(size, true),
+            (DW_ATE_UNSIGNED | DW_ATE_UNSIGNED_CHAR, _) => int_kind(size, false),
+            (DW_ATE_SIGNED_CHAR, 1) => return Type::Char(TextEncoding::Utf8),
+            (DW_ATE_UTF, 1 | 2 | 4) => return Type::Char(char_encoding),
+            _ => None,
+        };
+        kind.map_or_else(|| opaque(size), Type::Prim)
+    }
+
+    /// Standard library containers YClass has fields for.
+    fn container(&self, die: usize, depth: usize) -> Option<Type> {
+        let name = self.name(die)?;
+        if !self.in_namespace(die, |n| n == "std") {
+            return None;
+        }
+
+        let size = self.udata(die, DW_AT_BYTE_SIZE)?;
+        let element = || {
+            let param = self.dies[die]
+                .children
+                .iter()
+                .find(|&&c| self.dies[c].tag == DW_TAG_TEMPLATE_TYPE_PARAM)?;
+            Some(self.type_of(self.target(*param, DW_AT_TYPE), depth + 1))
+        };
+
+        if name.starts_with("basic_string<char,") && size == 32 {
+            Some(Type::Field(
+                Box::new(DataField::new("", FieldKind::StdString)),
+                8,
+            ))
+        } else if name.starts_with("vector<") && size == 24 {
+            Some(Type::Container(FieldKind::StdVector, Box::new(element()?)))
+        } else if name.starts_with("list<") && size == 24 {
+            Some(Type::Container(FieldKind::StdList, Box::new(element()?)))
+        } else {
+            None
+        }
+    }
+
+    /// Name of a type as written in C/C++, used for virtual method signatures.
+    fn type_name(&self, die: Option<usize>, depth: usize) -> String {
+        let Some(die) = die.filter(|_| depth < 16) else {
+            return "void".into();
+        };
+        let next = || self.type_name(self.target(die, DW_AT_TYPE), depth + 1);
+
+        match self.dies[die].tag {
+            DW_TAG_POINTER_TYPE => format!("{}*", next()),
+            DW_TAG_REFERENCE_TYPE => format!("{}&", next()),
+            DW_TAG_RVALUE_REFERENCE_TYPE => format!("{}&&", next()),
+            DW_TAG_CONST_TYPE => format!("const {}", next()),
+            DW_TAG_VOLATILE_TYPE => format!("volatile {}", next()),
+            DW_TAG_ARRAY_TYPE => format!("{}[]", next()),
+            DW_TAG_SUBROUTINE_TYPE => format!("{}(*)()", next()),
+            _ => self.name(die).unwrap_or("?").to_owned(),
+        }
+    }
+
+    /// Names every record and finds the first definition of each name.
+    fn name_records(&mut self) {
+        for die in 0..self.dies.len() {
+            if !is_record(self.dies[die].tag) {
+                continue;
+            }
+
+            let name = match self.name(die) {
+                Some(name) => ident(name),
+                None => format!("anonymous_{:x}", self.dies[die].offset),
+            };
+            if !self.flag(die, DW_AT_DECLARATION) && self.attr(die, DW_AT_BYTE_SIZE).is_some() {
+                match self.defined.get(&name) {
+                    None => _ = self.defined.insert(name.clone(), die),
+                    Some(&first)
+                        if self.udata(first, DW_AT_BYTE_SIZE)
+                            != self.udata(die, DW_AT_BYTE_SIZE) =>
+                    {
+                        self.warnings.push(format!(
+                            "`{name}` is defined differently more than once, the first definition is used"
+                        ));
+                    }
+                    _ => {}
+                }
+            }
+            self.names.insert(die, name);
+        }
+    }
+
+    fn into_info(mut self, min_vaddr: usize) -> DebugInfo {
+        self.name_records();
+
+        let mut definitions = self
+            .defined
+            .iter()
+            .map(|(name, &die)| (name.clone(), die))
+            .collect::<Vec<_>>();
+        definitions.sort_by_key(|(_, die)| *die);
+
+        let mut types = vec![];
+        let mut records = vec![];
+        for (name, die) in definitions {
+            let anonymous = self.name(die).is_none();
+            // Internals of the standard library would bury the interesting types.
+            let reserved = self
+                .name(die)
+                .is_some_and(|n| n.starts_with('_') || n.contains(' '));
+            if !anonymous
+                && !reserved
+                && !self.in_namespace(die, |n| n == "std" || n.starts_with("__"))
+                && self.container(die, 0).is_none()
+            {
+                types.push(DebugType {
+                    name: name.clone(),
+                    kind: match self.dies[die].tag {
+                        DW_TAG_UNION_TYPE => "union",
+                        DW_TAG_CLASS_TYPE => "class",
+                        _ => "struct",
+                    },
+                    size: self.udata(die, DW_AT_BYTE_SIZE).unwrap_or_default() as usize,
+                });
+            }
+            records.push(self.record(die, name, anonymous));
+        }
+        types.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let mut globals = (0..self.dies.len())
+            .filter_map(|die| self.global(die))
+            .collect::<Vec<_>>();
+        globals.sort_by(|a, b| a.name.cmp(&b.name));
+
+        // Types repeat in every unit that uses them, so do their warnings.
+        let mut seen = HashSet::new();
+        self.warnings.retain(|w| seen.insert(w.clone()));
+
+        DebugInfo {
+            types,
+            globals,
+            records,
+            min_vaddr,
+            warnings: self.warnings,
+        }
+    }
+
+    fn record(&mut self, die: usize, name: String, anonymous: bool) -> Record {
+        let mut record = Record {
+            name,
+            union: self.dies[die].tag == DW_TAG_UNION_TYPE,
+            anonymous,
+            defined: true,
+            size: self.udata(die, DW_AT_BYTE_SIZE).map(|n| n as usize),
+            ..Default::default()
+        };
+
+        // Member index, first and last bit and storage size of every bit field.
+        let mut bits = vec![];
+        for child in self.dies[die].children.clone() {
+            match self.dies[child].tag {
+                DW_TAG_INHERITANCE => {
+                    let base = self.type_of(self.target(child, DW_AT_TYPE), 0);
+                    let Type::Named(base) = base else {
+                        continue;
+                    };
+
+                    match self.member_offset(child) {
+                        Some(0) if record.base.is_none() => record.base = Some(base),
+                        Some(offset) => record.members.push(Member {
+                            name: base.clone(),
+                            ty: Type::Named(base),
+                            bits: None,
+                            align: None,
+                            offset: Some(offset),
+                            comment: None,
+                        }),
+                        None => self.warnings.push(format!(
+                            "Virtual base `{base}` of `{}` is left out",
+                            record.name
+                        )),
+                    }
+                }
+                // Static members are declarations.
+                DW_TAG_MEMBER if !self.flag(child, DW_AT_DECLARATION) => {
+                    let name = self.name(child).unwrap_or_default().to_owned();
+                    let target = self.target(child, DW_AT_TYPE);
+                    let mut member = Member {
+                        name,
+                        ty: self.type_of(target, 0),
+                        bits: None,
+                        align: None,
+                        offset: self.member_offset(child),
+                        comment: None,
+                    };
+
+                    if member.name.starts_with("_vptr") {
+                        member.name = "vtable".into();
+                        member.ty = Type::Field(
+                            Box::new(DataField {
+                                slots: self.virtuals(die),
+                                ..DataField::new("", FieldKind::VTable)
+                            }),
+                            8,
+                        );
+                    } else if let Some(width) = self.udata(child, DW_AT_BIT_SIZE) {
+                        let unit = target
+                            .and_then(|t| self.strip(t))
+                            .and_then(|t| self.udata(t, DW_AT_BYTE_SIZE))
+                            .unwrap_or(4)
+                            .clamp(1, 16);
+                        let storage = self.udata(child, DW_AT_BYTE_SIZE).unwrap_or(unit);
+
+                        // Before DWARF 4 bits are numbered from the most significant one.
+                        let start = self.udata(child, DW_AT_DATA_BIT_OFFSET).unwrap_or_else(|| {
+                            let offset =
+                                (member.offset.unwrap_or_default() as u64).saturating_mul(8);
+                            match self.udata(child, DW_AT_BIT_OFFSET) {
+                                Some(bit) => offset
+                                    .saturating_add(storage.saturating_mul(8))
+                                    .saturating_sub(bit.saturating_add(width)),
+                                None => offset,
+                            }
+                        });
+                        let end = start.saturating_add(width);
+                        bits.push((record.members.len(), start, end, unit));
+                        member.bits = Some(width as u32);
+                    }
+
+                    record.members.push(member);
+                }
+                _ => {}
+            }
+        }
+
+        share_bits(&mut record, &bits);
+        record
+    }
+
+    /// Offset of a member or base, `None` if it's computed at runtime.
+    fn member_offset(&self, die: usize) -> Option<usize> {
+        match self.attr(die, DW_AT_DATA_MEMBER_LOCATION)? {
+            Value::Unsigned(offset) => Some(*offset as usize),
+            Value::Signed(offset) => Some(*offset as usize),
+            Value::Block(expr) if expr.first() == Some(&DW_OP_PLUS_UCONST) => {
+                Reader::new(expr, 1).uleb().map(|n| n as usize)
+            }
+            _ => None,
+        }
+    }
+
+    /// Virtual methods the record declares, ordered as in its virtual table.
+    fn virtuals(&self, die: usize) -> Vec<VTableSlot> {
+        let mut slots = vec![];
+        for &child in &self.dies[die].children {
+            let Some(Value::Block(expr)) = self.attr(child, DW_AT_VTABLE_ELEM_LOCATION) else {
+                continue;
+            };
+            if expr.first() != Some(&DW_OP_CONSTU) {
+                continue;
+            }
+            let Some(index) = Reader::new(expr, 1).uleb().map(|n| n as usize) else {
+                continue;
+            };
+
+            let name = self.name(child).unwrap_or_default();
+            let name = if name.starts_with('~') {
+                "destructor".to_owned()
+            } else {
+                ident(name)
+            };
+            let params = self.dies[child]
+                .children
+                .iter()
+                .filter(|&&p| !self.flag(p, DW_AT_ARTIFICIAL))
+                .map(|&p| self.type_name(self.target(p, DW_AT_TYPE), 0))
+                .collect::<Vec<_>>();
+            let signature = format!(
+                "{}({})",
+                self.type_name(self.target(child, DW_AT_TYPE), 0),
+                params.join(", ")
+            );
+
+            if slots.len() <= index {
+                slots.resize_with(index + 1, || None);
+            }
+            slots[index] = Some(VTableSlot { name, signature });
+        }
+
+        // Virtual destructors have no slot in the debug info, in the Itanium ABI
+        // they take the first two free ones.
+        let destructor = self.dies[die].children.iter().any(|&c| {
+            self.name(c).is_some_and(|n| n.starts_with('~'))
+                && self.udata(c, DW_AT_VIRTUALITY).is_some_and(|v| v != 0)
+                && self.attr(c, DW_AT_VTABLE_ELEM_LOCATION).is_none()
+        });
+        let mut destructors = ["destructor", "deleting_destructor"]
+            .into_iter()
+            .filter(|_| destructor);
+
+        slots
+            .into_iter()
+            .enumerate()
+            .map(|(i, slot)| {
+                slot.unwrap_or_else(|| VTableSlot {
+                    name: destructors
+                        .next()
+                        .map_or_else(|| format!("slot_{i}"), str::to_owned),
+                    signature: "void()".into(),
+                })
+            })
+            .collect()
+    }
+
+    fn global(&self, die: usize) -> Option<DebugGlobal> {
+        if self.dies[die].tag != DW_TAG_VARIABLE {
+            return None;
+        }
+        let parent = self.dies[die].parent?;
+        if !matches!(
+            self.dies[parent].tag,
+            DW_TAG_COMPILE_UNIT | DW_TAG_PARTIAL_UNIT | DW_TAG_NAMESPACE
+        ) {
+            return None;
+        }
+
+        let Some(Value::Block(expr)) = self.attr(die, DW_AT_LOCATION) else {
+            return None;
+        };
+        let unit = &self.units[self.dies[die].unit];
+        let mut r = Reader::new(expr, 1);
+        let address = match *expr.first()? {
+            DW_OP_ADDR => r.uint(unit.addr_size)?,
+            DW_OP_ADDRX | DW_OP_GNU_ADDR_INDEX => self.address(unit, r.uleb()?)?,
+            _ => return None,
+        };
+
+        // Definitions outside of classes and namespaces refer to their declaration.
+        let declaration = self.target(die, DW_AT_SPECIFICATION).unwrap_or(die);
+        let name = self.name(die).or_else(|| self.name(declaration))?;
+        let ty = self
+            .target(die, DW_AT_TYPE)
+            .or_else(|| self.target(declaration, DW_AT_TYPE))
+            .and_then(|t| self.strip(t))
+            .filter(|&t| is_record(self.dies[t].tag))?;
+        let class = self.names.get(&self.definition(ty))?;
+
+        self.defined.contains_key(class).then(|| DebugGlobal {
+            name: name.to_owned(),
+            class: class.clone(),
+            address: address as usize,
+        })
+    }
+}
+
+/// Places bit fields of the same storage into one integer that starts at the byte
+/// of their first bit, members before them might use the rest of the storage.
+fn share_bits(record: &mut Record, bits: &[(usize, u64, u64, u64)]) {
+    let mut i = 0;
+    while i < bits.len() {
+        let (_, start, _, unit) = bits[i];
+        let storage = start / (unit * 8);
+        let group = bits[i..]
+            .iter()
+            .take_while(|(_, s, _, u)| *u == unit && s / (unit * 8) == storage)
+            .count();
+        let end = bits[i + group - 1].2;
+
+        let (first, last) = (start / 8, end.saturating_sub(1) / 8);
+        let room = (storage + 1) * unit - first;
+        let size = [1, 2, 4, 8]
+            .into_iter()
+            .find(|&size| size > last.saturating_sub(first) && size <= room)
+            .or_else(|| [4, 2, 1].into_iter().find(|&size| size <= room))
+            .unwrap_or(1);
+
+        for &(member, ..) in &bits[i..i + group] {
+            let member = &mut record.members[member];
+            member.ty = Type::Prim(int_kind(size as usize, false).unwrap_or(FieldKind::U8));
+            member.offset = Some(first as usize);
+        }
+        i += group;
+    }
+}
+
+fn is_record(tag: u16) -> bool {
+    matches!(
+        tag,
+        DW_TAG_STRUCTURE_TYPE | DW_TAG_CLASS_TYPE | DW_TAG_UNION_TYPE
+    )
+}
+
+fn int_kind(size: usize, signed: bool) -> Option<FieldKind> {
+    Some(match (size, signed) {
+        (1, true) => FieldKind::I8,
+        (2, true) => FieldKind::I16,
+        (4, true) => FieldKind::I32,
+        (8, true) => FieldKind::I64,
+        (16, true) => FieldKind::I128,
+        (1, false) => FieldKind::U8,
+        (2, false) => FieldKind::U16,
+        (4, false) => FieldKind::U32,
+        (8, false) => FieldKind::U64,
+        (16, false) => FieldKind::U128,
+        _ => return None,
+    })
+}
+
+/// Bytes YClass has no type for, i.e. `long double`.
+fn opaque(size: usize) -> Type {
+    Type::Array(Box::new(Type::Prim(FieldKind::Unk8)), Some(size))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project::layout::fields;
+
+    /// `fixtures/player.c` built with gcc 12 as `gcc -g -nostdlib -static
+    /// -fno-asynchronous-unwind-tables -Wl,--build-id=none -o player player.c`,
+    /// then `strip --only-keep-debug -o player.debug player`.
+    const PLAYER: &[u8] = include_bytes!("fixtures/player.debug");
+
+    #[test]
+    fn types_and_globals_are_read() {
+        let info = DebugInfo::read(PLAYER).unwrap();
+        let types = info
+            .types
+            .iter()
+            .map(|t| (t.name.as_str(), t.size))
+            .collect::<Vec<_>>();
+        assert_eq!(types, [("Player", 56), ("Vec3", 12)]);
+
+        let [global] = &info.globals[..] else {
+            panic!("one global is expected");
+        };
+        assert_eq!(
+            (global.name.as_str(), global.class.as_str()),
+            ("player", "Player")
+        );
+    }
+
+    #[test]
+    fn unpicked_types_are_inlined() {
+        let info = DebugInfo::read(PLAYER).unwrap();
+        let imported = info.import(&HashSet::from(["Player".to_owned()]));
+        assert_eq!(imported.len(), 1);
+        assert!(imported.warnings.is_empty(), "{:?}", imported.warnings);
+
+        assert_eq!(
+            fields(&imported.classes[0]),
+            [
+                ("target", 0, FieldKind::Ptr),
+                ("health", 8, FieldKind::I32),
+                ("position_x", 12, FieldKind::F32),
+                ("position_y", 16, FieldKind::F32),
+                ("position_z", 20, FieldKind::F32),
+                ("flags", 24, FieldKind::U8),
+                ("name", 25, FieldKind::Text(TextEncoding::Utf8, 16)),
+                ("speed", 48, FieldKind::F64),
+            ]
+        );
+        assert_eq!(
+            imported.classes[0].fields[0].metadata.as_deref(),
+            Some("Player")
+        );
+    }
+
+    #[test]
+    fn corrupted_files_are_rejected_or_read_partially() {
+        for (i, byte) in (0..PLAYER.len()).flat_map(|i| [(i, 0xFF), (i, 0x80)]) {
+            let mut data = PLAYER.to_vec();
+            data[i] = byte;
+            if let Ok(info) = DebugInfo::read(&data) {
+                let picked = info.types.iter().map(|t| t.name.clone()).collect();
+                info.import(&picked);
+            }
+        }
+    }
+}
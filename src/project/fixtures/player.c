@@ -0,0 +1,17 @@
+struct Vec3 {
+    float x, y, z;
+};
+
+struct Player {
+    struct Player *target;
+    int health;
+    struct Vec3 position;
+    unsigned char flags : 3;
+    unsigned char team : 5;
+    char name[16];
+    double speed;
+};
+
+struct Player player;
+
+void _start(void) {}
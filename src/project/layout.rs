@@ -0,0 +1,830 @@
+/// This module contains layouts of records read from other formats, shared by the importers.
+/// Records become classes with fields at offsets computed like a C compiler does.
+use super::{DataClass, DataField, Imported};
+use crate::field::{FieldKind, TextEncoding, VTableSlot};
+use std::{collections::HashMap, rc::Rc};
+
+/// Arrays with more elements are left as padding instead of a field per element.
+const ARRAY_FIELDS_LIMIT: usize = 16;
+
+/// Bigger types are left out, their padding alone would be hundreds of thousands of fields.
+const SIZE_LIMIT: usize = 1 << 20;
+
+#[derive(Debug, Clone)]
+pub(super) enum Type {
+    Void,
+    Function,
+    Prim(FieldKind),
+    Char(TextEncoding),
+    Enum(String, FieldKind),
+    Pointer(Box<Type>),
+    /// Size is `None` if it couldn't be evaluated.
+    Array(Box<Type>, Option<usize>),
+    /// `std::vector<T>` or `std::list<T>`.
+    Container(FieldKind, Box<Type>),
+    /// Field that is imported as is, with its alignment.
+    Field(Box<DataField>, usize),
+    /// Defined struct, class or union.
+    Record(String),
+    /// Name that is resolved after the whole header is read.
+    Named(String),
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct Member {
+    pub name: String,
+    pub ty: Type,
+    pub bits: Option<u32>,
+    pub align: Option<usize>,
+    /// Offset known from the source, i.e. debug info, instead of the computed one.
+    /// Bit fields at the same offset share the storage.
+    pub offset: Option<usize>,
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub(super) struct Record {
+    pub name: String,
+    pub union: bool,
+    pub anonymous: bool,
+    /// Only used as a type of members, no class is made out of it.
+    pub inline: bool,
+    pub defined: bool,
+    pub base: Option<String>,
+    /// Methods of the virtual table the record adds.
+    pub virtuals: Vec<VTableSlot>,
+    pub members: Vec<Member>,
+    pub pack: Option<usize>,
+    pub align: Option<usize>,
+    /// Size known from the source instead of the computed one.
+    pub size: Option<usize>,
+    pub comment: Option<String>,
+}
+
+/// Integer kind of a character, shown as a character.
+pub(super) fn char_kind(encoding: TextEncoding) -> FieldKind {
+    match encoding {
+        TextEncoding::Utf8 => FieldKind::I8,
+        TextEncoding::Utf16 => FieldKind::U16,
+        TextEncoding::Utf32 => FieldKind::U32,
+    }
+}
+
+/// Class name out of a C++ name, i.e. `Pair<int, float>` becomes `Pair_int_float`.
+pub(super) fn ident(name: &str) -> String {
+    let mut ident = String::with_capacity(name.len());
+    for c in name.chars() {
+        if c.is_alphanumeric() || c == '_' {
+            ident.push(c);
+        } else if !ident.ends_with('_') {
+            ident.push('_');
+        }
+    }
+    ident.trim_end_matches('_').to_owned()
+}
+
+/// Saturates instead of overflowing, results past [`SIZE_LIMIT`] are rejected anyway.
+fn align_up(offset: usize, align: usize) -> usize {
+    offset.div_ceil(align.max(1)).saturating_mul(align.max(1))
+}
+
+/// Layout of a record, offsets are relative to its start.
+#[derive(Default)]
+struct Layout {
+    size: usize,
+    align: usize,
+    /// Nothing but the minimal size of 1 byte.
+    empty: bool,
+    base: Option<(String, usize)>,
+    /// Virtual table pointer the record adds at offset 0.
+    vtable: Option<Vec<VTableSlot>>,
+    /// The record or one of its bases has a virtual table.
+    polymorphic: bool,
+    slots: Vec<Slot>,
+    /// Why the members after the last slot couldn't be laid out.
+    incomplete: Option<String>,
+}
+
+enum Slot {
+    Member {
+        offset: usize,
+        name: String,
+        ty: Type,
+        comment: Option<String>,
+    },
+    /// Bit fields sharing the same storage.
+    Bits {
+        offset: usize,
+        kind: FieldKind,
+        names: Vec<(String, u32)>,
+    },
+}
+
+impl Slot {
+    fn offset(&self) -> usize {
+        match self {
+            Self::Member { offset, .. } | Self::Bits { offset, .. } => *offset,
+        }
+    }
+}
+
+pub(super) struct Importer {
+    records: Vec<Record>,
+    index: HashMap<String, usize>,
+    types: HashMap<String, Type>,
+    /// Types every file of the format knows without declaring them.
+    builtin: fn(&str) -> Option<Type>,
+    /// `None` while the layout is being computed, to catch records containing themselves.
+    layouts: HashMap<String, Option<Rc<Layout>>>,
+    warnings: Vec<String>,
+}
+
+impl Importer {
+    pub fn new(
+        records: Vec<Record>,
+        types: HashMap<String, Type>,
+        builtin: fn(&str) -> Option<Type>,
+        warnings: Vec<String>,
+    ) -> Self {
+        let index = records
+            .iter()
+            .enumerate()
+            .map(|(i, r)| (r.name.clone(), i))
+            .collect();
+
+        Self {
+            records,
+            index,
+            types,
+            builtin,
+            layouts: HashMap::new(),
+            warnings,
+        }
+    }
+
+    /// Builds a class out of every named record.
+    pub fn import(mut self) -> Imported {
+        let names = self
+            .records
+            .iter()
+            .filter(|r| r.defined && !r.anonymous && !r.inline)
+            .map(|r| r.name.clone())
+            .collect::<Vec<_>>();
+        let classes = names.iter().map(|name| self.class(name)).collect();
+
+        Imported {
+            classes,
+            warnings: self.warnings,
+        }
+    }
+
+    fn resolve(&self, mut ty: Type) -> Type {
+        for _ in 0..32 {
+            let Type::Named(name) = &ty else {
+                return ty;
+            };
+
+            if self
+                .index
+                .get(name)
+                .is_some_and(|&i| self.records[i].defined)
+            {
+                return Type::Record(name.clone());
+            } else if let Some(next) = self
+                .types
+                .get(name)
+                .cloned()
+                .or_else(|| (self.builtin)(name))
+            {
+                ty = next;
+            } else {
+                return ty;
+            }
+        }
+        ty
+    }
+
+    /// Size and alignment, the error says why it's unknown.
+    fn size_align(&mut self, ty: &Type) -> Result<(usize, usize), String> {
+        match ty {
+            Type::Void | Type::Function => Err("it has no size".into()),
+            Type::Field(field, align) => Ok((field.kind.size(), *align)),
+            Type::Prim(kind) | Type::Enum(_, kind) => {
+                let size = kind.size();
+                let align = match kind {
+                    FieldKind::StdString => 8,
+                    FieldKind::I128 | FieldKind::U128 if !cfg!(windows) => 16,
+                    _ => size.min(8),
+                };
+                Ok((size, align))
+            }
+            Type::Char(encoding) => Ok((encoding.unit_size(), encoding.unit_size())),
+            Type::Pointer(_) => Ok((8, 8)),
+            Type::Container(kind, _) => Ok((kind.size(), 8)),
+            Type::Array(element, len) => {
+                let (size, align) = self.size_align(&self.resolve(*element.clone()))?;
+                let len = len.ok_or("array size can't be evaluated")?;
+                let size = size
+                    .checked_mul(len)
+                    .filter(|&size| size <= SIZE_LIMIT)
+                    .ok_or_else(|| format!("array of {len} elements is too big"))?;
+                Ok((size, align))
+            }
+            Type::Record(name) => {
+                let layout = self.layout(name);
+                match &layout.incomplete {
+                    Some(reason) => Err(reason.clone()),
+                    None => Ok((layout.size, layout.align)),
+                }
+            }
+            Type::Named(name) => Err(format!("type `{name}` is unknown")),
+        }
+    }
+
+    fn layout(&mut self, name: &str) -> Rc<Layout> {
+        match self.layouts.get(name) {
+            Some(Some(layout)) => return layout.clone(),
+            Some(None) => {
+                return Rc::new(Layout {
+                    incomplete: Some(format!("`{name}` contains itself")),
+                    ..Default::default()
+                })
+            }
+            None => {}
+        }
+
+        self.layouts.insert(name.to_owned(), None);
+        let layout = Rc::new(self.compute_layout(name));
+        self.layouts.insert(name.to_owned(), Some(layout.clone()));
+        layout
+    }
+
+    fn compute_layout(&mut self, name: &str) -> Layout {
+        let record = self.records[self.index[name]].clone();
+        let mut layout = Layout {
+            align: 1,
+            ..Default::default()
+        };
+        let mut offset = 0;
+
+        if !record.virtuals.is_empty() {
+            layout.vtable = Some(record.virtuals.clone());
+            layout.polymorphic = true;
+            layout.align = 8;
+            offset = 8;
+        }
+
+        if let Some(base) = record.base.as_ref() {
+            let Type::Record(base) = self.resolve(Type::Named(base.clone())) else {
+                layout.incomplete = Some(format!("base `{base}` is unknown"));
+                return layout;
+            };
+
+            let base_layout = self.layout(&base);
+            if let Some(reason) = &base_layout.incomplete {
+                layout.incomplete = Some(reason.clone());
+                return layout;
+            }
+
+            // Base that has a virtual table already provides the pointer.
+            if base_layout.polymorphic {
+                layout.vtable = None;
+                offset = 0;
+            }
+            layout.polymorphic |= base_layout.polymorphic;
+            layout.align = layout.align.max(base_layout.align);
+
+            let base_size = if base_layout.empty {
+                0
+            } else {
+                base_layout.size
+            };
+            layout.base = Some((base, offset));
+            offset = offset.saturating_add(base_size);
+        }
+
+        let mut union_size = 0;
+        // Offset, size and used bits of the current bit field storage.
+        let mut bits: Option<(usize, usize, u32)> = None;
+
+        for member in record.members {
+            let ty = self.resolve(member.ty);
+            let (size, natural_align) = match self.size_align(&ty) {
+                Ok(size_align) => size_align,
+                Err(reason) => {
+                    let member = if member.name.is_empty() {
+                        "anonymous member".to_owned()
+                    } else {
+                        format!("`{}`", member.name)
+                    };
+                    layout.incomplete = Some(format!("{member} of `{name}`: {reason}"));
+                    break;
+                }
+            };
+
+            let mut align = record
+                .pack
+                .map_or(natural_align, |pack| natural_align.min(pack));
+            if let Some(forced) = member.align {
+                align = align.max(forced);
+            }
+            layout.align = layout.align.max(align);
+
+            if let Some(width) = member.bits {
+                let Some(kind) = scalar_kind(&ty) else {
+                    layout.incomplete = Some(format!(
+                        "bit field `{}` of `{name}` isn't an integer",
+                        member.name
+                    ));
+                    break;
+                };
+
+                match bits.as_mut() {
+                    _ if width == 0 => {
+                        bits = None;
+                        offset = align_up(offset, align);
+                    }
+                    Some((at, unit, used))
+                        if !record.union
+                            && *unit == size
+                            && member
+                                .offset
+                                .map_or(used.saturating_add(width) <= size as u32 * 8, |o| {
+                                    o == *at
+                                }) =>
+                    {
+                        *used = used.saturating_add(width);
+                        if let Some(Slot::Bits { names, .. }) = layout.slots.last_mut() {
+                            names.push((member.name, width));
+                        }
+                    }
+                    _ => {
+                        let at = if record.union {
+                            0
+                        } else {
+                            member.offset.unwrap_or_else(|| align_up(offset, align))
+                        };
+                        let Some(end) = end_of(at, size) else {
+                            layout.incomplete = Some(format!(
+                                "bit field `{}` of `{name}` is too far",
+                                member.name
+                            ));
+                            break;
+                        };
+                        layout.slots.push(Slot::Bits {
+                            offset: at,
+                            kind,
+                            names: vec![(member.name, width)],
+                        });
+                        bits = Some((at, size, width));
+                        if record.union {
+                            union_size = union_size.max(size);
+                        } else {
+                            offset = end;
+                        }
+                    }
+                }
+                continue;
+            }
+            bits = None;
+
+            let at = if record.union {
+                union_size = union_size.max(size);
+                0
+            } else {
+                member.offset.unwrap_or_else(|| align_up(offset, align))
+            };
+            let Some(end) = end_of(at, size) else {
+                layout.incomplete = Some(format!("`{}` of `{name}` is too far", member.name));
+                break;
+            };
+            layout.slots.push(Slot::Member {
+                offset: at,
+                name: member.name,
+                ty,
+                comment: member.comment,
+            });
+            if !record.union {
+                offset = end;
+            }
+        }
+
+        if let Some(forced) = record.align {
+            layout.align = layout.align.max(forced);
+        }
+
+        let size = if record.union { union_size } else { offset };
+        layout.size = align_up(size, layout.align);
+        if layout.size == 0 {
+            layout.size = 1;
+            layout.empty = true;
+        }
+
+        if let Some(size) = record.size.filter(|&size| size <= SIZE_LIMIT) {
+            layout.empty = size <= 1 && layout.slots.is_empty() && layout.base.is_none();
+            layout.size = size;
+        }
+
+        layout
+    }
+
+    /// Builds the class out of the record, bases that YClass would place differently are inlined.
+    fn class(&mut self, name: &str) -> DataClass {
+        let layout = self.layout(name);
+        let record = &self.records[self.index[name]];
+        let union = record.union;
+
+        let mut fields = vec![];
+        let mut base = None;
+        let mut start = 0;
+
+        if union {
+            self.add_value(&Type::Record(name.to_owned()), "value", 0, &mut fields);
+        } else {
+            if let Some((base_name, base_offset)) = &layout.base {
+                let base_layout = self.layout(base_name);
+                let base_size = if base_layout.empty {
+                    0
+                } else {
+                    base_layout.size
+                };
+                // Classes are padded to 8 bytes when loaded, and members the compiler placed
+                // in the tail padding of the base would end up inside of it.
+                if *base_offset == 0
+                    && align_up(base_size, 8) == base_size
+                    && layout.slots.iter().all(|slot| slot.offset() >= base_size)
+                {
+                    base = Some(base_name.clone());
+                    start = base_size;
+                } else {
+                    self.warnings.push(format!(
+                        "Base `{base_name}` of `{name}` is inlined, YClass would place it differently"
+                    ));
+                    self.add_record(base_name, "", *base_offset, &mut fields);
+                }
+            }
+            self.add_layout(&layout, "", 0, &mut fields);
+        }
+
+        if let Some(reason) = &layout.incomplete {
+            self.warnings.push(format!(
+                "Fields of `{name}` after the last known one are left out, {reason}"
+            ));
+        }
+
+        // Keeps the size of the class when it ends with padding or a long array.
+        let sized = layout.incomplete.is_none() || self.records[self.index[name]].size.is_some();
+        if sized && !layout.empty && layout.size <= SIZE_LIMIT {
+            let end = fields.iter().map(|f| f.offset + f.kind.size()).max();
+            padding(end.unwrap_or(0).max(start), layout.size, &mut fields);
+        }
+
+        fields.iter_mut().for_each(|f| f.offset -= start);
+        DataClass {
+            id: None,
+            name: name.to_owned(),
+            base,
+            base_id: None,
+            comment: self.records[self.index[name]].comment.clone(),
+            fields,
+        }
+    }
+
+    /// Adds fields of a struct embedded at `offset`, their names start with `prefix`.
+    fn add_record(&mut self, name: &str, prefix: &str, offset: usize, out: &mut Vec<DataField>) {
+        let layout = self.layout(name);
+        if let Some((base, base_offset)) = &layout.base {
+            self.add_record(base, prefix, offset + base_offset, out);
+        }
+        self.add_layout(&layout, prefix, offset, out);
+    }
+
+    fn add_layout(
+        &mut self,
+        layout: &Layout,
+        prefix: &str,
+        offset: usize,
+        out: &mut Vec<DataField>,
+    ) {
+        if let Some(slots) = &layout.vtable {
+            out.push(DataField {
+                offset,
+                slots: slots.clone(),
+                ..DataField::new(&prefixed(prefix, "vtable"), FieldKind::VTable)
+            });
+        }
+
+        for slot in &layout.slots {
+            match slot {
+                Slot::Member {
+                    offset: at,
+                    name,
+                    ty,
+                    comment,
+                } => {
+                    let first = out.len();
+                    self.add_value(ty, &prefixed(prefix, name), offset + at, out);
+                    if let (Some(field), Some(comment)) = (out.get_mut(first), comment) {
+                        field.comment.get_or_insert_with(|| comment.clone());
+                    }
+                }
+                Slot::Bits {
+                    offset: at,
+                    kind,
+                    names,
+                } => {
+                    let Some((first, _)) = names.iter().find(|(n, _)| !n.is_empty()) else {
+                        continue;
+                    };
+                    let layout = names
+                        .iter()
+                        .map(|(n, width)| {
+                            format!("{}: {width}", if n.is_empty() { "_" } else { n })
+                        })
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    out.push(DataField {
+                        offset: offset + at,
+                        comment: Some(format!("Bit fields {layout}")),
+                        metadata: Some("hex".into()),
+                        ..DataField::new(&prefixed(prefix, first), *kind)
+                    });
+                }
+            }
+        }
+    }
+
+    fn add_value(&mut self, ty: &Type, name: &str, offset: usize, out: &mut Vec<DataField>) {
+        // Padding of classes generated by YClass.
+        if name.starts_with("_pad") {
+            return;
+        }
+
+        let field = match ty {
+            Type::Prim(kind) => DataField::new(name, *kind),
+            Type::Char(encoding) => DataField {
+                metadata: Some("char".into()),
+                ..DataField::new(name, char_kind(*encoding))
+            },
+            Type::Enum(enumeration, kind) => DataField {
+                comment: Some(format!("enum {enumeration}")),
+                ..DataField::new(name, *kind)
+            },
+            Type::Pointer(pointee) => match self.resolve(*pointee.clone()) {
+                Type::Char(encoding) => DataField::new(name, FieldKind::TextPtr(encoding, 64)),
+                Type::Record(class) if !self.records[self.index[&class]].anonymous => DataField {
+                    metadata: Some(class),
+                    ..DataField::new(name, FieldKind::Ptr)
+                },
+                // Declared somewhere else, an empty class is created for it.
+                Type::Named(class) => DataField {
+                    metadata: Some(class),
+                    ..DataField::new(name, FieldKind::Ptr)
+                },
+                _ => DataField {
+                    metadata: Some("hex".into()),
+                    ..DataField::new(name, FieldKind::U64)
+                },
+            },
+            Type::Array(element, Some(len)) => {
+                let element = self.resolve(*element.clone());
+                if let Type::Char(encoding) = element {
+                    DataField::new(name, FieldKind::Text(encoding, *len))
+                } else {
+                    let size = self.size_align(&element).map_or(0, |(size, _)| size);
+                    if *len > ARRAY_FIELDS_LIMIT {
+                        let Some(end) = len.checked_mul(size).and_then(|size| end_of(offset, size))
+                        else {
+                            self.warnings
+                                .push(format!("`{name}` is left out, the array is too big"));
+                            return;
+                        };
+                        padding(offset, end, out);
+                    } else {
+                        for i in 0..*len {
+                            self.add_value(
+                                &element,
+                                &format!("{name}_{i}"),
+                                offset + i * size,
+                                out,
+                            );
+                        }
+                    }
+                    return;
+                }
+            }
+            Type::Container(kind, element) => {
+                let element = self.resolve(*element.clone());
+                match (&element, scalar_kind(&element)) {
+                    (_, Some(element)) => DataField {
+                        element: Some(element),
+                        ..DataField::new(name, *kind)
+                    },
+                    (Type::Record(class) | Type::Named(class), None) => DataField {
+                        metadata: Some(class.clone()),
+                        ..DataField::new(name, *kind)
+                    },
+                    _ => DataField {
+                        element: Some(FieldKind::Unk8),
+                        ..DataField::new(name, *kind)
+                    },
+                }
+            }
+            Type::Record(record) => {
+                if !self.records[self.index[record]].union {
+                    self.add_record(record, name, offset, out);
+                    return;
+                }
+                let Some(field) = self.union(record, name, offset) else {
+                    return;
+                };
+                field
+            }
+            Type::Field(field, _) => DataField {
+                name: name.to_owned(),
+                ..(**field).clone()
+            },
+            Type::Void | Type::Function | Type::Array(_, None) | Type::Named(_) => return,
+        };
+
+        out.push(DataField { offset, ..field });
+    }
+
+    /// Union field, only members that are a single field can be alternatives.
+    fn union(&mut self, record: &str, name: &str, offset: usize) -> Option<DataField> {
+        let layout = self.layout(record);
+        let mut alternatives = vec![];
+
+        for slot in &layout.slots {
+            let mut fields = vec![];
+            match slot {
+                Slot::Member { name, ty, .. } => self.add_value(ty, name, 0, &mut fields),
+                Slot::Bits { kind, names, .. } => {
+                    if let Some((first, _)) = names.first() {
+                        fields.push(DataField {
+                            metadata: Some("hex".into()),
+                            ..DataField::new(first, *kind)
+                        });
+                    }
+                }
+            }
+
+            match fields.as_slice() {
+                [field] if field.offset == 0 && !matches!(field.kind, FieldKind::Union(_)) => {
+                    alternatives.push(fields.remove(0))
+                }
+                _ => {
+                    if let Slot::Member { name: member, .. } = slot {
+                        self.warnings.push(format!(
+                            "`{member}` of union `{record}` is left out, it isn't a single field"
+                        ));
+                    }
+                }
+            }
+        }
+
+        if alternatives.is_empty() {
+            return None;
+        }
+
+        let name = if name.is_empty() {
+            format!("union_{offset:x}")
+        } else {
+            name.to_owned()
+        };
+        Some(DataField {
+            alternatives,
+            ..DataField::new(&name, FieldKind::Union(layout.size))
+        })
+    }
+}
+
+/// End of `size` bytes at `offset`, `None` if it's past [`SIZE_LIMIT`].
+fn end_of(offset: usize, size: usize) -> Option<usize> {
+    offset.checked_add(size).filter(|&end| end <= SIZE_LIMIT)
+}
+
+/// Hex fields covering bytes from `offset` to `end`, like the padding YClass adds itself.
+fn padding(mut offset: usize, end: usize, out: &mut Vec<DataField>) {
+    while offset < end {
+        let kind = match end - offset {
+            8.. => FieldKind::Unk64,
+            4.. => FieldKind::Unk32,
+            2.. => FieldKind::Unk16,
+            _ => FieldKind::Unk8,
+        };
+        out.push(DataField {
+            offset,
+            ..DataField::new("", kind)
+        });
+        offset += kind.size();
+    }
+}
+
+fn prefixed(prefix: &str, name: &str) -> String {
+    match (prefix.is_empty(), name.is_empty()) {
+        (true, _) => name.to_owned(),
+        (false, true) => prefix.to_owned(),
+        (false, false) => format!("{prefix}_{name}"),
+    }
+}
+
+/// Kind of a single numeric value, used for bit fields and container elements.
+pub(super) fn scalar_kind(ty: &Type) -> Option<FieldKind> {
+    match ty {
+        Type::Prim(kind) | Type::Enum(_, kind) => Some(*kind),
+        Type::Field(field, _) => Some(field.kind),
+        Type::Char(encoding) => Some(char_kind(*encoding)),
+        Type::Pointer(_) => Some(FieldKind::U64),
+        Type::Container(kind, _) => Some(*kind),
+        _ => None,
+    }
+}
+
+/// Names, offsets and kinds of the fields, for tests of the importers.
+#[cfg(test)]
+pub(super) fn fields(class: &DataClass) -> Vec<(&str, usize, FieldKind)> {
+    class
+        .fields
+        .iter()
+        .map(|f| (f.name.as_str(), f.offset, f.kind))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn member(name: &str, ty: Type, offset: Option<usize>) -> Member {
+        Member {
+            name: name.into(),
+            ty,
+            bits: None,
+            align: None,
+            offset,
+            comment: None,
+        }
+    }
+
+    fn import(records: Vec<Record>) -> Imported {
+        Importer::new(records, HashMap::new(), |_| None, vec![]).import()
+    }
+
+    /// `struct B { virtual ~B(); int x; }` as the Itanium ABI lays it out.
+    fn polymorphic_base() -> Record {
+        Record {
+            name: "B".into(),
+            defined: true,
+            virtuals: vec![VTableSlot::new(0)],
+            members: vec![member("x", Type::Prim(FieldKind::I32), Some(8))],
+            size: Some(16),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn base_with_members_in_its_tail_padding_is_inlined() {
+        let derived = Record {
+            name: "D".into(),
+            defined: true,
+            base: Some("B".into()),
+            members: vec![member("y", Type::Prim(FieldKind::I32), Some(12))],
+            size: Some(16),
+            ..Default::default()
+        };
+
+        let imported = import(vec![polymorphic_base(), derived]);
+        let class = &imported.classes[1];
+        assert_eq!(class.base, None);
+        assert_eq!(
+            fields(class),
+            [
+                ("vtable", 0, FieldKind::VTable),
+                ("x", 8, FieldKind::I32),
+                ("y", 12, FieldKind::I32),
+            ]
+        );
+        assert!(imported.warnings[0].contains("is inlined"));
+    }
+
+    #[test]
+    fn base_is_kept_when_members_follow_it() {
+        let derived = Record {
+            name: "D".into(),
+            defined: true,
+            base: Some("B".into()),
+            members: vec![member("y", Type::Prim(FieldKind::I32), Some(16))],
+            size: Some(24),
+            ..Default::default()
+        };
+
+        let imported = import(vec![polymorphic_base(), derived]);
+        let class = &imported.classes[1];
+        assert_eq!(class.base.as_deref(), Some("B"));
+        assert_eq!(
+            fields(class),
+            [("y", 0, FieldKind::I32), ("", 4, FieldKind::Unk32)]
+        );
+        assert!(imported.warnings.is_empty());
+    }
+}
@@ -0,0 +1,450 @@
+/// This module contains three-way merging of projects, used as `yclass merge`.
+use super::{DataClass, DataField, ProjectData, PROJECT_VERSION};
+use crate::class::ClassId;
+use std::{
+    collections::{BTreeSet, HashMap},
+    fs,
+};
+
+const USAGE: &str = "Usage: yclass merge <BASE> <OURS> <THEIRS> [OUTPUT]";
+
+/// Runs `yclass merge`, the merged project is written to `OUTPUT` or `OURS` if there is none,
+/// which is what git merge drivers expect. Returns the exit code: 0 if the merge is clean,
+/// 1 if there are conflicts and 2 on errors.
+pub fn run(args: &[String]) -> i32 {
+    let [base, ours, theirs, rest @ ..] = args else {
+        eprintln!("{USAGE}");
+        return 2;
+    };
+
+    match merge_files(base, ours, theirs, rest.first().unwrap_or(ours)) {
+        Ok(conflicts) if conflicts.is_empty() => 0,
+        Ok(conflicts) => {
+            for conflict in &conflicts {
+                eprintln!("CONFLICT: {conflict}");
+            }
+            eprintln!(
+                "{} conflict(s), our side was kept for each of them",
+                conflicts.len()
+            );
+            1
+        }
+        Err(e) => {
+            eprintln!("Failed to merge the projects. {e}");
+            2
+        }
+    }
+}
+
+fn merge_files(base: &str, ours: &str, theirs: &str, output: &str) -> eyre::Result<Vec<String>> {
+    let read = |path: &str| {
+        fs::read_to_string(path)
+            .map_err(Into::into)
+            .and_then(|text| ProjectData::from_str(&text))
+            .map_err(|e| eyre::eyre!("{path}: {e}"))
+    };
+
+    let (merged, conflicts) = merge(read(base)?, read(ours)?, read(theirs)?);
+    fs::write(output, merged.to_string())?;
+    Ok(conflicts)
+}
+
+/// Merges changes made in `ours` and `theirs` since `base`. Classes are matched by
+/// their ids and fields by their offsets. When both sides changed the same thing
+/// differently our side is kept and the conflict is reported.
+pub fn merge(
+    mut base: ProjectData,
+    ours: ProjectData,
+    mut theirs: ProjectData,
+) -> (ProjectData, Vec<String>) {
+    adopt_ids(&mut base, &ours.classes);
+    adopt_ids(&mut theirs, &ours.classes);
+    adopt_ids(&mut theirs, &base.classes);
+
+    let mut conflicts = vec![];
+    let mut classes = vec![];
+
+    for our in &ours.classes {
+        match (find(&base.classes, our), find(&theirs.classes, our)) {
+            (Some(b), Some(their)) => classes.push(merge_class(b, our, their, &mut conflicts)),
+            (Some(b), None) if b == our => {}
+            (Some(_), None) => {
+                conflicts.push(format!(
+                    "Class `{}` was changed by us and deleted by them",
+                    our.name
+                ));
+                classes.push(our.clone());
+            }
+            (None, Some(their)) if their != our => {
+                conflicts.push(format!(
+                    "Class `{}` was added differently by both",
+                    our.name
+                ));
+                classes.push(our.clone());
+            }
+            (None, _) => classes.push(our.clone()),
+        }
+    }
+
+    for their in &theirs.classes {
+        if find(&ours.classes, their).is_some() {
+            continue;
+        }
+
+        match find(&base.classes, their) {
+            Some(b) if b == their => {}
+            Some(_) => {
+                conflicts.push(format!(
+                    "Class `{}` was deleted by us and changed by them",
+                    their.name
+                ));
+                classes.push(their.clone());
+            }
+            None => classes.push(their.clone()),
+        }
+    }
+
+    // Projects with two classes of the same name can't be loaded, so the later one
+    // is renamed like `ClassList::unique_name` does.
+    let mut taken = classes
+        .iter()
+        .map(|c| c.name.clone())
+        .collect::<BTreeSet<_>>();
+    let mut seen = BTreeSet::new();
+    for class in &mut classes {
+        if seen.insert(class.name.clone()) {
+            continue;
+        }
+
+        let name = (2..)
+            .map(|i| format!("{}{i}", class.name))
+            .find(|n| !taken.contains(n))
+            .unwrap();
+        conflicts.push(format!(
+            "Class name `{}` is used twice, the second class is renamed to `{name}`",
+            class.name
+        ));
+        taken.insert(name.clone());
+        seen.insert(name.clone());
+        class.name = name;
+    }
+
+    let read_only = pick(
+        "Read-only mode",
+        &base.read_only,
+        &ours.read_only,
+        &theirs.read_only,
+        &mut conflicts,
+    );
+
+    let includes = pick(
+        "Includes",
+        &base.includes,
+        &ours.includes,
+        &theirs.includes,
+        &mut conflicts,
+    );
+
+    let merged = ProjectData {
+        version: PROJECT_VERSION,
+        includes,
+        classes,
+        read_only,
+        // Session is personal, there is nothing to merge.
+        session: ours.session,
+    };
+    (merged, conflicts)
+}
+
+/// Gives classes of the project ids of the same classes in `reference`. Sides that migrated
+/// the same version 1 project on their own have different random ids for every class.
+fn adopt_ids(project: &mut ProjectData, reference: &[DataClass]) {
+    let ids = project
+        .classes
+        .iter()
+        .filter_map(|class| {
+            let id = class.id?;
+            let same = find(reference, class)?.id?;
+            (id != same).then_some((id, same))
+        })
+        .collect::<HashMap<_, _>>();
+    if ids.is_empty() {
+        return;
+    }
+
+    for class in &mut project.classes {
+        for id in [&mut class.id, &mut class.base_id] {
+            if let Some(&same) = id.and_then(|id| ids.get(&id)) {
+                *id = Some(same);
+            }
+        }
+        class
+            .fields
+            .iter_mut()
+            .for_each(|f| adopt_field_ids(f, &ids));
+    }
+}
+
+fn adopt_field_ids(field: &mut DataField, ids: &HashMap<ClassId, ClassId>) {
+    if let Some(&same) = field.class.and_then(|id| ids.get(&id)) {
+        field.class = Some(same);
+    }
+    field
+        .alternatives
+        .iter_mut()
+        .for_each(|alt| adopt_field_ids(alt, ids));
+}
+
+/// Finds the same class by id, or by name in projects that had no ids.
+fn find<'a>(classes: &'a [DataClass], class: &DataClass) -> Option<&'a DataClass> {
+    classes
+        .iter()
+        .find(|c| c.id.is_some() && c.id == class.id)
+        .or_else(|| classes.iter().find(|c| c.name == class.name))
+}
+
+/// Side that changed the value, `None` if both changed it differently.
+fn merge_value<T: PartialEq + Clone>(base: &T, ours: &T, theirs: &T) -> Option<T> {
+    if ours == theirs || theirs == base {
+        Some(ours.clone())
+    } else if ours == base {
+        Some(theirs.clone())
+    } else {
+        None
+    }
+}
+
+fn pick<T: PartialEq + Clone>(
+    what: &str,
+    base: &T,
+    ours: &T,
+    theirs: &T,
+    conflicts: &mut Vec<String>,
+) -> T {
+    merge_value(base, ours, theirs).unwrap_or_else(|| {
+        conflicts.push(format!("{what} was changed differently by both"));
+        ours.clone()
+    })
+}
+
+fn merge_class(
+    base: &DataClass,
+    ours: &DataClass,
+    theirs: &DataClass,
+    conflicts: &mut Vec<String>,
+) -> DataClass {
+    let class = &ours.name;
+    let fields = merge_fields(class, &base.fields, &ours.fields, &theirs.fields, conflicts);
+
+    DataClass {
+        id: ours.id.or(theirs.id),
+        name: pick(
+            &format!("Name of class `{class}`"),
+            &base.name,
+            &ours.name,
+            &theirs.name,
+            conflicts,
+        ),
+        base: pick(
+            &format!("Base of class `{class}`"),
+            &base.base,
+            &ours.base,
+            &theirs.base,
+            conflicts,
+        ),
+        base_id: merge_value(&base.base_id, &ours.base_id, &theirs.base_id).unwrap_or(ours.base_id),
+        comment: pick(
+            &format!("Comment of class `{class}`"),
+            &base.comment,
+            &ours.comment,
+            &theirs.comment,
+            conflicts,
+        ),
+        fields,
+    }
+}
+
+fn merge_fields(
+    class: &str,
+    base: &[DataField],
+    ours: &[DataField],
+    theirs: &[DataField],
+    conflicts: &mut Vec<String>,
+) -> Vec<DataField> {
+    let offsets = [base, ours, theirs]
+        .iter()
+        .flat_map(|fields| fields.iter().map(|f| f.offset))
+        .collect::<BTreeSet<_>>();
+    fn at(fields: &[DataField], offset: usize) -> Option<&DataField> {
+        fields.iter().find(|f| f.offset == offset)
+    }
+
+    let mut merged: Vec<DataField> = vec![];
+    for offset in offsets {
+        let (b, o, t) = (at(base, offset), at(ours, offset), at(theirs, offset));
+        let field = merge_value(&b, &o, &t).unwrap_or_else(|| {
+            conflicts.push(format!(
+                "Field of class `{class}` at {offset:#X} was changed differently by both"
+            ));
+            o
+        });
+
+        let Some(field) = field else {
+            continue;
+        };
+
+        // Fields of both sides are merged independently and might overlap.
+        if let Some(prev) = merged.last() {
+            if prev.offset + prev.kind.size() > field.offset {
+                conflicts.push(format!(
+                    "Fields of class `{class}` at {:#X} and {offset:#X} overlap",
+                    prev.offset
+                ));
+                continue;
+            }
+        }
+        merged.push(field.clone());
+    }
+
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{field::FieldKind, project::layout::fields};
+
+    fn project(text: &str) -> ProjectData {
+        ProjectData::from_str(text).unwrap()
+    }
+
+    /// Version 2 project with a single `Player` class of the given fields.
+    fn player(fields: &str) -> ProjectData {
+        project(&format!(
+            r#"(version: 2, classes: [(id: Some(1), name: "Player", fields: [{fields}])])"#
+        ))
+    }
+
+    const HEALTH: &str = r#"(name: "health", offset: 8, kind: I32, metadata: None)"#;
+
+    #[test]
+    fn changes_of_one_side_are_taken() {
+        let ours = player(&format!(
+            r#"{HEALTH}, (name: "armor", offset: 16, kind: U32, metadata: None)"#
+        ));
+        let mut theirs = player(HEALTH);
+        theirs.classes[0].comment = Some("Local player".into());
+
+        let (merged, conflicts) = merge(player(HEALTH), ours, theirs);
+        assert!(conflicts.is_empty(), "{conflicts:?}");
+        assert_eq!(
+            fields(&merged.classes[0]),
+            [("health", 8, FieldKind::I32), ("armor", 16, FieldKind::U32)]
+        );
+        assert_eq!(merged.classes[0].comment.as_deref(), Some("Local player"));
+    }
+
+    #[test]
+    fn field_changed_differently_keeps_ours() {
+        let ours = player(r#"(name: "health", offset: 8, kind: U32, metadata: None)"#);
+        let theirs = player(r#"(name: "health", offset: 8, kind: F32, metadata: None)"#);
+
+        let (merged, conflicts) = merge(player(HEALTH), ours, theirs);
+        assert_eq!(fields(&merged.classes[0]), [("health", 8, FieldKind::U32)]);
+        assert_eq!(
+            conflicts,
+            ["Field of class `Player` at 0x8 was changed differently by both"]
+        );
+    }
+
+    #[test]
+    fn overlapping_fields_are_reported() {
+        let ours = player(&format!(
+            r#"{HEALTH}, (name: "position", offset: 16, kind: U64, metadata: None)"#
+        ));
+        let theirs = player(&format!(
+            r#"{HEALTH}, (name: "level", offset: 20, kind: I32, metadata: None)"#
+        ));
+
+        let (merged, conflicts) = merge(player(HEALTH), ours, theirs);
+        assert_eq!(
+            fields(&merged.classes[0]),
+            [
+                ("health", 8, FieldKind::I32),
+                ("position", 16, FieldKind::U64)
+            ]
+        );
+        assert_eq!(
+            conflicts,
+            ["Fields of class `Player` at 0x10 and 0x14 overlap"]
+        );
+    }
+
+    #[test]
+    fn class_changed_by_us_and_deleted_by_them_is_kept() {
+        let mut ours = player(HEALTH);
+        ours.classes[0].comment = Some("Local player".into());
+        let theirs = project("(version: 2, classes: [])");
+
+        let (merged, conflicts) = merge(player(HEALTH), ours, theirs);
+        assert_eq!(merged.classes.len(), 1);
+        assert_eq!(
+            conflicts,
+            ["Class `Player` was changed by us and deleted by them"]
+        );
+    }
+
+    #[test]
+    fn class_added_differently_by_both_keeps_ours() {
+        let base = project("(version: 2, classes: [])");
+        let ours = player(HEALTH);
+        let theirs = player(r#"(name: "health", offset: 8, kind: F32, metadata: None)"#);
+
+        let (merged, conflicts) = merge(base, ours, theirs);
+        assert_eq!(fields(&merged.classes[0]), [("health", 8, FieldKind::I32)]);
+        assert_eq!(conflicts, ["Class `Player` was added differently by both"]);
+    }
+
+    /// Version 1 project, ids are made up every time it's migrated.
+    const LEGACY: &str = r#"(classes: [
+        (name: "Player", fields: [
+            (name: "target", offset: 0, kind: Ptr, metadata: Some("Player")),
+            (name: "health", offset: 8, kind: I32, metadata: None),
+        ]),
+    ])"#;
+
+    #[test]
+    fn sides_migrated_on_their_own_merge_cleanly() {
+        let mut theirs = project(LEGACY);
+        theirs.classes[0].comment = Some("Local player".into());
+
+        let (merged, conflicts) = merge(project(LEGACY), project(LEGACY), theirs);
+        assert!(conflicts.is_empty(), "{conflicts:?}");
+
+        let class = &merged.classes[0];
+        assert_eq!(class.comment.as_deref(), Some("Local player"));
+        assert_eq!(class.fields[0].class, class.id);
+    }
+
+    #[test]
+    fn duplicate_class_names_are_renamed() {
+        let base = project(r#"(version: 2, classes: [(id: Some(1), name: "A", fields: [])])"#);
+        let ours = project(
+            r#"(version: 2, classes: [
+                (id: Some(1), name: "A", fields: []),
+                (id: Some(2), name: "Foo", fields: []),
+            ])"#,
+        );
+        let theirs = project(r#"(version: 2, classes: [(id: Some(1), name: "Foo", fields: [])])"#);
+
+        let (merged, conflicts) = merge(base, ours, theirs);
+        let names = merged
+            .classes
+            .iter()
+            .map(|c| c.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["Foo", "Foo2"]);
+        assert!(conflicts.iter().any(|c| c.contains("renamed to `Foo2`")));
+        assert!(merged.load(None).is_ok());
+    }
+}
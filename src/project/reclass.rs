@@ -0,0 +1,581 @@
+/// This module contains import of ReClass.NET(`.rcnet`) and ReClassEx(`.reclass`) projects.
+/// Nodes are placed one after another like ReClass does, hex nodes become hex fields.
+use super::{
+    layout::{ident, Importer, Member, Record, Type},
+    DataField, Imported,
+};
+use crate::field::{FieldKind, TextEncoding, VTableSlot};
+use roxmltree::{Document, Node};
+use std::{
+    collections::HashMap,
+    io::{Cursor, Read},
+};
+
+/// Reads classes of a ReClass.NET project, it's a zip archive with `Data.xml` inside.
+pub fn import_rcnet(data: &[u8]) -> eyre::Result<Imported> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(data))?;
+    let mut xml = String::new();
+    archive
+        .by_name("Data.xml")
+        .map_err(|_| eyre::eyre!("There is no Data.xml, it isn't a ReClass.NET project"))?
+        .read_to_string(&mut xml)?;
+
+    let document = Document::parse(&xml)?;
+    let root = document.root_element();
+    if !root.has_tag_name("reclass") {
+        eyre::bail!("Data.xml isn't a ReClass.NET project");
+    }
+
+    let mut project = Project {
+        // Platform of the ReClass.NET build that saved the project, `x86` or `x64`.
+        pointer_size: if root.attribute("platform") == Some("x86") {
+            4
+        } else {
+            8
+        },
+        ..Default::default()
+    };
+
+    let classes = children(root, "classes")
+        .flat_map(|c| children(c, "class"))
+        .collect::<Vec<_>>();
+    for (i, class) in classes.iter().enumerate() {
+        let name = class_name(class.attribute("name"), i);
+        if let Some(uuid) = class.attribute("uuid") {
+            project.classes.insert(uuid.to_owned(), name);
+        }
+    }
+
+    for item in children(root, "enums").flat_map(|e| children(e, "enum")) {
+        let kind = match item.attribute("size") {
+            Some("1" | "OneByte") => FieldKind::I8,
+            Some("2" | "TwoBytes") => FieldKind::I16,
+            Some("8" | "EightBytes") => FieldKind::I64,
+            _ => FieldKind::I32,
+        };
+        project
+            .enums
+            .insert(item.attribute("name").unwrap_or_default().to_owned(), kind);
+    }
+
+    for (i, class) in classes.into_iter().enumerate() {
+        let name = class_name(class.attribute("name"), i);
+        let mut members = vec![];
+
+        for (j, node) in children(class, "node").enumerate() {
+            let field = node_name(node.attribute("name"), j);
+            match project.rcnet_node(node, &format!("{name}_{field}")) {
+                Ok(ty) => members.push(Member {
+                    comment: comment(node.attribute("comment")),
+                    ..member(field, ty)
+                }),
+                Err(reason) => {
+                    project.warnings.push(format!(
+                        "Nodes of `{name}` from `{field}` on are left out, {reason}"
+                    ));
+                    break;
+                }
+            }
+        }
+
+        project.add_class(name, members, comment(class.attribute("comment")));
+    }
+
+    Ok(project.import())
+}
+
+/// Reads classes of a ReClassEx project, every node there knows its size
+/// so unsupported ones are left as padding.
+pub fn import_reclass(text: &str) -> eyre::Result<Imported> {
+    let document = Document::parse(text)?;
+    let root = document.root_element();
+    if !root.has_tag_name("ReClass") {
+        eyre::bail!("It isn't a ReClassEx project");
+    }
+
+    let mut project = Project::default();
+    for (i, class) in children(root, "Class").enumerate() {
+        let name = class_name(class.attribute("Name"), i);
+        let mut members = vec![];
+        let mut offset = 0;
+
+        for (j, node) in children(class, "Node").enumerate() {
+            let field = node_name(node.attribute("Name"), j);
+            let size = number(node, "Size").unwrap_or_default();
+
+            match project.reclass_node(node, size) {
+                Ok(ty) => members.push(Member {
+                    offset: Some(offset),
+                    comment: comment(node.attribute("Comment")),
+                    ..member(field, ty)
+                }),
+                Err(reason) => project.warnings.push(format!(
+                    "`{field}` of `{name}` is left as padding, {reason}"
+                )),
+            }
+            offset = offset.saturating_add(size);
+        }
+
+        project.add_class(name, members, comment(class.attribute("Comment")));
+    }
+
+    Ok(project.import())
+}
+
+#[derive(Default)]
+struct Project {
+    records: Vec<Record>,
+    warnings: Vec<String>,
+    /// Names of ReClass.NET classes by their uuid.
+    classes: HashMap<String, String>,
+    /// Kinds of ReClass.NET enums by their name.
+    enums: HashMap<String, FieldKind>,
+    pointer_size: usize,
+    /// Some pointers were 4 bytes, YClass only has 8 byte ones.
+    narrow_pointers: bool,
+}
+
+impl Project {
+    fn add_class(&mut self, name: String, members: Vec<Member>, comment: Option<String>) {
+        self.records.push(Record {
+            name,
+            defined: true,
+            members,
+            // ReClass doesn't align nodes.
+            pack: Some(1),
+            comment,
+            ..Default::default()
+        });
+    }
+
+    fn import(mut self) -> Imported {
+        if self.narrow_pointers {
+            self.warnings
+                .push("Pointers of 4 bytes are imported as 32-bit hex numbers".into());
+        }
+        Importer::new(self.records, HashMap::new(), |_| None, self.warnings).import()
+    }
+
+    fn pointer(&mut self, target: Type, size: usize) -> Type {
+        if size == 4 {
+            self.narrow_pointers = true;
+            hex(FieldKind::U32)
+        } else {
+            Type::Pointer(Box::new(target))
+        }
+    }
+
+    fn vtable(&mut self, slots: Vec<VTableSlot>, size: usize) -> Type {
+        if size == 4 {
+            return self.pointer(Type::Void, size);
+        }
+        let field = DataField {
+            slots,
+            ..DataField::new("", FieldKind::VTable)
+        };
+        Type::Field(Box::new(field), 8)
+    }
+
+    /// Class a ReClass.NET node refers to by uuid.
+    fn reference(&self, node: Node) -> Option<Type> {
+        node.attribute("reference")
+            .and_then(|uuid| self.classes.get(uuid))
+            .map(|class| Type::Named(class.clone()))
+    }
+
+    /// Type of a ReClass.NET node, `name` is used for unions made out of it.
+    fn rcnet_node(&mut self, node: Node, name: &str) -> Result<Type, String> {
+        let kind = node.attribute("type").unwrap_or_default();
+        let length = number(node, "length").unwrap_or_default();
+        let count = number(node, "count").unwrap_or_default();
+        let inner = children(node, "node").next();
+
+        let ty = match kind {
+            "Hex8Node" => Type::Prim(FieldKind::Unk8),
+            "Hex16Node" => Type::Prim(FieldKind::Unk16),
+            "Hex32Node" => Type::Prim(FieldKind::Unk32),
+            "Hex64Node" => Type::Prim(FieldKind::Unk64),
+            "Int8Node" => Type::Prim(FieldKind::I8),
+            "Int16Node" => Type::Prim(FieldKind::I16),
+            "Int32Node" => Type::Prim(FieldKind::I32),
+            "Int64Node" => Type::Prim(FieldKind::I64),
+            "UInt8Node" => Type::Prim(FieldKind::U8),
+            "UInt16Node" => Type::Prim(FieldKind::U16),
+            "UInt32Node" => Type::Prim(FieldKind::U32),
+            "UInt64Node" => Type::Prim(FieldKind::U64),
+            "NIntNode" if self.pointer_size == 4 => Type::Prim(FieldKind::I32),
+            "NIntNode" => Type::Prim(FieldKind::I64),
+            "NUIntNode" if self.pointer_size == 4 => Type::Prim(FieldKind::U32),
+            "NUIntNode" => Type::Prim(FieldKind::U64),
+            "BoolNode" => Type::Prim(FieldKind::Bool),
+            "FloatNode" => Type::Prim(FieldKind::F32),
+            "DoubleNode" => Type::Prim(FieldKind::F64),
+            "Vector2Node" => floats(2),
+            "Vector3Node" => floats(3),
+            "Vector4Node" => floats(4),
+            "Matrix3x3Node" => floats(9),
+            "Matrix3x4Node" => floats(12),
+            "Matrix4x4Node" => floats(16),
+            "Utf8TextNode" => text(TextEncoding::Utf8, length),
+            "Utf16TextNode" => text(TextEncoding::Utf16, length),
+            "Utf32TextNode" => text(TextEncoding::Utf32, length),
+            "Utf8TextPtrNode" => self.pointer(Type::Char(TextEncoding::Utf8), self.pointer_size),
+            "Utf16TextPtrNode" => self.pointer(Type::Char(TextEncoding::Utf16), self.pointer_size),
+            "Utf32TextPtrNode" => self.pointer(Type::Char(TextEncoding::Utf32), self.pointer_size),
+            "BitFieldNode" => bits(number(node, "bits").unwrap_or_default() / 8)?,
+            "EnumNode" => {
+                let enumeration = node.attribute("reference").unwrap_or_default();
+                let kind = self
+                    .enums
+                    .get(enumeration)
+                    .copied()
+                    .unwrap_or(FieldKind::I32);
+                Type::Enum(enumeration.to_owned(), kind)
+            }
+            "FunctionPtrNode" => self.pointer(Type::Function, self.pointer_size),
+            "VirtualMethodTableNode" => {
+                let slots = children(node, "method")
+                    .enumerate()
+                    .map(|(i, method)| VTableSlot {
+                        name: node_name(method.attribute("name"), i),
+                        ..VTableSlot::new(i)
+                    })
+                    .collect();
+                self.vtable(slots, self.pointer_size)
+            }
+            // Older projects refer to classes directly instead of through an inner node.
+            "ClassPointerNode" => {
+                let target = self.reference(node).unwrap_or(Type::Void);
+                self.pointer(target, self.pointer_size)
+            }
+            "PointerNode" => {
+                let target = match inner {
+                    Some(inner) => self.rcnet_node(inner, name).unwrap_or(Type::Void),
+                    None => self.reference(node).unwrap_or(Type::Void),
+                };
+                self.pointer(target, self.pointer_size)
+            }
+            "ClassInstanceNode" => self
+                .reference(node)
+                .ok_or("the class it embeds is missing")?,
+            "ClassInstanceArrayNode" => Type::Array(
+                Box::new(
+                    self.reference(node)
+                        .ok_or("the class it embeds is missing")?,
+                ),
+                Some(count),
+            ),
+            "ClassPointerArrayNode" => {
+                let target = self.reference(node).unwrap_or(Type::Void);
+                let pointer = self.pointer(target, self.pointer_size);
+                Type::Array(Box::new(pointer), Some(count))
+            }
+            "ArrayNode" => {
+                let element = match inner {
+                    Some(inner) => self.rcnet_node(inner, name)?,
+                    None => self
+                        .reference(node)
+                        .ok_or("the class it embeds is missing")?,
+                };
+                Type::Array(Box::new(element), Some(count))
+            }
+            "UnionNode" => {
+                let mut members = vec![];
+                for (i, alternative) in children(node, "node").enumerate() {
+                    let field = node_name(alternative.attribute("name"), i);
+                    let ty = self.rcnet_node(alternative, &format!("{name}_{field}"))?;
+                    members.push(member(field, ty));
+                }
+                self.records.push(Record {
+                    name: name.to_owned(),
+                    union: true,
+                    inline: true,
+                    defined: true,
+                    members,
+                    pack: Some(1),
+                    ..Default::default()
+                });
+                Type::Named(name.to_owned())
+            }
+            "" => return Err("the node has no type".into()),
+            other => return Err(format!("`{other}` nodes aren't supported")),
+        };
+        Ok(ty)
+    }
+
+    /// Type of a ReClassEx node, their type is a number.
+    fn reclass_node(&mut self, node: Node, size: usize) -> Result<Type, String> {
+        let class = |attribute| Type::Named(ident(node.attribute(attribute).unwrap_or_default()));
+
+        let ty = match number(node, "Type").unwrap_or_default() {
+            1 => class("Instance"),
+            4 => Type::Prim(FieldKind::Unk32),
+            5 => Type::Prim(FieldKind::Unk64),
+            6 => Type::Prim(FieldKind::Unk16),
+            7 => Type::Prim(FieldKind::Unk8),
+            8 => self.pointer(class("Pointer"), size),
+            9 => Type::Prim(FieldKind::I64),
+            10 => Type::Prim(FieldKind::I32),
+            11 => Type::Prim(FieldKind::I16),
+            12 => Type::Prim(FieldKind::I8),
+            13 => Type::Prim(FieldKind::F32),
+            14 => Type::Prim(FieldKind::F64),
+            15 => Type::Prim(FieldKind::U32),
+            16 => Type::Prim(FieldKind::U16),
+            17 => Type::Prim(FieldKind::U8),
+            18 => text(TextEncoding::Utf8, size),
+            19 => text(TextEncoding::Utf16, size / 2),
+            20 => self.pointer(Type::Function, size),
+            22 => floats(2),
+            23 => floats(3),
+            24 => floats(4),
+            25 => floats(16),
+            26 => {
+                let slots = children(node, "Function")
+                    .enumerate()
+                    .map(|(i, function)| VTableSlot {
+                        name: node_name(function.attribute("Name"), i),
+                        ..VTableSlot::new(i)
+                    })
+                    .collect();
+                self.vtable(slots, size)
+            }
+            27 => {
+                let array = children(node, "Array")
+                    .next()
+                    .ok_or("the array has no element class")?;
+                let element = Type::Named(ident(array.attribute("Name").unwrap_or_default()));
+                Type::Array(Box::new(element), number(array, "Total"))
+            }
+            29 => self.pointer(Type::Char(TextEncoding::Utf8), size),
+            30 => self.pointer(Type::Char(TextEncoding::Utf16), size),
+            31 => bits(size)?,
+            32 => Type::Prim(FieldKind::U64),
+            34 => {
+                let count = number(node, "Count").unwrap_or(size / 8);
+                let pointer = self.pointer(class("Pointer"), size / count.max(1));
+                Type::Array(Box::new(pointer), Some(count))
+            }
+            21 => return Err("custom nodes aren't supported".into()),
+            33 => return Err("function nodes aren't supported".into()),
+            other => return Err(format!("nodes of type {other} aren't supported")),
+        };
+        Ok(ty)
+    }
+}
+
+fn children<'a, 'input>(
+    node: Node<'a, 'input>,
+    tag: &'static str,
+) -> impl Iterator<Item = Node<'a, 'input>> {
+    node.children().filter(move |n| n.has_tag_name(tag))
+}
+
+fn member(name: String, ty: Type) -> Member {
+    Member {
+        name,
+        ty,
+        bits: None,
+        align: None,
+        offset: None,
+        comment: None,
+    }
+}
+
+fn number(node: Node, attribute: &str) -> Option<usize> {
+    node.attribute(attribute)?.trim().parse().ok()
+}
+
+fn class_name(name: Option<&str>, index: usize) -> String {
+    match name.map(ident) {
+        Some(name) if !name.is_empty() => name,
+        _ => format!("Class{index}"),
+    }
+}
+
+fn node_name(name: Option<&str>, index: usize) -> String {
+    match name.map(ident) {
+        Some(name) if !name.is_empty() => name,
+        _ => format!("N{index}"),
+    }
+}
+
+fn comment(text: Option<&str>) -> Option<String> {
+    text.map(str::trim)
+        .filter(|t| !t.is_empty())
+        .map(str::to_owned)
+}
+
+fn hex(kind: FieldKind) -> Type {
+    Type::Field(
+        Box::new(DataField {
+            metadata: Some("hex".into()),
+            ..DataField::new("", kind)
+        }),
+        kind.size(),
+    )
+}
+
+fn floats(count: usize) -> Type {
+    Type::Array(Box::new(Type::Prim(FieldKind::F32)), Some(count))
+}
+
+fn text(encoding: TextEncoding, length: usize) -> Type {
+    Type::Array(Box::new(Type::Char(encoding)), Some(length))
+}
+
+/// Bit field of `size` bytes, shown as a hex number.
+fn bits(size: usize) -> Result<Type, String> {
+    let kind = match size {
+        1 => FieldKind::U8,
+        2 => FieldKind::U16,
+        4 => FieldKind::U32,
+        8 => FieldKind::U64,
+        _ => return Err(format!("bit fields of {size} bytes aren't supported")),
+    };
+    Ok(hex(kind))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project::layout::fields;
+    use std::io::Write;
+    use zip::{write::FileOptions, ZipWriter};
+
+    fn rcnet(xml: &str) -> Vec<u8> {
+        let mut zip = ZipWriter::new(Cursor::new(vec![]));
+        zip.start_file("Data.xml", FileOptions::default()).unwrap();
+        zip.write_all(xml.as_bytes()).unwrap();
+        zip.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn x86_pointers_keep_offsets() {
+        let imported = import_rcnet(&rcnet(
+            r#"<?xml version="1.0" encoding="utf-8"?>
+            <reclass version="65537" platform="x86">
+              <classes>
+                <class uuid="A" name="Entity">
+                  <node type="ClassPointerNode" name="next" reference="A"/>
+                  <node type="Int32Node" name="health"/>
+                </class>
+              </classes>
+            </reclass>"#,
+        ))
+        .unwrap();
+
+        assert_eq!(
+            fields(&imported.classes[0]),
+            [("next", 0, FieldKind::U32), ("health", 4, FieldKind::I32)]
+        );
+        assert_eq!(imported.warnings.len(), 1);
+    }
+
+    #[test]
+    fn rcnet_nodes_are_imported() {
+        let imported = import_rcnet(&rcnet(
+            r#"<?xml version="1.0" encoding="utf-8"?>
+            <reclass version="65537" platform="x64">
+              <classes>
+                <class uuid="A" name="Player" comment="Local player">
+                  <node type="VirtualMethodTableNode" name="vtable">
+                    <method name="Update"/>
+                    <method name="Draw"/>
+                  </node>
+                  <node type="ClassInstanceNode" name="position" reference="B"/>
+                  <node type="PointerNode" name="target">
+                    <node type="ClassInstanceNode" reference="A"/>
+                  </node>
+                  <node type="ArrayNode" name="ammo" count="4">
+                    <node type="Int16Node"/>
+                  </node>
+                  <node type="UnionNode" name="value">
+                    <node type="FloatNode" name="speed"/>
+                    <node type="UInt32Node" name="raw"/>
+                  </node>
+                  <node type="Hex32Node"/>
+                  <node type="Vector9Node" name="unknown"/>
+                  <node type="Int32Node" name="lost"/>
+                </class>
+                <class uuid="B" name="Vec2">
+                  <node type="FloatNode" name="x"/>
+                  <node type="FloatNode" name="y"/>
+                </class>
+              </classes>
+            </reclass>"#,
+        ))
+        .unwrap();
+
+        assert_eq!(
+            fields(&imported.classes[0]),
+            [
+                ("vtable", 0, FieldKind::VTable),
+                ("position_x", 8, FieldKind::F32),
+                ("position_y", 12, FieldKind::F32),
+                ("target", 16, FieldKind::Ptr),
+                ("ammo_0", 24, FieldKind::I16),
+                ("ammo_1", 26, FieldKind::I16),
+                ("ammo_2", 28, FieldKind::I16),
+                ("ammo_3", 30, FieldKind::I16),
+                ("value", 32, FieldKind::Union(4)),
+                ("N5", 36, FieldKind::Unk32),
+            ]
+        );
+
+        let player = &imported.classes[0];
+        assert_eq!(player.comment.as_deref(), Some("Local player"));
+        let slots = player.fields[0]
+            .slots
+            .iter()
+            .map(|s| s.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(slots, ["Update", "Draw"]);
+        assert_eq!(player.fields[3].metadata.as_deref(), Some("Player"));
+        let alternatives = player.fields[8]
+            .alternatives
+            .iter()
+            .map(|a| (a.name.as_str(), a.kind))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            alternatives,
+            [("speed", FieldKind::F32), ("raw", FieldKind::U32)]
+        );
+
+        assert_eq!(
+            imported.warnings,
+            ["Nodes of `Player` from `unknown` on are left out, `Vector9Node` nodes aren't supported"]
+        );
+    }
+
+    #[test]
+    fn reclass_nodes_keep_their_offsets() {
+        let imported = import_reclass(
+            r#"<?xml version="1.0" encoding="utf-8"?>
+            <ReClass>
+              <Class Name="Player" Comment="Local player">
+                <Node Name="target" Type="8" Size="8" Pointer="Player"/>
+                <Node Name="hook" Type="33" Size="8"/>
+                <Node Name="health" Type="10" Size="4"/>
+                <Node Name="name" Type="18" Size="16"/>
+                <Node Name="speed" Type="13" Size="4"/>
+              </Class>
+            </ReClass>"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            fields(&imported.classes[0]),
+            [
+                ("target", 0, FieldKind::Ptr),
+                ("health", 16, FieldKind::I32),
+                ("name", 20, FieldKind::Text(TextEncoding::Utf8, 16)),
+                ("speed", 36, FieldKind::F32),
+            ]
+        );
+        assert_eq!(
+            imported.warnings,
+            ["`hook` of `Player` is left as padding, function nodes aren't supported"]
+        );
+    }
+}
@@ -0,0 +1,1155 @@
+/// This module contains import of `#[repr(C)]` structs and unions from Rust source,
+/// including what the Rust generator emits, so generated bindings can be brought back.
+use super::{
+    layout::{scalar_kind, Importer, Member, Record, Type},
+    DataField, Imported,
+};
+use crate::field::{FieldKind, RelativeTo, TextEncoding, TimeUnit, VTableSlot};
+use std::collections::{HashMap, HashSet};
+
+/// Reads every `#[repr(C)]` struct and union of the source.
+pub fn import(text: &str) -> Imported {
+    let mut parser = Parser {
+        tokens: lex(text),
+        ..Default::default()
+    };
+    parser.parse_items();
+    parser.finish()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Lifetime,
+    Number(i64),
+    Punct(char),
+    /// `::`
+    Scope,
+    Str,
+    /// `//` comment, `trailing` if it follows code on the same line.
+    Comment {
+        text: String,
+        trailing: bool,
+    },
+    /// `///` comment.
+    Doc(String),
+}
+
+fn lex(text: &str) -> Vec<Token> {
+    let chars = text.chars().collect::<Vec<_>>();
+    let mut tokens = vec![];
+    let mut code_on_line = false;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '\n' => {
+                code_on_line = false;
+                i += 1;
+                continue;
+            }
+            _ if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            '/' if chars.get(i + 1) == Some(&'/') => {
+                let start = i;
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+                let line = chars[start..i].iter().collect::<String>();
+                if let Some(doc) = line.strip_prefix("///") {
+                    tokens.push(Token::Doc(doc.strip_prefix(' ').unwrap_or(doc).to_owned()));
+                } else if !line.starts_with("//!") {
+                    tokens.push(Token::Comment {
+                        text: line[2..].trim().to_owned(),
+                        trailing: code_on_line,
+                    });
+                }
+                continue;
+            }
+            '/' if chars.get(i + 1) == Some(&'*') => {
+                let mut depth = 0;
+                while i < chars.len() {
+                    if chars[i] == '/' && chars.get(i + 1) == Some(&'*') {
+                        depth += 1;
+                        i += 2;
+                    } else if chars[i] == '*' && chars.get(i + 1) == Some(&'/') {
+                        depth -= 1;
+                        i += 2;
+                        if depth == 0 {
+                            break;
+                        }
+                    } else {
+                        i += 1;
+                    }
+                }
+                continue;
+            }
+            '"' => {
+                i += 1;
+                while i < chars.len() && chars[i] != '"' {
+                    i += if chars[i] == '\\' { 2 } else { 1 };
+                }
+                i += 1;
+                tokens.push(Token::Str);
+            }
+            'r' if chars.get(i + 1) == Some(&'#') || chars.get(i + 1) == Some(&'"') => {
+                // Raw string or raw identifier.
+                let hashes = chars[i + 1..].iter().take_while(|&&c| c == '#').count();
+                if chars.get(i + 1 + hashes) == Some(&'"') {
+                    i += 2 + hashes;
+                    let end = format!("\"{}", "#".repeat(hashes));
+                    while i < chars.len()
+                        && !chars[i..].iter().collect::<String>().starts_with(&end)
+                    {
+                        i += 1;
+                    }
+                    i += end.len();
+                    tokens.push(Token::Str);
+                } else {
+                    i += 2;
+                    let start = i;
+                    while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                        i += 1;
+                    }
+                    tokens.push(Token::Ident(chars[start..i].iter().collect()));
+                }
+            }
+            '\'' => {
+                // Lifetime or a character literal.
+                if chars.get(i + 2) == Some(&'\'') || chars.get(i + 1) == Some(&'\\') {
+                    i += 1;
+                    let value = if chars[i] == '\\' {
+                        chars[i + 1]
+                    } else {
+                        chars[i]
+                    } as i64;
+                    while i < chars.len() && chars[i] != '\'' {
+                        i += if chars[i] == '\\' { 2 } else { 1 };
+                    }
+                    i += 1;
+                    tokens.push(Token::Number(value));
+                } else {
+                    i += 1;
+                    while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                        i += 1;
+                    }
+                    tokens.push(Token::Lifetime);
+                }
+            }
+            ':' if chars.get(i + 1) == Some(&':') => {
+                tokens.push(Token::Scope);
+                i += 2;
+            }
+            _ if c.is_ascii_digit() => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                let literal = chars[start..i].iter().collect::<String>();
+                tokens.push(Token::Number(parse_number(&literal).unwrap_or_default()));
+            }
+            _ if c.is_alphabetic() || c == '_' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                tokens.push(Token::Ident(chars[start..i].iter().collect()));
+            }
+            _ => {
+                tokens.push(Token::Punct(c));
+                i += 1;
+            }
+        }
+        code_on_line = true;
+    }
+
+    tokens
+}
+
+/// Parses an integer literal with an optional type suffix, i.e. `0x10usize`.
+fn parse_number(literal: &str) -> Option<i64> {
+    let literal = literal.replace('_', "");
+    let (digits, radix) = match literal.get(..2) {
+        Some("0x") => (&literal[2..], 16),
+        Some("0o") => (&literal[2..], 8),
+        Some("0b") => (&literal[2..], 2),
+        _ => (literal.as_str(), 10),
+    };
+
+    let end = digits
+        .find(|c: char| !c.is_digit(radix))
+        .unwrap_or(digits.len());
+    u64::from_str_radix(&digits[..end], radix)
+        .ok()
+        .map(|n| n as i64)
+}
+
+/// `#[repr(...)]` of an item.
+#[derive(Default)]
+struct Repr {
+    c: bool,
+    transparent: bool,
+    pack: Option<usize>,
+    align: Option<usize>,
+    /// Integer type of a field-less enum.
+    int: Option<FieldKind>,
+}
+
+/// Member as it's written, comments are interpreted once every item is known.
+struct RawMember {
+    name: String,
+    ty: Type,
+    /// Type is a reference rather than `Option<&T>` or a raw pointer.
+    reference: bool,
+    doc: Option<String>,
+    leading: Option<String>,
+    trailing: Option<String>,
+}
+
+#[derive(Default)]
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    constants: HashMap<String, i64>,
+    types: HashMap<String, Type>,
+    records: Vec<(Record, Vec<RawMember>)>,
+    warnings: Vec<String>,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn is_punct(&self, c: char) -> bool {
+        self.peek() == Some(&Token::Punct(c))
+    }
+
+    fn eat_punct(&mut self, c: char) -> bool {
+        let eaten = self.is_punct(c);
+        self.pos += eaten as usize;
+        eaten
+    }
+
+    fn eat_ident(&mut self, ident: &str) -> bool {
+        let eaten = matches!(self.peek(), Some(Token::Ident(i)) if i == ident);
+        self.pos += eaten as usize;
+        eaten
+    }
+
+    fn ident(&mut self) -> Option<String> {
+        match self.peek() {
+            Some(Token::Ident(ident)) => {
+                let ident = ident.clone();
+                self.pos += 1;
+                Some(ident)
+            }
+            _ => None,
+        }
+    }
+
+    /// Skips from the opening bracket to after the matching closing one.
+    fn skip_balanced(&mut self, open: char, close: char) {
+        let mut depth = 0;
+        while let Some(token) = self.peek().cloned() {
+            self.pos += 1;
+            if token == Token::Punct(open) {
+                depth += 1;
+            } else if token == Token::Punct(close) {
+                depth -= 1;
+                if depth == 0 {
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Skips an item up to its `;` or the end of its body.
+    fn skip_item(&mut self) {
+        while let Some(token) = self.peek() {
+            match token {
+                Token::Punct(';') => {
+                    self.pos += 1;
+                    return;
+                }
+                Token::Punct('{') => {
+                    self.skip_balanced('{', '}');
+                    return;
+                }
+                Token::Punct('}') => return,
+                Token::Punct('(') => self.skip_balanced('(', ')'),
+                Token::Punct('[') => self.skip_balanced('[', ']'),
+                _ => self.pos += 1,
+            }
+        }
+    }
+
+    fn skip_visibility(&mut self) {
+        if self.eat_ident("pub") && self.is_punct('(') {
+            self.skip_balanced('(', ')');
+        }
+    }
+
+    /// Reads `#[...]` attributes, only `repr` is kept.
+    fn parse_attributes(&mut self, repr: &mut Repr) {
+        while self.is_punct('#') {
+            self.pos += 1;
+            let inner = self.eat_punct('!');
+            let start = self.pos;
+            self.skip_balanced('[', ']');
+            if inner {
+                continue;
+            }
+
+            let tokens = &self.tokens[start..self.pos];
+            if tokens.get(1) != Some(&Token::Ident("repr".into())) {
+                continue;
+            }
+
+            for (i, token) in tokens.iter().enumerate() {
+                let Token::Ident(ident) = token else {
+                    continue;
+                };
+                let value = match tokens.get(i + 2) {
+                    Some(Token::Number(n)) if tokens.get(i + 1) == Some(&Token::Punct('(')) => {
+                        Some(*n as usize)
+                    }
+                    _ => None,
+                };
+
+                match ident.as_str() {
+                    "C" => repr.c = true,
+                    "transparent" => repr.transparent = true,
+                    "packed" => repr.pack = Some(value.unwrap_or(1)),
+                    "align" => repr.align = value,
+                    other => {
+                        if let Some(Type::Prim(kind)) = builtin(other) {
+                            repr.int = Some(kind);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Items of the file or an inline module, returns after the closing `}`.
+    fn parse_items(&mut self) {
+        let mut repr = Repr::default();
+        let mut docs = vec![];
+
+        while let Some(token) = self.peek().cloned() {
+            match token {
+                Token::Doc(doc) => {
+                    docs.push(doc);
+                    self.pos += 1;
+                    continue;
+                }
+                Token::Comment { .. } => {
+                    self.pos += 1;
+                    continue;
+                }
+                Token::Punct('#') => {
+                    self.parse_attributes(&mut repr);
+                    continue;
+                }
+                Token::Punct('}') => {
+                    self.pos += 1;
+                    return;
+                }
+                _ => {}
+            }
+
+            self.skip_visibility();
+            let comment = (!docs.is_empty()).then(|| docs.join("\n"));
+            match self.peek().cloned() {
+                Some(Token::Ident(ident)) if ident == "struct" => {
+                    self.pos += 1;
+                    self.parse_record(false, &repr, comment);
+                }
+                Some(Token::Ident(ident))
+                    if ident == "union"
+                        && matches!(self.tokens.get(self.pos + 1), Some(Token::Ident(_))) =>
+                {
+                    self.pos += 1;
+                    self.parse_record(true, &repr, comment);
+                }
+                Some(Token::Ident(ident)) if ident == "enum" => {
+                    self.pos += 1;
+                    self.parse_enum(&repr);
+                }
+                Some(Token::Ident(ident)) if ident == "type" => {
+                    self.pos += 1;
+                    self.parse_alias();
+                }
+                Some(Token::Ident(ident))
+                    if ident == "const"
+                        && matches!(self.tokens.get(self.pos + 1), Some(Token::Ident(_))) =>
+                {
+                    self.pos += 1;
+                    self.parse_const();
+                }
+                Some(Token::Ident(ident)) if ident == "mod" => {
+                    self.pos += 1;
+                    self.ident();
+                    if self.eat_punct('{') {
+                        self.parse_items();
+                    } else {
+                        self.skip_item();
+                    }
+                }
+                _ => self.skip_item(),
+            }
+
+            repr = Repr::default();
+            docs.clear();
+        }
+    }
+
+    fn skip_generics(&mut self) {
+        if self.is_punct('<') {
+            self.skip_balanced('<', '>');
+        }
+        if self.eat_ident("where") {
+            while !matches!(self.peek(), None | Some(Token::Punct('{' | ';'))) {
+                self.pos += 1;
+            }
+        }
+    }
+
+    fn parse_record(&mut self, union: bool, repr: &Repr, comment: Option<String>) {
+        let Some(name) = self.ident() else {
+            self.skip_item();
+            return;
+        };
+        self.skip_generics();
+
+        if !repr.c && !repr.transparent {
+            self.warnings.push(format!(
+                "`{name}` isn't `#[repr(C)]`, its layout is unknown and it's left out"
+            ));
+            self.skip_item();
+            return;
+        }
+
+        let mut members = vec![];
+        if self.eat_punct('{') {
+            self.parse_members(&mut members);
+        } else if self.eat_punct('(') {
+            let mut i = 0;
+            while !matches!(self.peek(), None | Some(Token::Punct(')'))) {
+                self.skip_visibility();
+                let (ty, reference) = self.parse_type();
+                members.push(RawMember {
+                    name: format!("_{i}"),
+                    ty,
+                    reference,
+                    doc: None,
+                    leading: None,
+                    trailing: None,
+                });
+                i += 1;
+                if !self.eat_punct(',') {
+                    break;
+                }
+            }
+            self.eat_punct(')');
+            self.skip_generics();
+            self.eat_punct(';');
+        } else {
+            self.eat_punct(';');
+        }
+
+        let record = Record {
+            name,
+            union,
+            defined: true,
+            pack: repr.pack,
+            align: repr.align,
+            comment,
+            ..Default::default()
+        };
+        self.records.push((record, members));
+    }
+
+    /// Reads named fields up to and including the closing `}`.
+    fn parse_members(&mut self, members: &mut Vec<RawMember>) {
+        let mut docs = vec![];
+        let mut leading = None;
+
+        while let Some(token) = self.peek().cloned() {
+            match token {
+                Token::Punct('}') => {
+                    self.pos += 1;
+                    return;
+                }
+                Token::Doc(doc) => {
+                    docs.push(doc);
+                    self.pos += 1;
+                }
+                Token::Comment { text, trailing } => {
+                    self.pos += 1;
+                    match members.last_mut() {
+                        Some(member) if trailing => member.trailing = Some(text),
+                        _ => leading = Some(text),
+                    }
+                }
+                Token::Punct('#') => self.parse_attributes(&mut Repr::default()),
+                Token::Ident(_) => {
+                    self.skip_visibility();
+                    let Some(name) = self.ident() else {
+                        continue;
+                    };
+                    if !self.eat_punct(':') {
+                        continue;
+                    }
+
+                    let (ty, reference) = self.parse_type();
+                    members.push(RawMember {
+                        name,
+                        ty,
+                        reference,
+                        doc: (!docs.is_empty()).then(|| docs.join("\n")),
+                        leading: leading.take(),
+                        trailing: None,
+                    });
+                    docs.clear();
+                    self.eat_punct(',');
+                }
+                _ => self.pos += 1,
+            }
+        }
+    }
+
+    /// Returns the type and whether it's a reference.
+    fn parse_type(&mut self) -> (Type, bool) {
+        match self.peek().cloned() {
+            Some(Token::Punct('*')) => {
+                self.pos += 1;
+                if !self.eat_ident("const") {
+                    self.eat_ident("mut");
+                }
+                (Type::Pointer(Box::new(self.parse_pointee())), false)
+            }
+            Some(Token::Punct('&')) => {
+                self.pos += 1;
+                if self.peek() == Some(&Token::Lifetime) {
+                    self.pos += 1;
+                }
+                self.eat_ident("mut");
+                (Type::Pointer(Box::new(self.parse_pointee())), true)
+            }
+            Some(Token::Punct('[')) => {
+                self.pos += 1;
+                let element = match self.parse_type().0 {
+                    Type::Named(name) if name == "u8" => Type::Char(TextEncoding::Utf8),
+                    element => element,
+                };
+                let len = if self.eat_punct(';') {
+                    self.expression().map(|n| n as usize)
+                } else {
+                    None
+                };
+                while !matches!(self.peek(), None | Some(Token::Punct(']'))) {
+                    self.pos += 1;
+                }
+                self.eat_punct(']');
+                (Type::Array(Box::new(element), len), false)
+            }
+            Some(Token::Punct('(')) => {
+                self.skip_balanced('(', ')');
+                (Type::Void, false)
+            }
+            Some(Token::Ident(ident)) if matches!(ident.as_str(), "fn" | "extern" | "unsafe") => {
+                while !matches!(self.peek(), None | Some(Token::Punct('('))) {
+                    self.pos += 1;
+                }
+                self.skip_balanced('(', ')');
+                if self.eat_punct('-') && self.eat_punct('>') {
+                    self.parse_type();
+                }
+                (Type::Pointer(Box::new(Type::Function)), false)
+            }
+            _ => (self.parse_path(), false),
+        }
+    }
+
+    /// Type pointed to, see [`pointee`].
+    fn parse_pointee(&mut self) -> Type {
+        pointee(self.parse_type().0)
+    }
+
+    /// Path with optional generic arguments, i.e. `Option<&'static T>` or `Vec<T>`.
+    fn parse_path(&mut self) -> Type {
+        let mut name = String::new();
+        loop {
+            self.pos += self.peek().is_some_and(|t| *t == Token::Scope) as usize;
+            let Some(segment) = self.ident() else {
+                break;
+            };
+            name = segment;
+            if self.peek() != Some(&Token::Scope) {
+                break;
+            }
+        }
+
+        if !self.eat_punct('<') {
+            return Type::Named(name);
+        }
+
+        let (argument, _) = self.parse_type();
+        while !matches!(self.peek(), None | Some(Token::Punct('>'))) {
+            if self.is_punct('<') {
+                self.skip_balanced('<', '>');
+            } else {
+                self.pos += 1;
+            }
+        }
+        self.eat_punct('>');
+
+        match name.as_str() {
+            "NonNull" | "Box" => Type::Pointer(Box::new(pointee(argument))),
+            // Only types with a niche keep their size, i.e. `Option<&T>` and `Option<fn()>`.
+            "Option" => {
+                if !matches!(&argument, Type::Pointer(_))
+                    && !matches!(&argument, Type::Named(n) if n == "bool" || n == "char")
+                {
+                    let inner = match &argument {
+                        Type::Named(name) => name.as_str(),
+                        _ => "_",
+                    };
+                    self.warnings.push(format!(
+                        "`Option<{inner}>` is bigger than `{inner}`, offsets after it may be off"
+                    ));
+                }
+                argument
+            }
+            "ManuallyDrop" | "Cell" | "UnsafeCell" => argument,
+            "Vec" => Type::Container(FieldKind::RustVec, Box::new(argument)),
+            "LinkedList" => Type::Container(FieldKind::RustList, Box::new(argument)),
+            "PhantomData" => Type::Array(Box::new(Type::Prim(FieldKind::U8)), Some(0)),
+            _ => Type::Named(name),
+        }
+    }
+
+    fn parse_enum(&mut self, repr: &Repr) {
+        let Some(name) = self.ident() else {
+            self.skip_item();
+            return;
+        };
+        self.skip_generics();
+        if !self.eat_punct('{') {
+            self.skip_item();
+            return;
+        }
+
+        let (mut value, mut max, mut data) = (0, 0, false);
+        while let Some(token) = self.peek().cloned() {
+            match token {
+                Token::Punct('}') => {
+                    self.pos += 1;
+                    break;
+                }
+                Token::Punct('#') => self.parse_attributes(&mut Repr::default()),
+                Token::Ident(variant) => {
+                    self.pos += 1;
+                    if self.is_punct('(') || self.is_punct('{') {
+                        data = true;
+                        let (open, close) = if self.is_punct('(') {
+                            ('(', ')')
+                        } else {
+                            ('{', '}')
+                        };
+                        self.skip_balanced(open, close);
+                    }
+                    if self.eat_punct('=') {
+                        value = self.expression().unwrap_or(value);
+                    }
+                    self.constants.insert(format!("{name}::{variant}"), value);
+                    max = max.max(value);
+                    value += 1;
+                }
+                _ => self.pos += 1,
+            }
+        }
+
+        if data && repr.int.is_none() {
+            self.warnings.push(format!(
+                "Enum `{name}` has data, fields of its type are left out"
+            ));
+            return;
+        }
+
+        // Without a `repr` the smallest type that fits is used.
+        let kind = repr.int.unwrap_or(if repr.c {
+            FieldKind::I32
+        } else if max <= u8::MAX as i64 {
+            FieldKind::U8
+        } else if max <= u16::MAX as i64 {
+            FieldKind::U16
+        } else {
+            FieldKind::U32
+        });
+        self.types.insert(name.clone(), Type::Enum(name, kind));
+    }
+
+    fn parse_alias(&mut self) {
+        if let Some(name) = self.ident() {
+            self.skip_generics();
+            if self.eat_punct('=') {
+                let (ty, _) = self.parse_type();
+                self.types.insert(name, ty);
+            }
+        }
+        self.skip_item();
+    }
+
+    fn parse_const(&mut self) {
+        if let Some(name) = self.ident() {
+            if self.eat_punct(':') {
+                self.parse_type();
+            }
+            if self.eat_punct('=') {
+                if let Some(value) = self.expression() {
+                    self.constants.insert(name, value);
+                }
+            }
+        }
+        self.skip_item();
+    }
+
+    /// Evaluates an integer constant expression, `None` if it has unknown names.
+    fn expression(&mut self) -> Option<i64> {
+        let mut value = self.term()?;
+        loop {
+            if self.eat_punct('+') {
+                value = value.wrapping_add(self.term()?);
+            } else if self.eat_punct('-') {
+                value = value.wrapping_sub(self.term()?);
+            } else {
+                return Some(value);
+            }
+        }
+    }
+
+    fn term(&mut self) -> Option<i64> {
+        let mut value = self.factor()?;
+        loop {
+            if self.eat_punct('*') {
+                value = value.wrapping_mul(self.factor()?);
+            } else if self.eat_punct('/') {
+                value = value.checked_div(self.factor()?)?;
+            } else {
+                return Some(value);
+            }
+        }
+    }
+
+    fn factor(&mut self) -> Option<i64> {
+        match self.peek().cloned()? {
+            Token::Number(n) => {
+                self.pos += 1;
+                Some(n)
+            }
+            Token::Punct('(' | '{') => {
+                let close = if self.is_punct('(') { ')' } else { '}' };
+                self.pos += 1;
+                let value = self.expression();
+                self.eat_punct(close);
+                value
+            }
+            Token::Ident(_) => {
+                let mut path = vec![];
+                while let Some(segment) = self.ident() {
+                    path.push(segment);
+                    if self.peek() != Some(&Token::Scope) {
+                        break;
+                    }
+                    self.pos += 1;
+                }
+                // `Enum::Variant` is stored as such, other paths by their last segment.
+                let tail = path[path.len().saturating_sub(2)..].join("::");
+                self.constants
+                    .get(&tail)
+                    .or_else(|| self.constants.get(path.last()?))
+                    .copied()
+            }
+            _ => None,
+        }
+    }
+
+    /// Interprets comments the Rust generator leaves, finds bases and virtual tables.
+    fn finish(mut self) -> Imported {
+        let fields = |members: &[RawMember]| {
+            !members.is_empty()
+                && members.iter().all(|m| {
+                    matches!(&m.ty, Type::Named(n) if n == "usize")
+                        || matches!(&m.ty, Type::Pointer(f) if matches!(**f, Type::Function))
+                })
+        };
+        let vtables = self
+            .records
+            .iter()
+            .filter(|(r, members)| !r.union && fields(members))
+            .map(|(r, members)| {
+                let slots = members
+                    .iter()
+                    .map(|m| VTableSlot {
+                        name: m.name.clone(),
+                        signature: m.trailing.clone().unwrap_or_else(|| "void()".into()),
+                    })
+                    .collect::<Vec<_>>();
+                (r.name.clone(), slots)
+            })
+            .collect::<HashMap<_, _>>();
+
+        let mut used_vtables = HashSet::new();
+        let mut by_value = HashSet::new();
+        let records = std::mem::take(&mut self.records);
+        let mut out = vec![];
+
+        for (mut record, members) in records {
+            for (i, member) in members.into_iter().enumerate() {
+                // The Rust generator emits the base class as the first field.
+                if let (0, "base", Type::Named(base)) = (i, member.name.as_str(), &member.ty) {
+                    record.base = Some(base.clone());
+                    continue;
+                }
+
+                let ty = match (&member.ty, member.reference) {
+                    (Type::Pointer(pointee), true) => match &**pointee {
+                        Type::Named(table) if vtables.contains_key(table) => {
+                            used_vtables.insert(table.clone());
+                            Type::Field(
+                                Box::new(DataField {
+                                    slots: vtables[table].clone(),
+                                    ..DataField::new("", FieldKind::VTable)
+                                }),
+                                8,
+                            )
+                        }
+                        _ => self.refine(&member),
+                    },
+                    _ => self.refine(&member),
+                };
+
+                if let Type::Named(name) = &ty {
+                    by_value.insert(name.clone());
+                }
+                record.members.push(Member {
+                    name: member.name,
+                    ty,
+                    bits: None,
+                    align: None,
+                    offset: None,
+                    comment: member.doc,
+                });
+            }
+            out.push(record);
+        }
+
+        out.retain(|r| !used_vtables.contains(&r.name));
+        // Unions the generator emits for union fields aren't classes of their own.
+        for record in out.iter_mut() {
+            record.inline = record.union && by_value.contains(&record.name);
+        }
+
+        Importer::new(out, self.types, builtin, self.warnings).import()
+    }
+
+    /// Type of the member with the meaning of its comments, i.e. `[u8; 16], // GUID`.
+    fn refine(&self, member: &RawMember) -> Type {
+        let ty = member.ty.clone();
+        let field = |kind, metadata: Option<String>| {
+            Type::Field(
+                Box::new(DataField {
+                    metadata,
+                    ..DataField::new("", kind)
+                }),
+                natural_align(&ty),
+            )
+        };
+
+        // Chains are preceded by `// name: [[self+0x10]+0x28]+0x4 -> i32`.
+        if let Some((expr, value)) = member
+            .leading
+            .as_deref()
+            .and_then(|c| c.strip_prefix(&format!("{}: ", member.name)))
+            .and_then(|c| c.split_once(" -> "))
+        {
+            let offsets = expr
+                .split_once(']')
+                .map(|(_, rest)| {
+                    rest.split(['+', ']'])
+                        .filter_map(|n| parse_number(n.trim()).map(|n| n as usize))
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default();
+            let value = scalar_kind(&parse_type_str(value)).unwrap_or(FieldKind::I32);
+
+            return Type::Field(
+                Box::new(DataField {
+                    offsets,
+                    element: Some(value),
+                    ..DataField::new("", FieldKind::Chain)
+                }),
+                8,
+            );
+        }
+
+        let Some(note) = member.trailing.as_deref() else {
+            return ty;
+        };
+
+        match note {
+            "GUID" => return field(FieldKind::Guid, None),
+            "UUID" => return field(FieldKind::Uuid, None),
+            "IPv4" => return field(FieldKind::Ipv4, None),
+            "IPv6" => return field(FieldKind::Ipv6, None),
+            "RGBA" => return field(FieldKind::Rgba, None),
+            "timespec" => return field(FieldKind::Timespec, None),
+            "timeval" => return field(FieldKind::Timeval, None),
+            "std::string" => return field(FieldKind::StdString, None),
+            "port, big endian" => return field(FieldKind::Port, None),
+            "Intrusive list" => {
+                if let Type::Pointer(element) = ty {
+                    return Type::Container(FieldKind::IntrusiveList, element);
+                }
+            }
+            _ => {}
+        }
+
+        if let Some(label) = note.strip_prefix("Unix time, ") {
+            if let Some(unit) = [TimeUnit::Seconds, TimeUnit::Millis, TimeUnit::Nanos]
+                .into_iter()
+                .find(|u| u.label() == label)
+            {
+                return field(FieldKind::UnixTime(unit), None);
+            }
+        }
+
+        for (prefix, kind) in [
+            ("std::vector<", FieldKind::StdVector),
+            ("std::list<", FieldKind::StdList),
+        ] {
+            if let Some(element) = note.strip_prefix(prefix).and_then(|e| e.strip_suffix('>')) {
+                return Type::Container(kind, Box::new(parse_type_str(element)));
+            }
+        }
+
+        if let Some((class, to)) = note
+            .strip_prefix("*const ")
+            .and_then(|n| n.split_once(" relative to "))
+        {
+            if let Some(to) = [RelativeTo::Field, RelativeTo::End]
+                .into_iter()
+                .find(|r| r.label() == to)
+            {
+                let kind = match &ty {
+                    Type::Named(n) if n == "i32" => FieldKind::RelPtr32(to),
+                    _ => FieldKind::RelPtr64(to),
+                };
+                return field(kind, (class != "()").then(|| class.to_owned()));
+            }
+        }
+
+        // Notes of numeric fields, i.e. `f16, big endian` or `Q16.16`.
+        let Some(Type::Prim(mut kind)) = builtin_named(&ty) else {
+            return ty;
+        };
+        let (mut metadata, mut known) = (None, false);
+        for note in note.split(", ") {
+            known = true;
+            match note {
+                "f16" => kind = FieldKind::F16,
+                "bf16" => kind = FieldKind::BF16,
+                "big endian" => metadata = Some("be".to_owned()),
+                _ => {
+                    known = false;
+                    if let Some(frac) = note
+                        .strip_prefix('Q')
+                        .and_then(|q| q.split_once('.'))
+                        .and_then(|(_, frac)| frac.parse().ok())
+                    {
+                        known = true;
+                        kind = if kind.size() == 4 {
+                            FieldKind::Fixed32(frac)
+                        } else {
+                            FieldKind::Fixed64(frac)
+                        };
+                    }
+                }
+            }
+        }
+
+        // Other comments are left to the reader.
+        if known {
+            field(kind, metadata)
+        } else {
+            ty
+        }
+    }
+}
+
+/// Parses a type written in a comment.
+fn parse_type_str(text: &str) -> Type {
+    let mut parser = Parser {
+        tokens: lex(text),
+        ..Default::default()
+    };
+    let (ty, _) = parser.parse_type();
+    builtin_named(&ty).unwrap_or(ty)
+}
+
+/// Strings are pointers to `u8` and `u16`.
+fn pointee(ty: Type) -> Type {
+    match ty {
+        Type::Named(name) if matches!(name.as_str(), "u8" | "i8" | "c_char") => {
+            Type::Char(TextEncoding::Utf8)
+        }
+        Type::Named(name) if name == "u16" => Type::Char(TextEncoding::Utf16),
+        ty => ty,
+    }
+}
+
+fn builtin_named(ty: &Type) -> Option<Type> {
+    match ty {
+        Type::Named(name) => builtin(name),
+        _ => None,
+    }
+}
+
+fn natural_align(ty: &Type) -> usize {
+    match builtin_named(ty).as_ref().unwrap_or(ty) {
+        Type::Prim(kind) => kind.size().min(8),
+        Type::Array(element, _) => natural_align(element),
+        Type::Char(encoding) => encoding.unit_size(),
+        _ => 8,
+    }
+}
+
+/// Primitive types and `core::ffi` types.
+fn builtin(name: &str) -> Option<Type> {
+    use FieldKind::*;
+
+    let kind = match name {
+        "i8" | "c_schar" => I8,
+        "u8" | "c_uchar" => U8,
+        "i16" | "c_short" => I16,
+        "u16" | "c_ushort" => U16,
+        "i32" | "c_int" => I32,
+        "u32" | "c_uint" => U32,
+        "i64" | "isize" | "c_longlong" => I64,
+        "u64" | "usize" | "c_ulonglong" => U64,
+        "c_long" if cfg!(windows) => I32,
+        "c_ulong" if cfg!(windows) => U32,
+        "c_long" => I64,
+        "c_ulong" => U64,
+        "i128" => I128,
+        "u128" => U128,
+        "f32" | "c_float" => F32,
+        "f64" | "c_double" => F64,
+        "bool" => Bool,
+        "String" => RustString,
+        "char" => return Some(Type::Char(TextEncoding::Utf32)),
+        "c_char" => return Some(Type::Char(TextEncoding::Utf8)),
+        "c_void" => return Some(Type::Void),
+        _ => return None,
+    };
+    Some(Type::Prim(kind))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        field::CodegenData,
+        generator::{Generator, RustGenerator},
+        project::{layout::fields, ProjectData},
+    };
+
+    #[test]
+    fn owning_pointers_are_pointer_fields() {
+        let imported = import(
+            "#[repr(C)]
+            pub struct Node {
+                pub next: NonNull<Node>,
+                pub data: Box<Data>,
+                pub prev: Option<NonNull<Node>>,
+                pub parent: Option<&'static Node>,
+                pub value: u32,
+            }
+            #[repr(C)]
+            pub struct Data {
+                pub values: [u64; 4],
+            }",
+        );
+
+        assert_eq!(
+            fields(&imported.classes[0]),
+            [
+                ("next", 0, FieldKind::Ptr),
+                ("data", 8, FieldKind::Ptr),
+                ("prev", 16, FieldKind::Ptr),
+                ("parent", 24, FieldKind::Ptr),
+                ("value", 32, FieldKind::U32),
+                ("", 36, FieldKind::Unk32),
+            ]
+        );
+        assert_eq!(
+            imported.classes[0].fields[1].metadata.as_deref(),
+            Some("Data")
+        );
+        assert!(imported.warnings.is_empty());
+    }
+
+    #[test]
+    fn generated_code_is_imported_back() {
+        let project = r#"(version: 2, classes: [
+            (id: Some(1), name: "Player", fields: [
+                (name: "target", offset: 0, kind: Ptr, metadata: Some("Player")),
+                (name: "health", offset: 8, kind: I32, metadata: None),
+                (name: "speed", offset: 12, kind: F32, metadata: None),
+                (name: "name", offset: 16, kind: Text(Utf8, 16), metadata: None),
+                (name: "alive", offset: 32, kind: Bool, metadata: None),
+                (name: "team", offset: 33, kind: U8, metadata: None),
+                (name: "port", offset: 34, kind: Port, metadata: None),
+                (name: "color", offset: 36, kind: Rgba, metadata: None),
+                (name: "score", offset: 40, kind: U64, metadata: None),
+            ]),
+        ])"#;
+        let (classes, _) = ProjectData::from_str(project).unwrap().load(None).unwrap();
+
+        let mut generator = RustGenerator::default();
+        let data = CodegenData {
+            classes: classes.classes(),
+        };
+        for class in classes.classes() {
+            data.codegen_class(class, &mut generator);
+        }
+        let imported = import(&generator.finilize());
+        assert!(imported.warnings.is_empty(), "{:?}", imported.warnings);
+
+        let stored = ProjectData::store(classes.classes(), false);
+        let expected = fields(&stored.classes[0]);
+        assert_eq!(expected.len(), 9);
+        assert_eq!(fields(&imported.classes[0]), expected);
+        assert_eq!(
+            imported.classes[0].fields[0].metadata.as_deref(),
+            Some("Player")
+        );
+    }
+
+    #[test]
+    fn option_of_a_type_without_niche_is_reported() {
+        let imported = import(
+            "#[repr(C)]
+            pub struct Stats {
+                pub kills: Option<u32>,
+            }",
+        );
+        assert!(imported.warnings[0].contains("`Option<u32>`"));
+    }
+}
@@ -0,0 +1,73 @@
+/// This module contains autosaving of the opened project to recover it after a crash.
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+/// How often the opened project is written to the recovery file.
+pub const AUTOSAVE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Project left by a run of YClass that didn't shut down cleanly.
+pub struct Recovery {
+    pub data: String,
+    /// Path of the project that was opened, if it was saved before.
+    pub project: Option<PathBuf>,
+    pub saved_at: SystemTime,
+}
+
+fn dir() -> PathBuf {
+    dirs::config_dir()
+        .map(|dir| dir.join("yclass"))
+        .unwrap_or_else(|| ".".into())
+}
+
+fn recovery_path() -> PathBuf {
+    dir().join("recovery.yclass")
+}
+
+/// Exists while YClass is running, contains path of the opened project.
+fn marker_path() -> PathBuf {
+    dir().join("running")
+}
+
+/// Returns the autosaved project if the last run didn't shut down cleanly,
+/// then marks this run as running.
+pub fn start() -> Option<Recovery> {
+    let recovery = fs::read_to_string(marker_path()).ok().and_then(|project| {
+        let data = fs::read_to_string(recovery_path()).ok()?;
+        let saved_at = fs::metadata(recovery_path())
+            .and_then(|m| m.modified())
+            .unwrap_or_else(|_| SystemTime::now());
+
+        Some(Recovery {
+            data,
+            project: (!project.is_empty()).then(|| project.into()),
+            saved_at,
+        })
+    });
+
+    _ = fs::create_dir_all(dir()).and_then(|_| fs::write(marker_path(), ""));
+    recovery
+}
+
+/// Writes the project to the recovery file.
+pub fn autosave(data: &str, project: Option<&Path>) -> eyre::Result<()> {
+    fs::create_dir_all(dir())?;
+    fs::write(recovery_path(), data)?;
+
+    let project = project.and_then(Path::to_str).unwrap_or_default();
+    fs::write(marker_path(), project)?;
+    Ok(())
+}
+
+/// Forgets the autosaved project the user didn't want to restore.
+pub fn discard() {
+    _ = fs::remove_file(recovery_path());
+}
+
+/// Removes the recovery file, called when YClass shuts down cleanly.
+pub fn finish() {
+    _ = fs::remove_file(recovery_path());
+    _ = fs::remove_file(marker_path());
+}
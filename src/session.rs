@@ -0,0 +1,119 @@
+/// This module contains the inspection session that is saved along with the project.
+use crate::{class::ClassId, field::FieldKind};
+use eframe::egui::{collapsing_header::CollapsingState, Context, Id};
+use serde::{Deserialize, Serialize};
+use std::{
+    cell::RefCell,
+    collections::BTreeMap,
+    hash::{Hash, Hasher},
+};
+
+/// Everything needed to continue the inspection where it was left off.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Session {
+    #[serde(default)]
+    pub selected_class: Option<ClassId>,
+    /// Base addresses of classes, in the syntax of the inspector's address box.
+    #[serde(default)]
+    pub addresses: BTreeMap<ClassId, String>,
+    /// Collapsible nodes of the inspector that aren't in their default state.
+    #[serde(default)]
+    pub nodes: BTreeMap<u64, bool>,
+    /// Process that was attached, attaching to it is suggested when the project is opened.
+    #[serde(default)]
+    pub process_name: Option<String>,
+    #[serde(default)]
+    pub spider: Option<SpiderSettings>,
+    #[serde(default)]
+    pub windows: OpenWindows,
+}
+
+/// Contents of the spider window's inputs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpiderSettings {
+    pub max_levels: String,
+    pub struct_size: String,
+    pub alignment: String,
+    pub field_kind: FieldKind,
+    pub base_address: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct OpenWindows {
+    #[serde(default)]
+    pub spider: bool,
+    #[serde(default)]
+    pub generator: bool,
+    #[serde(default)]
+    pub journal: bool,
+    #[serde(default)]
+    pub frozen: bool,
+}
+
+/// Tracks which collapsible nodes of the inspector are open so it can be saved.
+#[derive(Default)]
+pub struct NodeStates {
+    /// States from the opened project, applied when the node is shown for the first time.
+    restored: RefCell<BTreeMap<u64, bool>>,
+    /// Nodes shown since the project was opened that aren't in their default state.
+    changed: RefCell<BTreeMap<u64, bool>>,
+}
+
+impl NodeStates {
+    /// Loads the state of the node with `id`, use instead of [`CollapsingState::load_with_default_open`].
+    pub fn load(&self, ctx: &Context, id: Id, default_open: bool) -> CollapsingState {
+        let key = node_key(id);
+        let mut state = CollapsingState::load_with_default_open(ctx, id, default_open);
+        if let Some(open) = self.restored.borrow_mut().remove(&key) {
+            state.set_open(open);
+        }
+
+        if state.is_open() == default_open {
+            self.changed.borrow_mut().remove(&key);
+        } else {
+            self.changed.borrow_mut().insert(key, state.is_open());
+        }
+
+        state
+    }
+
+    pub fn restore(&self, nodes: BTreeMap<u64, bool>) {
+        *self.restored.borrow_mut() = nodes;
+        self.changed.borrow_mut().clear();
+    }
+
+    /// States of shown nodes together with restored states of nodes that weren't shown yet.
+    pub fn snapshot(&self) -> BTreeMap<u64, bool> {
+        let mut nodes = self.restored.borrow().clone();
+        nodes.extend(self.changed.borrow().iter().map(|(k, v)| (*k, *v)));
+        nodes
+    }
+}
+
+/// Value of the id, it only stays the same between runs because inspector's ids are
+/// derived from fixed seeds.
+fn node_key(id: Id) -> u64 {
+    #[derive(Default)]
+    struct KeyHasher(u64);
+
+    impl Hasher for KeyHasher {
+        fn finish(&self) -> u64 {
+            self.0
+        }
+
+        fn write(&mut self, bytes: &[u8]) {
+            for b in bytes {
+                self.0 = self.0.rotate_left(8) ^ *b as u64;
+            }
+        }
+
+        fn write_u64(&mut self, n: u64) {
+            self.0 = n;
+        }
+    }
+
+    let mut hasher = KeyHasher::default();
+    id.hash(&mut hasher);
+    hasher.finish()
+}
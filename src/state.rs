@@ -1,6 +1,15 @@
 use crate::{
-    class::ClassList, config::YClassConfig, context::Selection, hotkeys::HotkeyManager,
-    process::Process, project::ProjectData,
+    address::parse_address,
+    class::ClassList,
+    config::YClassConfig,
+    context::Selection,
+    freeze::DEFAULT_FREEZE_INTERVAL,
+    history::History,
+    hotkeys::HotkeyManager,
+    process::Process,
+    project::{Imported, ProjectData},
+    recovery::Recovery,
+    session::{NodeStates, OpenWindows, Session},
 };
 use egui_notify::Toasts;
 use parking_lot::RwLock;
@@ -9,7 +18,7 @@ use std::{
     collections::HashSet,
     fs,
     path::{Path, PathBuf},
-    sync::Arc,
+    sync::{atomic::AtomicU64, Arc},
 };
 
 pub type StateRef = &'static RefCell<GlobalState>;
@@ -18,9 +27,22 @@ pub struct GlobalState {
     pub last_opened_project: Option<PathBuf>,
     pub selection: Option<Selection>,
     pub process: Arc<RwLock<Option<Process>>>,
+    /// Milliseconds between rewrites of frozen values, shared with the freezer thread.
+    pub freeze_interval: Arc<AtomicU64>,
     pub hotkeys: HotkeyManager,
     pub class_list: ClassList,
+    /// Structural changes of `class_list` that can be undone.
+    pub history: History,
     pub config: YClassConfig,
+    /// Read-only mode of the opened project, see [`GlobalState::read_only`].
+    pub project_read_only: bool,
+    /// Projects whose classes are imported into the opened one, relative to its directory.
+    pub includes: Vec<String>,
+    /// Session of the opened project, the spider window stores its settings here.
+    pub session: Session,
+    /// Which of the tool bar's windows are open.
+    pub windows: OpenWindows,
+    pub nodes: NodeStates,
     pub toasts: Toasts,
     /// `true` means project was just created and contains no useful
     /// information
@@ -34,17 +56,78 @@ impl Default for GlobalState {
         Self {
             hotkeys: HotkeyManager::default(),
             class_list: ClassList::default(),
+            history: History::default(),
             last_opened_project: None,
             toasts: Toasts::default(),
             process: Arc::default(),
+            freeze_interval: Arc::new(AtomicU64::new(
+                config.freeze_interval.unwrap_or(DEFAULT_FREEZE_INTERVAL),
+            )),
             selection: None,
             dummy: true,
+            project_read_only: false,
+            includes: vec![],
+            session: Session::default(),
+            windows: OpenWindows::default(),
+            nodes: NodeStates::default(),
             config,
         }
     }
 }
 
 impl GlobalState {
+    /// Whether writing to memory is forbidden either globally or by the project.
+    pub fn read_only(&self) -> bool {
+        self.config.read_only.unwrap_or_default() || self.project_read_only
+    }
+
+    /// Collects the session to save with the project.
+    fn collect_session(&self) -> Session {
+        let mut session = self.session.clone();
+        session.selected_class = self.class_list.selected();
+        session.addresses = self
+            .class_list
+            .classes()
+            .iter()
+            .filter(|c| c.address.get() != 0)
+            .map(|c| (c.id(), format!("0x{:X}", c.address.get())))
+            .collect();
+        session.nodes = self.nodes.snapshot();
+        session.windows = self.windows;
+        if let Some(Ok(name)) = self.process.read().as_ref().map(|p| p.name()) {
+            session.process_name = Some(name);
+        }
+
+        session
+    }
+
+    /// Restores the session of the opened project, except for the tool bar's part.
+    pub fn restore_session(&mut self, session: Session) {
+        if let Some(id) = session.selected_class {
+            if self.class_list.by_id(id).is_some() {
+                *self.class_list.selected_mut() = Some(id);
+            }
+        }
+
+        for (id, expr) in &session.addresses {
+            if let (Some(class), Some(address)) = (self.class_list.by_id(*id), parse_address(expr))
+            {
+                class.address.set(address);
+            }
+        }
+
+        self.nodes.restore(session.nodes.clone());
+        self.windows = session.windows;
+        self.session = session;
+    }
+
+    /// Passes the read-only mode to the attached process which enforces it.
+    pub fn sync_read_only(&self) {
+        if let Some(process) = self.process.read().as_ref() {
+            process.set_read_only(self.read_only());
+        }
+    }
+
     pub fn save_project_as(&mut self) {
         if let Some(path) = rfd::FileDialog::new()
             .set_title("Save current project")
@@ -57,7 +140,7 @@ impl GlobalState {
 
     pub fn save_project(&mut self, path: Option<&Path>) {
         if let Some(path) = path {
-            let pd = ProjectData::store(self.class_list.classes()).to_string();
+            let pd = self.project_data();
             if let Err(e) = fs::write(path, pd.as_bytes()) {
                 self.toasts
                     .error(format!("Failed to save the project. {e}"));
@@ -66,7 +149,7 @@ impl GlobalState {
                 self.dummy = false;
             }
         } else if let Some(ref last) = self.last_opened_project {
-            let pd = ProjectData::store(self.class_list.classes()).to_string();
+            let pd = self.project_data();
             if let Err(e) = fs::write(last, pd.as_bytes()) {
                 self.toasts
                     .error(format!("Failed to save the project. {e}"));
@@ -83,6 +166,71 @@ impl GlobalState {
         }
     }
 
+    /// Project as it would be saved right now.
+    pub fn project_data(&self) -> String {
+        self.project().to_string()
+    }
+
+    fn project(&self) -> ProjectData {
+        ProjectData::store(self.class_list.classes(), self.project_read_only)
+            .with_includes(self.includes.clone())
+            .with_session(self.collect_session())
+    }
+
+    /// Adds imported classes to the project, warnings of the import are shown as a toast.
+    pub fn import(&mut self, imported: Imported) {
+        if imported.is_empty() {
+            self.toasts.error("Nothing to import was found");
+            return;
+        }
+
+        let count = imported.len();
+        let mut pd = self.project();
+        let warnings = pd.import(imported);
+        let path = self.last_opened_project.clone();
+
+        if let Err(e) = self.load_project_data(pd, path.as_deref()) {
+            self.toasts.error(format!("Failed to import. {e}"));
+            return;
+        }
+
+        self.toasts.info(format!("Imported {count} class(es)"));
+        self.show_warnings(&warnings);
+    }
+
+    /// Shows the first few of `warnings` as a toast.
+    fn show_warnings(&mut self, warnings: &[String]) {
+        if !warnings.is_empty() {
+            let mut text = warnings.iter().take(5).cloned().collect::<Vec<_>>();
+            if warnings.len() > 5 {
+                text.push(format!("...and {} more", warnings.len() - 5));
+            }
+            self.toasts.warning(text.join("\n"));
+        }
+    }
+
+    /// Replaces the included projects and reloads the classes, `includes` are relative to
+    /// the directory of the opened project.
+    pub fn set_includes(&mut self, includes: Vec<String>) {
+        let old = std::mem::replace(&mut self.includes, includes);
+        let data = self.project_data();
+        let path = self.last_opened_project.clone();
+
+        if let Err(e) = self.load_project(&data, path.as_deref()) {
+            self.includes = old;
+            self.toasts
+                .error(format!("Failed to load the included projects. {e}"));
+        }
+    }
+
+    /// Saves the config, failures are shown as toasts.
+    pub fn save_config(&mut self) {
+        if let Err(e) = self.config.save() {
+            self.toasts.error(format!("Failed to save the config. {e}"));
+        }
+    }
+
+    /// Returns `true` if a project was opened.
     pub fn open_project(&mut self) -> bool {
         if let Some(path) = rfd::FileDialog::new()
             .set_title("Open existing project")
@@ -91,7 +239,7 @@ impl GlobalState {
         {
             self.open_project_path(&path)
         } else {
-            true
+            false
         }
     }
 
@@ -100,26 +248,21 @@ impl GlobalState {
             self.save_project(None);
         }
 
-        match fs::read_to_string(path) {
-            Ok(data) => {
-                if let Some(pd) = ProjectData::from_str(&data) {
-                    self.class_list = pd.load();
-                    self.dummy = false;
-                    self.last_opened_project = Some(path.to_path_buf());
-
-                    if let Some(recent) = self.config.recent_projects.as_mut() {
-                        recent.insert(path.to_path_buf());
-                    } else {
-                        self.config.recent_projects =
-                            Some(HashSet::from_iter([path.to_path_buf()]));
-                    }
-                    self.config.save();
+        match fs::read_to_string(path)
+            .map_err(Into::into)
+            .and_then(|data| self.load_project(&data, Some(path)))
+        {
+            Ok(()) => {
+                self.last_opened_project = Some(path.to_path_buf());
 
-                    true
+                if let Some(recent) = self.config.recent_projects.as_mut() {
+                    recent.insert(path.to_path_buf());
                 } else {
-                    self.toasts.error("Project file is in invalid format");
-                    false
+                    self.config.recent_projects = Some(HashSet::from_iter([path.to_path_buf()]));
                 }
+                self.save_config();
+
+                true
             }
             Err(e) => {
                 self.toasts
@@ -128,4 +271,40 @@ impl GlobalState {
             }
         }
     }
+
+    /// Restores the project autosaved by a run that didn't shut down cleanly.
+    pub fn recover(&mut self, recovery: &Recovery) {
+        match self.load_project(&recovery.data, recovery.project.as_deref()) {
+            Ok(()) => self.last_opened_project = recovery.project.clone(),
+            Err(e) => {
+                _ = self
+                    .toasts
+                    .error(format!("Failed to recover the project. {e}"))
+            }
+        }
+    }
+
+    /// Replaces classes and the session with the ones of the project in `data`
+    /// which is saved at `path`.
+    fn load_project(&mut self, data: &str, path: Option<&Path>) -> eyre::Result<()> {
+        self.load_project_data(ProjectData::from_str(data)?, path)
+    }
+
+    fn load_project_data(&mut self, mut pd: ProjectData, path: Option<&Path>) -> eyre::Result<()> {
+        let (read_only, session) = (pd.read_only(), pd.take_session());
+        let includes = pd.includes().to_vec();
+
+        let (class_list, warnings) = pd.load(path.and_then(Path::parent))?;
+        self.class_list = class_list;
+        self.project_read_only = read_only;
+        self.includes = includes;
+        self.restore_session(session.unwrap_or_default());
+        self.sync_read_only();
+        self.history.clear();
+        self.selection = None;
+        self.dummy = false;
+        self.show_warnings(&warnings);
+
+        Ok(())
+    }
 }
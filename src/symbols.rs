@@ -0,0 +1,249 @@
+//! This module resolves addresses into modules and exported symbols.
+//! * On UNIX symbols are read from `.symtab` and `.dynsym` of the module's ELF file.
+//! * On Windows symbols are read from the export directory of the loaded module.
+
+use memflex::external::OwnedProcess;
+use once_cell::sync::OnceCell;
+use std::path::{Path, PathBuf};
+
+pub struct Module {
+    pub name: String,
+    pub base: usize,
+    pub size: usize,
+    path: PathBuf,
+    // Sorted by address, loaded on the first lookup.
+    symbols: OnceCell<Vec<Symbol>>,
+}
+
+struct Symbol {
+    address: usize,
+    size: usize,
+    name: String,
+}
+
+impl Module {
+    #[cfg(test)]
+    pub fn new(name: &str, base: usize, size: usize) -> Self {
+        Self {
+            name: name.to_owned(),
+            base,
+            size,
+            path: PathBuf::from(name),
+            symbols: OnceCell::new(),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn contains(&self, address: usize) -> bool {
+        self.base <= address && address < self.base + self.size
+    }
+
+    /// Returns the name of the symbol `address` falls in and offset from its start.
+    pub fn symbol_at(&self, process: &OwnedProcess, address: usize) -> Option<(&str, usize)> {
+        let symbols = self
+            .symbols
+            .get_or_init(|| load_symbols(self, process).unwrap_or_default());
+
+        let index = symbols
+            .partition_point(|s| s.address <= address)
+            .checked_sub(1)?;
+        let symbol = &symbols[index];
+
+        // Symbols without size(i.e. exports) cover everything up to the next one.
+        if symbol.size != 0 && address >= symbol.address + symbol.size {
+            return None;
+        }
+
+        Some((&symbol.name, address - symbol.address))
+    }
+}
+
+#[cfg(unix)]
+pub fn collect_modules(process: &OwnedProcess) -> Vec<Module> {
+    let Ok(maps) = std::fs::read_to_string(format!("/proc/{}/maps", process.id())) else {
+        return vec![];
+    };
+
+    let mut modules: Vec<Module> = vec![];
+    for line in maps.lines() {
+        let mut columns = line.splitn(6, ' ');
+        let (Some(range), Some(path)) = (columns.next(), columns.nth(4)) else {
+            continue;
+        };
+
+        let path = path.trim_start();
+        let Some((from, to)) = range.split_once('-').and_then(|(from, to)| {
+            Some((
+                usize::from_str_radix(from, 16).ok()?,
+                usize::from_str_radix(to, 16).ok()?,
+            ))
+        }) else {
+            continue;
+        };
+
+        if !path.starts_with('/') {
+            continue;
+        }
+
+        if let Some(module) = modules.iter_mut().find(|m| m.path.as_os_str() == path) {
+            let end = (module.base + module.size).max(to);
+            module.base = module.base.min(from);
+            module.size = end - module.base;
+        } else {
+            let path = PathBuf::from(path);
+            modules.push(Module {
+                name: path
+                    .file_name()
+                    .map(|n| n.to_string_lossy().into_owned())
+                    .unwrap_or_default(),
+                base: from,
+                size: to - from,
+                symbols: OnceCell::new(),
+                path,
+            });
+        }
+    }
+
+    modules
+}
+
+#[cfg(windows)]
+pub fn collect_modules(process: &OwnedProcess) -> Vec<Module> {
+    let Ok(modules) = process.modules() else {
+        return vec![];
+    };
+
+    modules
+        .map(|m| Module {
+            path: PathBuf::from(&m.name),
+            name: m.name,
+            base: m.base as usize,
+            size: m.size,
+            symbols: OnceCell::new(),
+        })
+        .collect()
+}
+
+#[cfg(unix)]
+fn load_symbols(module: &Module, _: &OwnedProcess) -> Option<Vec<Symbol>> {
+    const SHT_SYMTAB: u32 = 2;
+    const SHT_DYNSYM: u32 = 11;
+    const PT_LOAD: u32 = 1;
+    const STT_FUNC: u8 = 2;
+    const STT_OBJECT: u8 = 1;
+
+    let data = std::fs::read(&module.path).ok()?;
+    // Offsets come from the file, anything out of it makes the lookup fail instead of panicking.
+    let bytes = |o: usize, len: usize| data.get(o..o.checked_add(len)?);
+    let u16_at = |o: usize| Some(u16::from_le_bytes(bytes(o, 2)?.try_into().ok()?));
+    let u32_at = |o: usize| Some(u32::from_le_bytes(bytes(o, 4)?.try_into().ok()?));
+    let u64_at = |o: usize| Some(u64::from_le_bytes(bytes(o, 8)?.try_into().ok()?) as usize);
+    let entry = |table: usize, i: usize, size: usize| table.checked_add(i.checked_mul(size)?);
+
+    // Only 64-bit little endian ELF files are supported.
+    if data.get(..6)? != b"\x7fELF\x02\x01" {
+        return None;
+    }
+
+    // Shared objects are loaded at an arbitrary address, symbol values are relative to the lowest segment.
+    let (phoff, phentsize, phnum) = (u64_at(0x20)?, u16_at(0x36)? as usize, u16_at(0x38)?);
+    let min_vaddr = (0..phnum as usize)
+        .filter_map(|i| entry(phoff, i, phentsize))
+        .filter(|ph| u32_at(*ph) == Some(PT_LOAD))
+        .filter_map(|ph| u64_at(ph.checked_add(0x10)?))
+        .min()?;
+    let bias = module.base.wrapping_sub(min_vaddr & !0xFFF);
+
+    let (shoff, shentsize, shnum) = (u64_at(0x28)?, u16_at(0x3A)? as usize, u16_at(0x3C)?);
+    let mut symbols = vec![];
+
+    for i in 0..shnum as usize {
+        let section = entry(shoff, i, shentsize)?;
+        let field = |offset: usize| section.checked_add(offset);
+        let kind = u32_at(field(4)?)?;
+        if kind != SHT_SYMTAB && kind != SHT_DYNSYM {
+            continue;
+        }
+
+        let (offset, size, entsize) = (
+            u64_at(field(0x18)?)?,
+            u64_at(field(0x20)?)?,
+            u64_at(field(0x38)?)?,
+        );
+        let strtab = entry(shoff, u32_at(field(0x28)?)? as usize, shentsize)?;
+        let strtab = u64_at(strtab.checked_add(0x18)?)?;
+
+        for sym in (offset..offset.checked_add(size)?).step_by(entsize.max(1)) {
+            let info = *data.get(sym.checked_add(4)?)?;
+            let value = u64_at(sym.checked_add(8)?)?;
+            if value == 0 || (info & 0xF != STT_FUNC && info & 0xF != STT_OBJECT) {
+                continue;
+            }
+
+            let name = strtab.checked_add(u32_at(sym)? as usize)?;
+            let name = data.get(name..)?;
+            let end = name.iter().position(|c| *c == 0)?;
+
+            symbols.push(Symbol {
+                address: bias.wrapping_add(value),
+                size: u64_at(sym.checked_add(16)?)?,
+                name: String::from_utf8_lossy(&name[..end]).into_owned(),
+            });
+        }
+    }
+
+    symbols.sort_by_key(|s| s.address);
+    symbols.dedup_by_key(|s| s.address);
+    Some(symbols)
+}
+
+#[cfg(windows)]
+fn load_symbols(module: &Module, process: &OwnedProcess) -> Option<Vec<Symbol>> {
+    let u16_at = |o: usize| {
+        let mut buf = [0; 2];
+        process.read_buf(module.base + o, &mut buf).ok()?;
+        Some(u16::from_le_bytes(buf) as usize)
+    };
+    let u32_at = |o: usize| {
+        let mut buf = [0; 4];
+        process.read_buf(module.base + o, &mut buf).ok()?;
+        Some(u32::from_le_bytes(buf) as usize)
+    };
+
+    // PE32+ optional header, the first data directory is the export directory.
+    let nt = u32_at(0x3C)?;
+    let exports = u32_at(nt + 0x18 + 0x70)?;
+    if exports == 0 {
+        return None;
+    }
+
+    let (names_len, functions, names, ordinals) = (
+        u32_at(exports + 0x18)?,
+        u32_at(exports + 0x1C)?,
+        u32_at(exports + 0x20)?,
+        u32_at(exports + 0x24)?,
+    );
+
+    let mut symbols = vec![];
+    for i in 0..names_len {
+        let ordinal = u16_at(ordinals + i * 2)?;
+        let name = u32_at(names + i * 4)?;
+
+        let mut buf = [0; 256];
+        process.read_buf(module.base + name, &mut buf).ok()?;
+        let end = buf.iter().position(|c| *c == 0).unwrap_or(buf.len());
+
+        symbols.push(Symbol {
+            address: module.base + u32_at(functions + ordinal * 4)?,
+            size: 0,
+            name: String::from_utf8_lossy(&buf[..end]).into_owned(),
+        });
+    }
+
+    symbols.sort_by_key(|s| s.address);
+    symbols.dedup_by_key(|s| s.address);
+    Some(symbols)
+}
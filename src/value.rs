@@ -11,8 +11,17 @@ pub enum Value {
     I32(i32),
     U64(u64),
     I64(i64),
+    I128(i128),
+    U128(u128),
     F32(f32),
     F64(f64),
+    /// Half precision float, stored widened.
+    F16(f32),
+    /// Brain float, stored widened.
+    BF16(f32),
+    /// Raw value and fractional bits.
+    Fixed32(i32, u8),
+    Fixed64(i64, u8),
 }
 
 impl Value {
@@ -26,8 +35,35 @@ impl Value {
             Value::I32(_) => FieldKind::I32,
             Value::U64(_) => FieldKind::U64,
             Value::I64(_) => FieldKind::I64,
+            Value::I128(_) => FieldKind::I128,
+            Value::U128(_) => FieldKind::U128,
             Value::F32(_) => FieldKind::F32,
             Value::F64(_) => FieldKind::F64,
+            Value::F16(_) => FieldKind::F16,
+            Value::BF16(_) => FieldKind::BF16,
+            Value::Fixed32(_, frac) => FieldKind::Fixed32(*frac),
+            Value::Fixed64(_, frac) => FieldKind::Fixed64(*frac),
+        }
+    }
+
+    pub fn to_ne_bytes(self) -> Vec<u8> {
+        match self {
+            Value::U8(v) => v.to_ne_bytes().to_vec(),
+            Value::I8(v) => v.to_ne_bytes().to_vec(),
+            Value::U16(v) => v.to_ne_bytes().to_vec(),
+            Value::I16(v) => v.to_ne_bytes().to_vec(),
+            Value::U32(v) => v.to_ne_bytes().to_vec(),
+            Value::I32(v) => v.to_ne_bytes().to_vec(),
+            Value::U64(v) => v.to_ne_bytes().to_vec(),
+            Value::I64(v) => v.to_ne_bytes().to_vec(),
+            Value::I128(v) => v.to_ne_bytes().to_vec(),
+            Value::U128(v) => v.to_ne_bytes().to_vec(),
+            Value::F32(v) => v.to_ne_bytes().to_vec(),
+            Value::F64(v) => v.to_ne_bytes().to_vec(),
+            Value::F16(v) => f32_to_f16(v).to_ne_bytes().to_vec(),
+            Value::BF16(v) => f32_to_bf16(v).to_ne_bytes().to_vec(),
+            Value::Fixed32(v, _) => v.to_ne_bytes().to_vec(),
+            Value::Fixed64(v, _) => v.to_ne_bytes().to_vec(),
         }
     }
 }
@@ -43,8 +79,15 @@ impl PartialEq for Value {
             (Self::I32(l0), Self::I32(r0)) => l0 == r0,
             (Self::U64(l0), Self::U64(r0)) => l0 == r0,
             (Self::I64(l0), Self::I64(r0)) => l0 == r0,
+            (Self::I128(l0), Self::I128(r0)) => l0 == r0,
+            (Self::U128(l0), Self::U128(r0)) => l0 == r0,
             (Self::F32(l0), Self::F32(r0)) => (*l0 - *r0).abs() < f32::EPSILON,
             (Self::F64(l0), Self::F64(r0)) => (*l0 - *r0).abs() < f64::EPSILON,
+            // Both are widened from the same precision, so they're either exactly equal or not.
+            (Self::F16(l0), Self::F16(r0)) => l0 == r0,
+            (Self::BF16(l0), Self::BF16(r0)) => l0 == r0,
+            (Self::Fixed32(l0, _), Self::Fixed32(r0, _)) => l0 == r0,
+            (Self::Fixed64(l0, _), Self::Fixed64(r0, _)) => l0 == r0,
             _ => panic!("Comparing different value types"),
         }
     }
@@ -61,8 +104,14 @@ impl PartialOrd for Value {
             (Self::I32(l0), Self::I32(r0)) => l0.partial_cmp(r0),
             (Self::U64(l0), Self::U64(r0)) => l0.partial_cmp(r0),
             (Self::I64(l0), Self::I64(r0)) => l0.partial_cmp(r0),
+            (Self::I128(l0), Self::I128(r0)) => l0.partial_cmp(r0),
+            (Self::U128(l0), Self::U128(r0)) => l0.partial_cmp(r0),
             (Self::F32(l0), Self::F32(r0)) => l0.partial_cmp(r0),
             (Self::F64(l0), Self::F64(r0)) => l0.partial_cmp(r0),
+            (Self::F16(l0), Self::F16(r0)) => l0.partial_cmp(r0),
+            (Self::BF16(l0), Self::BF16(r0)) => l0.partial_cmp(r0),
+            (Self::Fixed32(l0, _), Self::Fixed32(r0, _)) => l0.partial_cmp(r0),
+            (Self::Fixed64(l0, _), Self::Fixed64(r0, _)) => l0.partial_cmp(r0),
             _ => panic!("Comparing different value types"),
         }
     }
@@ -79,8 +128,14 @@ impl Display for Value {
             Self::I32(v) => write!(f, "{v}"),
             Self::U64(v) => write!(f, "{v}"),
             Self::I64(v) => write!(f, "{v}"),
+            Self::I128(v) => write!(f, "{v}"),
+            Self::U128(v) => write!(f, "{v}"),
             Self::F32(v) => write!(f, "{v}"),
             Self::F64(v) => write!(f, "{v}"),
+            Self::F16(v) => write!(f, "{v}"),
+            Self::BF16(v) => write!(f, "{v}"),
+            Self::Fixed32(v, frac) => write!(f, "{}", fixed_to_f64(*v as i64, *frac)),
+            Self::Fixed64(v, frac) => write!(f, "{}", fixed_to_f64(*v, *frac)),
         }
     }
 }
@@ -98,5 +153,88 @@ macro_rules! from_impl {
 }
 
 from_impl!(
-    U8, u8, I8, i8, U16, u16, I16, i16, U32, u32, I32, i32, U64, u64, I64, i64, F32, f32, F64, f64
+    U8, u8, I8, i8, U16, u16, I16, i16, U32, u32, I32, i32, U64, u64, I64, i64, I128, i128, U128,
+    u128, F32, f32, F64, f64
 );
+
+pub fn f16_to_f32(bits: u16) -> f32 {
+    let sign = ((bits >> 15) as u32) << 31;
+    let exp = ((bits >> 10) & 0x1F) as u32;
+    let man = (bits & 0x3FF) as u32;
+
+    match exp {
+        0 => {
+            // Zero or subnormal, value is `man * 2^-24`.
+            let value = man as f32 * 2f32.powi(-24);
+            if sign != 0 {
+                -value
+            } else {
+                value
+            }
+        }
+        0x1F => f32::from_bits(sign | 0x7F80_0000 | (man << 13)),
+        _ => f32::from_bits(sign | ((exp + 127 - 15) << 23) | (man << 13)),
+    }
+}
+
+/// Converts to half precision, rounding to nearest even.
+pub fn f32_to_f16(value: f32) -> u16 {
+    let bits = value.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exp = ((bits >> 23) & 0xFF) as i32;
+    let man = bits & 0x7F_FFFF;
+
+    if exp == 0xFF {
+        return sign | 0x7C00 | if man != 0 { 0x200 } else { 0 };
+    }
+
+    let exp = exp - 127 + 15;
+    if exp >= 0x1F {
+        return sign | 0x7C00;
+    }
+
+    // Subnormals get their implicit bit back and lose more of the mantissa.
+    let (man, shift, exp) = if exp <= 0 {
+        if exp < -10 {
+            return sign;
+        }
+
+        (man | 0x80_0000, (14 - exp) as u32, 0)
+    } else {
+        (man, 13, (exp as u32) << 10)
+    };
+
+    let half = exp | (man >> shift);
+    let rem = man & ((1 << shift) - 1);
+    let halfway = 1 << (shift - 1);
+    let round = rem > halfway || (rem == halfway && half & 1 == 1);
+
+    // Carry from rounding correctly moves into exponent, up to infinity.
+    sign | (half + round as u32) as u16
+}
+
+pub fn bf16_to_f32(bits: u16) -> f32 {
+    f32::from_bits((bits as u32) << 16)
+}
+
+/// Converts to brain float, rounding to nearest even.
+pub fn f32_to_bf16(value: f32) -> u16 {
+    let bits = value.to_bits();
+    if value.is_nan() {
+        return (bits >> 16) as u16 | 0x40;
+    }
+
+    let round = ((bits >> 16) & 1) + 0x7FFF;
+    ((bits + round) >> 16) as u16
+}
+
+pub fn fixed_to_f64(raw: i64, frac: u8) -> f64 {
+    raw as f64 / 2f64.powi(frac as i32)
+}
+
+/// Returns `None` if the value doesn't fit into 64 bits.
+pub fn f64_to_fixed(value: f64, frac: u8) -> Option<i64> {
+    let scaled = (value * 2f64.powi(frac as i32)).round();
+    (scaled.is_finite() && scaled >= i64::MIN as f64 && scaled < i64::MAX as f64)
+        .then_some(scaled as i64)
+}